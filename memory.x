@@ -1,5 +1,7 @@
+/* The last 64K of the 4096K flash is reserved for the settings store
+ * (SETTINGS_OFFSET and SETTINGS_SIZE in src/config.rs). */
 MEMORY {
-    FLASH : ORIGIN = 0x10000000, LENGTH = 4096K
+    FLASH : ORIGIN = 0x10000000, LENGTH = 4096K - 64K
     RAM   : ORIGIN = 0x20000000, LENGTH = 512K
     SRAM8 : ORIGIN = 0x20080000, LENGTH = 4K
     SRAM9 : ORIGIN = 0x20081000, LENGTH = 4K
@@ -0,0 +1,725 @@
+/*
+ * @file arbiter.rs
+ * @brief Priority arbitration between competing LED requests
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: arbiter.rs
+//!
+//! DESCRIPTION:
+//! LED Request Arbiter for RP2350.
+//!
+//! BRIEF:
+//! Lets several parts of the firmware request LED indications at
+//! once. Each request carries a priority and an optional expiry;
+//! the highest-priority live request drives the LED and lower
+//! ones take over again when it ends.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::bank::Channel;
+use crate::led::{LedController, LedState};
+use crate::pattern::{EndAction, Pattern};
+
+/// Request priority levels.
+///
+/// # Details
+/// Ordered lowest to highest. Between equal priorities the most
+/// recently submitted request wins.
+///
+/// # Variants
+/// * `Background` - Idle indications such as a heartbeat
+/// * `Normal` - Ordinary status such as "busy"
+/// * `High` - Warnings that should override status
+/// * `Critical` - Error codes that override everything
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(dead_code)]
+pub enum Priority {
+    Background,
+    Normal,
+    High,
+    Critical,
+}
+
+/// Arbiter errors.
+///
+/// # Variants
+/// * `Full` - Every request slot is in use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ArbiterError {
+    Full,
+}
+
+/// Handle for a submitted request.
+///
+/// # Details
+/// Ids come from a 64-bit counter that increases with every
+/// submission. It cannot wrap in the lifetime of a device, so
+/// ids are never reused and a later request always compares
+/// greater, which `winner` relies on to break priority ties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(dead_code)]
+pub struct RequestId(u64);
+
+/// LED indication request.
+///
+/// # Fields
+/// * `priority` - Request priority
+/// * `pattern` - Pattern to play while this request wins
+/// * `ttl_ms` - Lifetime from submission, None to live until cancelled
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct Request {
+    pub priority: Priority,
+    pub pattern: Pattern,
+    pub ttl_ms: Option<u64>,
+}
+
+impl Request {
+    /// Creates a request that lives until cancelled.
+    ///
+    /// # Details
+    /// Patterns ending in `EndAction::Stop` also end the request
+    /// when they finish; `Hold` keeps the last state until the
+    /// request expires or is cancelled.
+    ///
+    /// # Arguments
+    /// * `priority` - Request priority
+    /// * `pattern` - Pattern to play
+    ///
+    /// # Returns
+    /// * `Self` - New Request instance
+    #[allow(dead_code)]
+    pub const fn new(priority: Priority, pattern: Pattern) -> Self {
+        Self {
+            priority,
+            pattern,
+            ttl_ms: None,
+        }
+    }
+
+    /// Sets the request lifetime.
+    ///
+    /// # Arguments
+    /// * `ttl_ms` - Lifetime from submission in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Request with the expiry applied
+    #[allow(dead_code)]
+    pub const fn with_ttl(mut self, ttl_ms: u64) -> Self {
+        self.ttl_ms = Some(ttl_ms);
+        self
+    }
+}
+
+/// Live request held by the arbiter.
+///
+/// # Fields
+/// * `id` - Request handle
+/// * `priority` - Request priority
+/// * `channel` - Pattern playback on the shared timebase
+/// * `expires_ms` - Absolute expiry time, if any
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Slot {
+    id: RequestId,
+    priority: Priority,
+    channel: Channel,
+    expires_ms: Option<u64>,
+}
+
+impl Slot {
+    /// Returns true once the request should be dropped.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time
+    ///
+    /// # Returns
+    /// * `bool` - true if expired or its Stop pattern finished
+    fn is_over(&self, now_ms: u64) -> bool {
+        let expired = self.expires_ms.is_some_and(|t| t <= now_ms);
+        let controller = self.channel.controller();
+        let stopped =
+            controller.is_finished() && controller.pattern().end_action() != EndAction::Hold;
+        expired || stopped
+    }
+}
+
+/// Priority arbiter in front of the LED.
+///
+/// # Details
+/// Holds up to N live requests. Every request keeps playing on
+/// the shared timebase even while outranked, so a preempted
+/// heartbeat resumes in phase when the higher request ends.
+///
+/// # Fields
+/// * `slots` - Live requests
+/// * `next_id` - Id for the next submission
+/// * `now_ms` - Time of the most recent advance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Arbiter<const N: usize> {
+    slots: [Option<Slot>; N],
+    next_id: u64,
+    now_ms: u64,
+}
+
+impl<const N: usize> Default for Arbiter<N> {
+    /// Returns default Arbiter instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New empty Arbiter
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Arbiter<N> {
+    /// Creates an empty arbiter.
+    ///
+    /// # Returns
+    /// * `Self` - New Arbiter instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            slots: [None; N],
+            next_id: 0,
+            now_ms: 0,
+        }
+    }
+
+    /// Submits a request at the current arbiter time.
+    ///
+    /// # Details
+    /// The request's pattern starts immediately, so if it wins
+    /// the LED changes without waiting for the next advance.
+    ///
+    /// # Arguments
+    /// * `request` - Request to add
+    ///
+    /// # Returns
+    /// * `Result<RequestId, ArbiterError>` - Handle or full error
+    #[allow(dead_code)]
+    pub fn submit(&mut self, request: Request) -> Result<RequestId, ArbiterError> {
+        let index = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(ArbiterError::Full)?;
+        Ok(self.place(index, request))
+    }
+
+    /// Replaces a request with a new one.
+    ///
+    /// # Details
+    /// Reuses the slot of `id` while it is live, so swapping a
+    /// request succeeds even when the arbiter is full and the old
+    /// request is never dropped without a successor. If `id` has
+    /// already ended this behaves like `submit`.
+    ///
+    /// # Arguments
+    /// * `id` - Handle of the request to replace
+    /// * `request` - Request to add
+    ///
+    /// # Returns
+    /// * `Result<RequestId, ArbiterError>` - New handle or full error
+    #[allow(dead_code)]
+    pub fn replace(&mut self, id: RequestId, request: Request) -> Result<RequestId, ArbiterError> {
+        let index = self
+            .slots
+            .iter()
+            .position(|s| s.is_some_and(|s| s.id == id))
+            .or_else(|| self.slots.iter().position(Option::is_none))
+            .ok_or(ArbiterError::Full)?;
+        Ok(self.place(index, request))
+    }
+
+    /// Starts a request in the given slot.
+    ///
+    /// # Arguments
+    /// * `index` - Slot to overwrite
+    /// * `request` - Request to start
+    ///
+    /// # Returns
+    /// * `RequestId` - Handle of the new request
+    fn place(&mut self, index: usize, request: Request) -> RequestId {
+        let now_ms = self.now_ms;
+        let id = RequestId(self.next_id);
+        self.next_id += 1;
+        let mut channel = Channel::new(LedController::with_pattern(request.pattern), 0, now_ms);
+        channel.advance(now_ms);
+        let slot = Slot {
+            id,
+            priority: request.priority,
+            channel,
+            expires_ms: request.ttl_ms.map(|ttl| now_ms.saturating_add(ttl)),
+        };
+        self.slots[index] = (!slot.is_over(now_ms)).then_some(slot);
+        id
+    }
+
+    /// Removes a request.
+    ///
+    /// # Arguments
+    /// * `id` - Handle returned by `submit`
+    ///
+    /// # Returns
+    /// * `bool` - true if the request was still live
+    #[allow(dead_code)]
+    pub fn cancel(&mut self, id: RequestId) -> bool {
+        match self
+            .slots
+            .iter_mut()
+            .find(|s| s.is_some_and(|s| s.id == id))
+        {
+            Some(slot) => {
+                *slot = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Advances every request to the given time.
+    ///
+    /// # Details
+    /// Plays due pattern transitions, then drops requests that
+    /// have expired or whose Stop pattern has finished. Time
+    /// never runs backwards; earlier values are ignored.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `LedState` - State the LED should show
+    #[allow(dead_code)]
+    pub fn advance(&mut self, now_ms: u64) -> LedState {
+        self.now_ms = self.now_ms.max(now_ms);
+        let now_ms = self.now_ms;
+        for entry in self.slots.iter_mut() {
+            if let Some(slot) = entry {
+                slot.channel.advance(now_ms);
+                if slot.is_over(now_ms) {
+                    *entry = None;
+                }
+            }
+        }
+        self.state()
+    }
+
+    /// Returns the LED state chosen by arbitration.
+    ///
+    /// # Returns
+    /// * `LedState` - Winning request's state, Off if none are live
+    #[allow(dead_code)]
+    pub fn state(&self) -> LedState {
+        self.winner()
+            .map_or(LedState::Off, |slot| slot.channel.state())
+    }
+
+    /// Returns the request currently driving the LED.
+    ///
+    /// # Returns
+    /// * `Option<(RequestId, Priority)>` - Winning request, None if idle
+    #[allow(dead_code)]
+    pub fn active(&self) -> Option<(RequestId, Priority)> {
+        self.winner().map(|slot| (slot.id, slot.priority))
+    }
+
+    /// Returns true if a request is still live.
+    ///
+    /// # Arguments
+    /// * `id` - Handle returned by `submit`
+    ///
+    /// # Returns
+    /// * `bool` - true if live
+    #[allow(dead_code)]
+    pub fn is_live(&self, id: RequestId) -> bool {
+        self.slots.iter().flatten().any(|slot| slot.id == id)
+    }
+
+    /// Returns the number of live requests.
+    ///
+    /// # Returns
+    /// * `usize` - Live request count
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Returns true if no requests are live.
+    ///
+    /// # Returns
+    /// * `bool` - true when idle
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the earliest time anything can change.
+    ///
+    /// # Details
+    /// Considers pattern transitions and expiries of every live
+    /// request, including outranked ones.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Absolute time in milliseconds, None if nothing is pending
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.slots
+            .iter()
+            .flatten()
+            .flat_map(|slot| [slot.channel.next_ms(), slot.expires_ms])
+            .flatten()
+            .min()
+    }
+
+    /// Returns the time of the most recent advance.
+    ///
+    /// # Returns
+    /// * `u64` - Arbiter time in milliseconds
+    #[allow(dead_code)]
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Finds the highest-priority, most recent live request.
+    ///
+    /// # Returns
+    /// * `Option<&Slot>` - Winning slot, None if idle
+    fn winner(&self) -> Option<&Slot> {
+        self.slots
+            .iter()
+            .flatten()
+            .max_by_key(|slot| (slot.priority, slot.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Segment;
+
+    fn solid(state: LedState) -> Pattern {
+        Pattern::from_segments(&[Segment {
+            state,
+            duration_ms: 1,
+        }])
+        .unwrap()
+        .with_end_action(EndAction::Hold)
+    }
+
+    // ==================== Priority Tests ====================
+
+    #[test]
+    fn test_priority_ordering() {
+        assert!(Priority::Background < Priority::Normal);
+        assert!(Priority::Normal < Priority::High);
+        assert!(Priority::High < Priority::Critical);
+    }
+
+    // ==================== Request Tests ====================
+
+    #[test]
+    fn test_request_new_has_no_ttl() {
+        let r = Request::new(Priority::Normal, Pattern::heartbeat());
+        assert_eq!(r.ttl_ms, None);
+    }
+
+    #[test]
+    fn test_request_with_ttl() {
+        let r = Request::new(Priority::Normal, Pattern::heartbeat()).with_ttl(5000);
+        assert_eq!(r.ttl_ms, Some(5000));
+    }
+
+    // ==================== submit() Tests ====================
+
+    #[test]
+    fn test_new_is_idle_and_off() {
+        let arb: Arbiter<4> = Arbiter::new();
+        assert!(arb.is_empty());
+        assert_eq!(arb.state(), LedState::Off);
+        assert_eq!(arb.active(), None);
+        assert_eq!(arb.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_submit_starts_immediately() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        let id = arb
+            .submit(Request::new(Priority::Background, Pattern::heartbeat()))
+            .unwrap();
+        assert_eq!(arb.state(), LedState::On);
+        assert_eq!(arb.active(), Some((id, Priority::Background)));
+        assert_eq!(arb.next_deadline(), Some(100));
+    }
+
+    #[test]
+    fn test_submit_ids_are_unique() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        let a = arb
+            .submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+            .unwrap();
+        let b = arb
+            .submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+            .unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_submit_when_full() {
+        let mut arb: Arbiter<1> = Arbiter::new();
+        arb.submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+            .unwrap();
+        let result = arb.submit(Request::new(Priority::Critical, Pattern::sos()));
+        assert_eq!(result, Err(ArbiterError::Full));
+    }
+
+    #[test]
+    fn test_replace_when_full() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        let old = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        arb.submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        let new = arb
+            .replace(old, Request::new(Priority::High, solid(LedState::Off)))
+            .unwrap();
+        assert!(!arb.is_live(old));
+        assert_eq!(arb.active(), Some((new, Priority::High)));
+        assert_eq!(arb.len(), 2);
+    }
+
+    #[test]
+    fn test_replace_ended_request_submits() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        let old = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        arb.cancel(old);
+        let new = arb
+            .replace(old, Request::new(Priority::Normal, solid(LedState::Off)))
+            .unwrap();
+        assert!(arb.is_live(new));
+        assert_eq!(arb.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_ended_request_when_full() {
+        let mut arb: Arbiter<1> = Arbiter::new();
+        let old = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        arb.cancel(old);
+        let other = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        assert_eq!(
+            arb.replace(old, Request::new(Priority::Normal, solid(LedState::Off))),
+            Err(ArbiterError::Full)
+        );
+        assert!(arb.is_live(other));
+    }
+
+    #[test]
+    fn test_submit_empty_pattern_is_not_kept() {
+        let mut arb: Arbiter<1> = Arbiter::new();
+        let id = arb
+            .submit(Request::new(Priority::Normal, Pattern::new()))
+            .unwrap();
+        assert!(!arb.is_live(id));
+        assert!(arb.is_empty());
+    }
+
+    // ==================== Arbitration Tests ====================
+
+    #[test]
+    fn test_highest_priority_wins() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        arb.submit(Request::new(Priority::Critical, solid(LedState::On)))
+            .unwrap();
+        arb.submit(Request::new(Priority::Background, solid(LedState::Off)))
+            .unwrap();
+        assert_eq!(arb.state(), LedState::On);
+        assert_eq!(arb.active().unwrap().1, Priority::Critical);
+    }
+
+    #[test]
+    fn test_equal_priority_most_recent_wins() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        arb.submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        let newer = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::Off)))
+            .unwrap();
+        assert_eq!(arb.active(), Some((newer, Priority::Normal)));
+        assert_eq!(arb.state(), LedState::Off);
+    }
+
+    #[test]
+    fn test_equal_priority_most_recent_wins_past_u32() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        arb.next_id = u64::from(u32::MAX);
+        arb.submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        let newer = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::Off)))
+            .unwrap();
+        assert_eq!(arb.active(), Some((newer, Priority::Normal)));
+        assert_eq!(arb.state(), LedState::Off);
+    }
+
+    #[test]
+    fn test_cancel_resumes_lower() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        let low = arb
+            .submit(Request::new(Priority::Background, solid(LedState::On)))
+            .unwrap();
+        let high = arb
+            .submit(Request::new(Priority::High, solid(LedState::Off)))
+            .unwrap();
+        assert_eq!(arb.state(), LedState::Off);
+        assert!(arb.cancel(high));
+        assert_eq!(arb.state(), LedState::On);
+        assert_eq!(arb.active().unwrap().0, low);
+    }
+
+    #[test]
+    fn test_cancel_unknown_id() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        let id = arb
+            .submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+            .unwrap();
+        assert!(arb.cancel(id));
+        assert!(!arb.cancel(id));
+    }
+
+    #[test]
+    fn test_expiry_resumes_lower() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        arb.submit(Request::new(Priority::Background, solid(LedState::On)))
+            .unwrap();
+        let busy = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::Off)).with_ttl(300))
+            .unwrap();
+        assert_eq!(arb.advance(299), LedState::Off);
+        assert_eq!(arb.next_deadline(), Some(300));
+        assert_eq!(arb.advance(300), LedState::On);
+        assert!(!arb.is_live(busy));
+    }
+
+    #[test]
+    fn test_ttl_counts_from_submission() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        arb.advance(1000);
+        let id = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)).with_ttl(50))
+            .unwrap();
+        arb.advance(1049);
+        assert!(arb.is_live(id));
+        arb.advance(1050);
+        assert!(!arb.is_live(id));
+    }
+
+    #[test]
+    fn test_stop_pattern_ends_request() {
+        let mut arb: Arbiter<4> = Arbiter::new();
+        arb.submit(Request::new(Priority::Background, solid(LedState::On)))
+            .unwrap();
+        let blink = Pattern::on_off(10, 10).with_end_action(EndAction::Stop);
+        let id = arb.submit(Request::new(Priority::Critical, blink)).unwrap();
+        assert_eq!(arb.advance(10), LedState::Off);
+        assert!(arb.is_live(id));
+        assert_eq!(arb.advance(20), LedState::On);
+        assert!(!arb.is_live(id));
+    }
+
+    #[test]
+    fn test_hold_pattern_stays_until_cancelled() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        let id = arb
+            .submit(Request::new(Priority::Normal, solid(LedState::On)))
+            .unwrap();
+        arb.advance(1_000_000);
+        assert!(arb.is_live(id));
+        assert_eq!(arb.state(), LedState::On);
+    }
+
+    #[test]
+    fn test_preempted_request_keeps_phase() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        arb.submit(Request::new(
+            Priority::Background,
+            Pattern::on_off(100, 100),
+        ))
+        .unwrap();
+        arb.submit(Request::new(Priority::Critical, solid(LedState::Off)).with_ttl(250))
+            .unwrap();
+        arb.advance(100);
+        arb.advance(200);
+        // Heartbeat would be On from 200 to 300 had it been visible.
+        assert_eq!(arb.advance(250), LedState::On);
+        assert_eq!(arb.advance(300), LedState::Off);
+    }
+
+    #[test]
+    fn test_next_deadline_includes_outranked() {
+        let mut arb: Arbiter<2> = Arbiter::new();
+        arb.submit(Request::new(Priority::Background, Pattern::on_off(30, 30)))
+            .unwrap();
+        arb.submit(Request::new(Priority::Critical, solid(LedState::On)))
+            .unwrap();
+        arb.advance(5);
+        assert_eq!(arb.next_deadline(), Some(30));
+    }
+
+    #[test]
+    fn test_slot_reused_after_expiry() {
+        let mut arb: Arbiter<1> = Arbiter::new();
+        arb.submit(Request::new(Priority::Normal, solid(LedState::On)).with_ttl(10))
+            .unwrap();
+        arb.advance(10);
+        assert!(
+            arb.submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_advance_ignores_backwards_time() {
+        let mut arb: Arbiter<1> = Arbiter::new();
+        arb.advance(500);
+        arb.advance(10);
+        assert_eq!(arb.now_ms(), 500);
+    }
+}
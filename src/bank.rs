@@ -0,0 +1,571 @@
+/*
+ * @file bank.rs
+ * @brief Multi-channel LED bank with per-channel patterns
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: bank.rs
+//!
+//! DESCRIPTION:
+//! Multi-Channel LED Bank for RP2350.
+//!
+//! BRIEF:
+//! Owns N LED channels, each with its own controller, pattern and
+//! phase offset, and advances all of them against a shared
+//! millisecond timebase.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::{LedController, LedState};
+use crate::pattern::Pattern;
+
+/// LED bank errors.
+///
+/// # Variants
+/// * `InvalidChannel` - Channel index is outside the bank
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum BankError {
+    InvalidChannel,
+}
+
+/// Single LED channel within a bank.
+///
+/// # Details
+/// The channel holds Off until its phase offset has elapsed,
+/// then plays its pattern like a standalone controller.
+///
+/// # Fields
+/// * `controller` - Pattern playback state
+/// * `offset_ms` - Delay before the pattern starts
+/// * `next_ms` - Absolute time of the next transition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Channel {
+    controller: LedController,
+    offset_ms: u64,
+    next_ms: u64,
+}
+
+impl Channel {
+    /// Creates a channel starting at the given time.
+    ///
+    /// # Arguments
+    /// * `controller` - Controller to play
+    /// * `offset_ms` - Phase offset in milliseconds
+    /// * `start_ms` - Bank time the offset counts from
+    ///
+    /// # Returns
+    /// * `Self` - New Channel instance
+    pub(crate) fn new(controller: LedController, offset_ms: u64, start_ms: u64) -> Self {
+        Self {
+            controller,
+            offset_ms,
+            next_ms: start_ms.saturating_add(offset_ms),
+        }
+    }
+
+    /// Applies every transition due at or before `now_ms`.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current bank time
+    ///
+    /// # Returns
+    /// * `bool` - true if the LED state changed
+    pub(crate) fn advance(&mut self, now_ms: u64) -> bool {
+        let before = self.controller.state();
+        while !self.controller.is_finished() && self.next_ms <= now_ms {
+            self.controller.toggle();
+            if !self.controller.is_finished() {
+                self.next_ms = self.next_ms.saturating_add(self.controller.delay_ms());
+            }
+        }
+        self.controller.state() != before
+    }
+
+    /// Returns the current LED state.
+    ///
+    /// # Returns
+    /// * `LedState` - Current state
+    #[allow(dead_code)]
+    pub fn state(&self) -> LedState {
+        self.controller.state()
+    }
+
+    /// Returns the phase offset.
+    ///
+    /// # Returns
+    /// * `u64` - Offset in milliseconds
+    #[allow(dead_code)]
+    pub fn offset_ms(&self) -> u64 {
+        self.offset_ms
+    }
+
+    /// Returns the next transition time.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Absolute time in milliseconds, None once finished
+    #[allow(dead_code)]
+    pub fn next_ms(&self) -> Option<u64> {
+        if self.controller.is_finished() {
+            None
+        } else {
+            Some(self.next_ms)
+        }
+    }
+
+    /// Returns the channel's controller.
+    ///
+    /// # Returns
+    /// * `&LedController` - Pattern playback state
+    #[allow(dead_code)]
+    pub fn controller(&self) -> &LedController {
+        &self.controller
+    }
+}
+
+/// Bank of N independently patterned LEDs.
+///
+/// # Details
+/// Time is supplied by the caller through `advance`, so the same
+/// bank runs under the Embassy timer and on a host virtual clock.
+///
+/// # Fields
+/// * `channels` - Per-LED channels
+/// * `now_ms` - Time of the most recent advance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct LedBank<const N: usize> {
+    channels: [Channel; N],
+    now_ms: u64,
+}
+
+impl<const N: usize> Default for LedBank<N> {
+    /// Returns default LedBank instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New LedBank with default values
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LedBank<N> {
+    /// Creates a bank of default blinking channels.
+    ///
+    /// # Details
+    /// Every channel starts at time zero with no phase offset.
+    ///
+    /// # Returns
+    /// * `Self` - New LedBank instance
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::from_controllers([LedController::new(); N])
+    }
+
+    /// Creates a bank from prepared controllers.
+    ///
+    /// # Arguments
+    /// * `controllers` - One controller per channel
+    ///
+    /// # Returns
+    /// * `Self` - New LedBank instance
+    #[allow(dead_code)]
+    pub fn from_controllers(controllers: [LedController; N]) -> Self {
+        Self {
+            channels: controllers.map(|c| Channel::new(c, 0, 0)),
+            now_ms: 0,
+        }
+    }
+
+    /// Creates a bank playing one pattern with staggered starts.
+    ///
+    /// # Details
+    /// Channel `i` starts `i * step_ms` after channel 0, which
+    /// turns a single pattern into a chase across the bank.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern for every channel
+    /// * `step_ms` - Phase offset between neighbouring channels
+    ///
+    /// # Returns
+    /// * `Self` - New LedBank instance
+    #[allow(dead_code)]
+    pub fn staggered(pattern: Pattern, step_ms: u64) -> Self {
+        let mut bank = Self::from_controllers([LedController::with_pattern(pattern); N]);
+        for (i, channel) in bank.channels.iter_mut().enumerate() {
+            *channel = Channel::new(channel.controller, step_ms.saturating_mul(i as u64), 0);
+        }
+        bank
+    }
+
+    /// Returns the number of channels.
+    ///
+    /// # Returns
+    /// * `usize` - Channel count
+    #[allow(dead_code)]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the bank has no channels.
+    ///
+    /// # Returns
+    /// * `bool` - true when N is zero
+    #[allow(dead_code)]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns a channel.
+    ///
+    /// # Arguments
+    /// * `index` - Channel index
+    ///
+    /// # Returns
+    /// * `Option<&Channel>` - Channel, or None if out of range
+    #[allow(dead_code)]
+    pub fn channel(&self, index: usize) -> Option<&Channel> {
+        self.channels.get(index)
+    }
+
+    /// Replaces a channel's pattern.
+    ///
+    /// # Details
+    /// The channel goes Off and restarts its pattern once its
+    /// phase offset has elapsed from the current bank time.
+    ///
+    /// # Arguments
+    /// * `index` - Channel index
+    /// * `pattern` - New pattern
+    ///
+    /// # Returns
+    /// * `Result<(), BankError>` - Ok or invalid channel
+    #[allow(dead_code)]
+    pub fn set_pattern(&mut self, index: usize, pattern: Pattern) -> Result<(), BankError> {
+        let now_ms = self.now_ms;
+        let channel = self.channel_mut(index)?;
+        *channel = Channel::new(
+            LedController::with_pattern(pattern),
+            channel.offset_ms,
+            now_ms,
+        );
+        Ok(())
+    }
+
+    /// Changes a channel's phase offset.
+    ///
+    /// # Details
+    /// Restarts the channel's pattern from the current bank time
+    /// so the new offset takes effect immediately.
+    ///
+    /// # Arguments
+    /// * `index` - Channel index
+    /// * `offset_ms` - New phase offset in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<(), BankError>` - Ok or invalid channel
+    #[allow(dead_code)]
+    pub fn set_offset(&mut self, index: usize, offset_ms: u64) -> Result<(), BankError> {
+        let now_ms = self.now_ms;
+        let channel = self.channel_mut(index)?;
+        let pattern = *channel.controller.pattern();
+        *channel = Channel::new(LedController::with_pattern(pattern), offset_ms, now_ms);
+        Ok(())
+    }
+
+    /// Advances every channel to the given time.
+    ///
+    /// # Details
+    /// Applies all transitions due at or before `now_ms`, catching
+    /// up through several segments if the caller was late. Time
+    /// never runs backwards; earlier values are ignored.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `usize` - Number of channels whose state changed
+    #[allow(dead_code)]
+    pub fn advance(&mut self, now_ms: u64) -> usize {
+        self.now_ms = self.now_ms.max(now_ms);
+        let now_ms = self.now_ms;
+        self.channels
+            .iter_mut()
+            .map(|c| c.advance(now_ms))
+            .filter(|&changed| changed)
+            .count()
+    }
+
+    /// Returns the earliest pending transition.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Absolute time in milliseconds, None when all channels finished
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.channels.iter().filter_map(Channel::next_ms).min()
+    }
+
+    /// Returns the time of the most recent advance.
+    ///
+    /// # Returns
+    /// * `u64` - Bank time in milliseconds
+    #[allow(dead_code)]
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Returns the state of every channel.
+    ///
+    /// # Returns
+    /// * `[LedState; N]` - Current states in channel order
+    #[allow(dead_code)]
+    pub fn states(&self) -> [LedState; N] {
+        self.channels.map(|c| c.state())
+    }
+
+    /// Returns true once every channel has finished.
+    ///
+    /// # Returns
+    /// * `bool` - true when no transitions remain
+    #[allow(dead_code)]
+    pub fn is_finished(&self) -> bool {
+        self.next_deadline().is_none()
+    }
+
+    /// Looks up a channel for modification.
+    ///
+    /// # Arguments
+    /// * `index` - Channel index
+    ///
+    /// # Returns
+    /// * `Result<&mut Channel, BankError>` - Channel or invalid channel
+    fn channel_mut(&mut self, index: usize) -> Result<&mut Channel, BankError> {
+        self.channels
+            .get_mut(index)
+            .ok_or(BankError::InvalidChannel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::{EndAction, Segment};
+
+    fn stop_pattern(on_ms: u64, off_ms: u64) -> Pattern {
+        Pattern::on_off(on_ms, off_ms).with_end_action(EndAction::Stop)
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_new_all_off() {
+        let bank: LedBank<4> = LedBank::new();
+        assert_eq!(bank.states(), [LedState::Off; 4]);
+        assert_eq!(bank.len(), 4);
+        assert!(!bank.is_empty());
+    }
+
+    #[test]
+    fn test_new_all_due_at_zero() {
+        let bank: LedBank<3> = LedBank::new();
+        assert_eq!(bank.next_deadline(), Some(0));
+    }
+
+    #[test]
+    fn test_default_matches_new() {
+        assert_eq!(LedBank::<2>::default(), LedBank::<2>::new());
+    }
+
+    #[test]
+    fn test_empty_bank() {
+        let mut bank: LedBank<0> = LedBank::new();
+        assert!(bank.is_empty());
+        assert_eq!(bank.advance(100), 0);
+        assert!(bank.is_finished());
+    }
+
+    #[test]
+    fn test_staggered_offsets() {
+        let bank: LedBank<4> = LedBank::staggered(Pattern::heartbeat(), 250);
+        let offsets: Vec<u64> = (0..4)
+            .map(|i| bank.channel(i).unwrap().offset_ms())
+            .collect();
+        assert_eq!(offsets, vec![0, 250, 500, 750]);
+    }
+
+    #[test]
+    fn test_channel_out_of_range() {
+        let bank: LedBank<2> = LedBank::new();
+        assert!(bank.channel(2).is_none());
+    }
+
+    // ==================== advance() Tests ====================
+
+    #[test]
+    fn test_advance_starts_channels() {
+        let mut bank: LedBank<2> = LedBank::new();
+        assert_eq!(bank.advance(0), 2);
+        assert_eq!(bank.states(), [LedState::On; 2]);
+        assert_eq!(bank.next_deadline(), Some(500));
+    }
+
+    #[test]
+    fn test_advance_respects_offset() {
+        let mut bank: LedBank<2> = LedBank::staggered(Pattern::on_off(100, 100), 50);
+        bank.advance(0);
+        assert_eq!(bank.states(), [LedState::On, LedState::Off]);
+        assert_eq!(bank.next_deadline(), Some(50));
+        bank.advance(50);
+        assert_eq!(bank.states(), [LedState::On, LedState::On]);
+        bank.advance(100);
+        assert_eq!(bank.states(), [LedState::Off, LedState::On]);
+        bank.advance(150);
+        assert_eq!(bank.states(), [LedState::Off, LedState::Off]);
+    }
+
+    #[test]
+    fn test_advance_counts_changes() {
+        let mut bank: LedBank<2> = LedBank::staggered(Pattern::on_off(100, 100), 50);
+        assert_eq!(bank.advance(0), 1);
+        assert_eq!(bank.advance(10), 0);
+        assert_eq!(bank.advance(50), 1);
+    }
+
+    #[test]
+    fn test_advance_catches_up_when_late() {
+        let mut bank: LedBank<1> =
+            LedBank::from_controllers([LedController::with_pattern(Pattern::on_off(10, 20))]);
+        bank.advance(65);
+        // 0 on, 10 off, 30 on, 40 off, 60 on, 70 off
+        assert_eq!(bank.states(), [LedState::On]);
+        assert_eq!(bank.next_deadline(), Some(70));
+    }
+
+    #[test]
+    fn test_advance_ignores_backwards_time() {
+        let mut bank: LedBank<1> = LedBank::new();
+        bank.advance(600);
+        bank.advance(100);
+        assert_eq!(bank.now_ms(), 600);
+        assert_eq!(bank.states(), [LedState::Off]);
+    }
+
+    #[test]
+    fn test_channels_are_independent() {
+        let mut bank: LedBank<2> = LedBank::from_controllers([
+            LedController::with_pattern(Pattern::on_off(10, 10)),
+            LedController::with_pattern(Pattern::on_off(30, 30)),
+        ]);
+        bank.advance(0);
+        assert_eq!(bank.next_deadline(), Some(10));
+        bank.advance(10);
+        assert_eq!(bank.states(), [LedState::Off, LedState::On]);
+        bank.advance(30);
+        assert_eq!(bank.states(), [LedState::Off, LedState::Off]);
+    }
+
+    #[test]
+    fn test_finished_channels_drop_out() {
+        let mut bank: LedBank<2> = LedBank::from_controllers([
+            LedController::with_pattern(stop_pattern(10, 10)),
+            LedController::with_pattern(stop_pattern(50, 50)),
+        ]);
+        bank.advance(20);
+        assert!(bank.channel(0).unwrap().next_ms().is_none());
+        assert_eq!(bank.next_deadline(), Some(50));
+        bank.advance(100);
+        assert!(bank.is_finished());
+        assert_eq!(bank.states(), [LedState::Off; 2]);
+    }
+
+    // ==================== set_pattern() Tests ====================
+
+    #[test]
+    fn test_set_pattern_restarts_from_now() {
+        let mut bank: LedBank<2> = LedBank::new();
+        bank.advance(700);
+        bank.set_pattern(1, Pattern::heartbeat()).unwrap();
+        assert_eq!(bank.channel(1).unwrap().state(), LedState::Off);
+        assert_eq!(bank.channel(1).unwrap().next_ms(), Some(700));
+        bank.advance(700);
+        assert_eq!(bank.channel(1).unwrap().next_ms(), Some(800));
+    }
+
+    #[test]
+    fn test_set_pattern_keeps_offset() {
+        let mut bank: LedBank<2> = LedBank::staggered(Pattern::heartbeat(), 40);
+        bank.advance(100);
+        bank.set_pattern(1, Pattern::sos()).unwrap();
+        assert_eq!(bank.channel(1).unwrap().next_ms(), Some(140));
+        assert_eq!(
+            bank.channel(1).unwrap().controller().pattern(),
+            &Pattern::sos()
+        );
+    }
+
+    #[test]
+    fn test_set_pattern_invalid_channel() {
+        let mut bank: LedBank<2> = LedBank::new();
+        assert_eq!(
+            bank.set_pattern(2, Pattern::heartbeat()),
+            Err(BankError::InvalidChannel)
+        );
+    }
+
+    // ==================== set_offset() Tests ====================
+
+    #[test]
+    fn test_set_offset_delays_channel() {
+        let mut bank: LedBank<1> = LedBank::new();
+        bank.set_offset(0, 300).unwrap();
+        bank.advance(299);
+        assert_eq!(bank.states(), [LedState::Off]);
+        bank.advance(300);
+        assert_eq!(bank.states(), [LedState::On]);
+    }
+
+    #[test]
+    fn test_set_offset_keeps_pattern() {
+        let mut bank: LedBank<1> = LedBank::from_controllers([LedController::with_pattern(
+            Pattern::from_segments(&[Segment::on(5), Segment::off(5)]).unwrap(),
+        )]);
+        bank.set_offset(0, 1).unwrap();
+        assert_eq!(bank.channel(0).unwrap().controller().pattern().len(), 2);
+    }
+
+    #[test]
+    fn test_set_offset_invalid_channel() {
+        let mut bank: LedBank<1> = LedBank::new();
+        assert_eq!(bank.set_offset(1, 0), Err(BankError::InvalidChannel));
+    }
+}
@@ -0,0 +1,632 @@
+/*
+ * @file blink_sim.rs
+ * @brief Host-side LED timeline simulator
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: blink_sim.rs
+//!
+//! DESCRIPTION:
+//! DAY001 Host-Side Blink Simulator.
+//!
+//! BRIEF:
+//! Runs LedController and the blink runner against a simulated
+//! clock and prints an ASCII waveform, or shows a live terminal
+//! indicator in real time. Can export the traced timeline as a
+//! VCD file for GTKWave. Previews timing changes to config.rs
+//! without flashing a Pico 2. Requires the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use day001_blink_led::blink_code::{self, BlinkCodeTiming};
+use day001_blink_led::codec::{self, MAX_ENCODED_LEN};
+use day001_blink_led::config::{BLINK_DELAY_MS, BlinkConfig, BlinkConfigBuilder};
+use day001_blink_led::dsl;
+use day001_blink_led::led::LedController;
+use day001_blink_led::mock::{VirtualClock, block_on};
+use day001_blink_led::morse::{self, MorseConfig};
+use day001_blink_led::pattern::Pattern;
+use day001_blink_led::runner::BlinkRunner;
+use day001_blink_led::trace::TracedController;
+use day001_blink_led::{vcd, waveform};
+use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal_async::delay::DelayNs;
+use std::convert::Infallible;
+use std::fs;
+use std::io::Write;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Maximum number of transitions exported to a VCD file.
+const TRACE_CAPACITY: usize = 4096;
+
+/// Command line usage text.
+const USAGE: &str = "\
+usage: blink-sim [PATTERN] [OPTIONS]
+
+patterns:
+  blink       symmetric blink from config.rs (default)
+  heartbeat   two quick pulses per second
+  double      double blink every two seconds
+  sos         morse SOS
+  morse       text given with --text
+  binary      encoded pattern loaded with --load
+  code        numeric blink code given with --code
+  custom      pattern language given with --text or --file,
+              e.g. \"on 100ms, repeat 3 { on 50ms, off 50ms }, off 1s\"
+
+options:
+  --on MS          ON time for blink
+  --off MS         OFF time for blink
+  --period MS      blink period
+  --freq MHZ       blink frequency in millihertz
+  --duty PERMILLE  blink duty cycle in tenths of a percent
+  --text TEXT      message for morse, or source for custom
+  --file PATH      read the custom pattern source from a file
+  --wpm N          morse character speed (default 12)
+  --farnsworth N   morse overall speed with Farnsworth spacing
+  --code N         number from 0 to 99 for the code pattern
+  --duration MS    simulated time (default 4000)
+  --resolution MS  milliseconds per character (default 50)
+  --width N        characters per row (default 80)
+  --live           show a real-time terminal indicator
+  --vcd PATH       write the traced timeline as a VCD file
+  --save PATH      write the pattern in the binary pattern format
+  --load PATH      play a pattern saved in the binary pattern format
+  --help           show this message";
+
+/// Simulator options.
+///
+/// # Fields
+/// * `pattern` - Selected pattern name
+/// * `config` - Blink configuration builder for the `blink` pattern
+/// * `text` - Message for `morse`, or source for `custom`
+/// * `morse` - Morse speed configuration
+/// * `code` - Number for the `code` pattern
+/// * `duration_ms` - Simulated or live run time
+/// * `resolution_ms` - Waveform milliseconds per character
+/// * `width` - Waveform characters per row
+/// * `live` - Real-time indicator instead of waveform
+/// * `vcd` - Optional VCD output path
+/// * `save` - Optional binary pattern output path
+/// * `blob` - Encoded pattern for the `binary` pattern
+#[derive(Debug, PartialEq)]
+struct Options {
+    pattern: String,
+    config: BlinkConfigBuilder,
+    text: String,
+    morse: MorseConfig,
+    code: u16,
+    duration_ms: u64,
+    resolution_ms: u64,
+    width: usize,
+    live: bool,
+    vcd: Option<String>,
+    save: Option<String>,
+    blob: Vec<u8>,
+}
+
+impl Default for Options {
+    /// Returns the default options.
+    ///
+    /// # Returns
+    /// * `Self` - Default options
+    fn default() -> Self {
+        Self {
+            pattern: String::from("blink"),
+            config: BlinkConfig::builder(),
+            text: String::new(),
+            morse: MorseConfig::default(),
+            code: 0,
+            duration_ms: 4 * 2 * BLINK_DELAY_MS,
+            resolution_ms: 50,
+            width: 80,
+            live: false,
+            vcd: None,
+            save: None,
+            blob: Vec::new(),
+        }
+    }
+}
+
+/// Parses command line arguments.
+///
+/// # Arguments
+/// * `args` - Arguments without the program name
+///
+/// # Returns
+/// * `Result<Option<Options>, String>` - Options, None for help, or error message
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| -> Result<u64, String> {
+            let raw = iter.next().ok_or_else(|| format!("{name} needs a value"))?;
+            raw.parse()
+                .map_err(|_| format!("{name}: invalid number '{raw}'"))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--live" => options.live = true,
+            "--vcd" => {
+                let path = iter.next().ok_or("--vcd needs a value")?;
+                options.vcd = Some(path.clone());
+            }
+            "--save" => {
+                let path = iter.next().ok_or("--save needs a value")?;
+                options.save = Some(path.clone());
+            }
+            "--load" => {
+                let path = iter.next().ok_or("--load needs a value")?;
+                options.blob = fs::read(path).map_err(|e| format!("cannot read '{path}': {e}"))?;
+                options.pattern = String::from("binary");
+            }
+            "--text" => {
+                let text = iter.next().ok_or("--text needs a value")?;
+                options.text = text.clone();
+            }
+            "--file" => {
+                let path = iter.next().ok_or("--file needs a value")?;
+                options.text = std::fs::read_to_string(path)
+                    .map_err(|e| format!("cannot read '{path}': {e}"))?;
+            }
+            "--wpm" => {
+                let wpm = u16::try_from(value("--wpm")?).map_err(|_| "--wpm: too large")?;
+                options.morse.wpm = wpm;
+            }
+            "--farnsworth" => {
+                let wpm =
+                    u16::try_from(value("--farnsworth")?).map_err(|_| "--farnsworth: too large")?;
+                options.morse.farnsworth_wpm = Some(wpm);
+            }
+            "--code" => {
+                options.code = u16::try_from(value("--code")?).map_err(|_| "--code: too large")?;
+                options.pattern = String::from("code");
+            }
+            "--on" => options.config = options.config.on_ms(value("--on")?),
+            "--off" => options.config = options.config.off_ms(value("--off")?),
+            "--period" => options.config = options.config.period_ms(value("--period")?),
+            "--freq" => {
+                let mhz = u32::try_from(value("--freq")?).map_err(|_| "--freq: too large")?;
+                options.config = options.config.frequency_millihertz(mhz);
+            }
+            "--duty" => {
+                let duty = u16::try_from(value("--duty")?).map_err(|_| "--duty: too large")?;
+                options.config = options.config.duty_permille(duty);
+            }
+            "--duration" => options.duration_ms = value("--duration")?,
+            "--resolution" => options.resolution_ms = value("--resolution")?,
+            "--width" => options.width = value("--width")? as usize,
+            name if name.starts_with('-') => return Err(format!("unknown option '{name}'")),
+            name => options.pattern = String::from(name),
+        }
+    }
+    Ok(Some(options))
+}
+
+/// Builds the controller for the selected pattern.
+///
+/// # Arguments
+/// * `options` - Parsed options
+///
+/// # Returns
+/// * `Result<LedController, String>` - Controller or error message
+fn build_controller(options: &Options) -> Result<LedController, String> {
+    let pattern = match options.pattern.as_str() {
+        "blink" => {
+            let config = options
+                .config
+                .build()
+                .map_err(|e| format!("invalid blink configuration: {e:?}"))?;
+            return Ok(LedController::with_config(config));
+        }
+        "heartbeat" => Pattern::heartbeat(),
+        "double" => Pattern::double_blink(),
+        "sos" => Pattern::sos(),
+        "morse" => morse::encode(&options.text, options.morse)
+            .map_err(|e| format!("cannot encode morse text: {e:?}"))?,
+        "code" => blink_code::encode(options.code, BlinkCodeTiming::default())
+            .map_err(|e| format!("cannot encode blink code: {e:?}"))?,
+        "binary" => codec::decode(&options.blob)
+            .map_err(|e| format!("cannot decode pattern file: {e:?}"))?,
+        "custom" => dsl::parse(&options.text).map_err(|e| {
+            format!(
+                "pattern error at line {}, column {}: {:?}",
+                e.line, e.column, e.kind
+            )
+        })?,
+        name => return Err(format!("unknown pattern '{name}'")),
+    };
+    Ok(LedController::with_pattern(pattern))
+}
+
+/// Simulates the controller and returns the recorded timeline.
+///
+/// # Arguments
+/// * `controller` - Controller to run
+/// * `duration_ms` - Simulated time in milliseconds
+///
+/// # Returns
+/// * `Vec<(u64, bool)>` - `(timestamp_ms, level)` transitions
+fn simulate(controller: &mut LedController, duration_ms: u64) -> Vec<(u64, bool)> {
+    let clock = VirtualClock::new();
+    let mut pin = clock.pin();
+    let mut runner = BlinkRunner::new(&mut pin, clock.delay());
+    while clock.now_ms() < duration_ms && !controller.is_finished() {
+        let Ok(_) = block_on(runner.step(controller));
+    }
+    pin.transitions().to_vec()
+}
+
+/// Writes the traced timeline of a controller as a VCD file.
+///
+/// # Arguments
+/// * `controller` - Controller to trace from its initial state
+/// * `duration_ms` - Traced time in milliseconds
+/// * `path` - Output file path
+///
+/// # Returns
+/// * `Result<usize, String>` - Number of transitions written, or error message
+fn export_vcd(controller: LedController, duration_ms: u64, path: &str) -> Result<usize, String> {
+    let mut traced = TracedController::<TRACE_CAPACITY>::new(controller);
+    traced.run_until(duration_ms);
+    let trace = traced.trace();
+    if trace.dropped() > 0 {
+        eprintln!(
+            "warning: {} transitions beyond {TRACE_CAPACITY} not exported",
+            trace.dropped()
+        );
+    }
+    let mut out = String::new();
+    let _ = vcd::write_vcd(&mut out, "led", trace.events(), duration_ms);
+    fs::write(path, out).map_err(|e| format!("{path}: {e}"))?;
+    Ok(trace.events().len())
+}
+
+/// Writes a pattern in the binary pattern format.
+///
+/// # Arguments
+/// * `pattern` - Pattern to save
+/// * `path` - Output file path
+///
+/// # Returns
+/// * `Result<usize, String>` - Bytes written, or error message
+fn save_pattern(pattern: &Pattern, path: &str) -> Result<usize, String> {
+    let mut buf = [0u8; MAX_ENCODED_LEN];
+    let len = codec::encode(pattern, &mut buf).map_err(|e| format!("{e:?}"))?;
+    fs::write(path, &buf[..len]).map_err(|e| format!("{path}: {e}"))?;
+    Ok(len)
+}
+
+/// Output pin drawing a live indicator on the terminal.
+///
+/// # Fields
+/// * `start` - Time the run began
+struct TerminalPin {
+    start: Instant,
+}
+
+impl TerminalPin {
+    /// Redraws the indicator line.
+    ///
+    /// # Arguments
+    /// * `level` - New pin level
+    fn draw(&self, level: bool) {
+        let (lamp, label) = if level {
+            ("(@)", "ON ")
+        } else {
+            ("( )", "OFF")
+        };
+        let elapsed = self.start.elapsed().as_millis();
+        print!("\r  LED {lamp} {label}  {elapsed:>8} ms");
+        let _ = std::io::stdout().flush();
+    }
+}
+
+impl ErrorType for TerminalPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for TerminalPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.draw(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.draw(true);
+        Ok(())
+    }
+}
+
+/// Async delay that sleeps the current thread.
+struct StdDelay;
+
+impl DelayNs for StdDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        std::thread::sleep(Duration::from_nanos(u64::from(ns)));
+    }
+}
+
+/// Runs the controller in real time with a terminal indicator.
+///
+/// # Arguments
+/// * `controller` - Controller to run
+/// * `duration_ms` - Run time in milliseconds
+fn run_live(controller: &mut LedController, duration_ms: u64) {
+    let start = Instant::now();
+    let mut runner = BlinkRunner::new(TerminalPin { start }, StdDelay);
+    while (start.elapsed().as_millis() as u64) < duration_ms && !controller.is_finished() {
+        let Ok(_) = block_on(runner.step(controller));
+    }
+    println!();
+}
+
+/// Simulator entry point.
+///
+/// # Returns
+/// * `ExitCode` - Success, or failure on bad arguments
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut controller = match build_controller(&options) {
+        Ok(controller) => controller,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = &options.save {
+        match save_pattern(controller.pattern(), path) {
+            Ok(len) => eprintln!("wrote {len} byte pattern to {path}"),
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if let Some(path) = &options.vcd {
+        match export_vcd(controller, options.duration_ms, path) {
+            Ok(count) => eprintln!("wrote {count} transitions to {path}"),
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if options.live {
+        run_live(&mut controller, options.duration_ms);
+        return ExitCode::SUCCESS;
+    }
+    let transitions = simulate(&mut controller, options.duration_ms);
+    let mut out = String::new();
+    let _ = waveform::render(
+        &mut out,
+        &transitions,
+        options.duration_ms,
+        options.resolution_ms,
+        options.width,
+    );
+    print!("{out}");
+    for (t, level) in &transitions {
+        println!("{t:>8} ms  {}", if *level { "ON" } else { "OFF" });
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| String::from(*s)).collect()
+    }
+
+    // ==================== parse_args() Tests ====================
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse_args(&[]).unwrap().unwrap();
+        assert_eq!(options, Options::default());
+    }
+
+    #[test]
+    fn test_parse_pattern_and_options() {
+        let options = parse_args(&args(&["sos", "--duration", "9000", "--live"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.pattern, "sos");
+        assert_eq!(options.duration_ms, 9000);
+        assert!(options.live);
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert_eq!(parse_args(&args(&["--help"])), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_vcd_path() {
+        let options = parse_args(&args(&["--vcd", "out.vcd"])).unwrap().unwrap();
+        assert_eq!(options.vcd.as_deref(), Some("out.vcd"));
+    }
+
+    #[test]
+    fn test_parse_missing_value() {
+        assert!(parse_args(&args(&["--on"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_bad_number() {
+        assert!(parse_args(&args(&["--period", "fast"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_option() {
+        assert!(parse_args(&args(&["--bogus"])).is_err());
+    }
+
+    // ==================== build_controller() Tests ====================
+
+    #[test]
+    fn test_build_period_and_duty() {
+        let options = parse_args(&args(&["--period", "2000", "--duty", "25"]))
+            .unwrap()
+            .unwrap();
+        let ctrl = build_controller(&options).unwrap();
+        assert_eq!(ctrl, LedController::with_pattern(Pattern::on_off(50, 1950)));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_config() {
+        let options = parse_args(&args(&["--on", "1"])).unwrap().unwrap();
+        assert!(build_controller(&options).is_err());
+    }
+
+    #[test]
+    fn test_build_morse() {
+        let options = parse_args(&args(&["morse", "--text", "E", "--wpm", "20"]))
+            .unwrap()
+            .unwrap();
+        let ctrl = build_controller(&options).unwrap();
+        assert_eq!(ctrl, LedController::with_pattern(Pattern::on_off(60, 420)));
+    }
+
+    #[test]
+    fn test_build_morse_rejects_unsupported() {
+        let options = parse_args(&args(&["morse", "--text", "#"]))
+            .unwrap()
+            .unwrap();
+        assert!(build_controller(&options).is_err());
+    }
+
+    #[test]
+    fn test_build_code() {
+        let options = parse_args(&args(&["--code", "5"])).unwrap().unwrap();
+        assert_eq!(options.pattern, "code");
+        let ctrl = build_controller(&options).unwrap();
+        let expected = blink_code::encode(5, BlinkCodeTiming::default()).unwrap();
+        assert_eq!(ctrl, LedController::with_pattern(expected));
+    }
+
+    #[test]
+    fn test_build_code_rejects_out_of_range() {
+        let options = parse_args(&args(&["--code", "100"])).unwrap().unwrap();
+        assert!(build_controller(&options).is_err());
+    }
+
+    #[test]
+    fn test_build_custom() {
+        let options = parse_args(&args(&["custom", "--text", "on 50ms, off 1950ms"]))
+            .unwrap()
+            .unwrap();
+        let ctrl = build_controller(&options).unwrap();
+        assert_eq!(ctrl, LedController::with_pattern(Pattern::on_off(50, 1950)));
+    }
+
+    #[test]
+    fn test_build_custom_reports_position() {
+        let options = parse_args(&args(&["custom", "--text", "on 50ms,\noff 5min"]))
+            .unwrap()
+            .unwrap();
+        let err = build_controller(&options).unwrap_err();
+        assert!(err.contains("line 2, column 6"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_missing_file() {
+        assert!(parse_args(&args(&["custom", "--file", "/nonexistent/pattern.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_build_unknown_pattern() {
+        let options = parse_args(&args(&["disco"])).unwrap().unwrap();
+        assert!(build_controller(&options).is_err());
+    }
+
+    // ==================== simulate() Tests ====================
+
+    #[test]
+    fn test_simulate_default_blink() {
+        let mut ctrl = LedController::new();
+        let timeline = simulate(&mut ctrl, 2000);
+        assert_eq!(
+            timeline,
+            vec![(0, true), (500, false), (1000, true), (1500, false)]
+        );
+    }
+
+    // ==================== export_vcd() Tests ====================
+
+    #[test]
+    fn test_export_vcd_writes_file() {
+        let path = std::env::temp_dir().join("blink_sim_test_export.vcd");
+        let path = path.to_str().unwrap();
+        let count = export_vcd(LedController::new(), 2000, path).unwrap();
+        let text = fs::read_to_string(path).unwrap();
+        let _ = fs::remove_file(path);
+        assert_eq!(count, 4);
+        assert!(text.contains("#500\n0!\n"));
+        assert!(text.ends_with("#2000\n"));
+    }
+
+    // ==================== save_pattern() Tests ====================
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = std::env::temp_dir().join("blink_sim_test_pattern.bin");
+        let path = path.to_str().unwrap();
+        let len = save_pattern(&Pattern::sos(), path).unwrap();
+        let options = parse_args(&args(&["--load", path])).unwrap().unwrap();
+        let _ = fs::remove_file(path);
+        assert_eq!(options.blob.len(), len);
+        let ctrl = build_controller(&options).unwrap();
+        assert_eq!(ctrl.pattern(), &Pattern::sos());
+    }
+
+    #[test]
+    fn test_build_binary_rejects_corrupt() {
+        let options = Options {
+            pattern: String::from("binary"),
+            blob: vec![b'L', b'P', 1, 0, 1, 0, 0, 0, 0, 0, 0],
+            ..Options::default()
+        };
+        let err = build_controller(&options).unwrap_err();
+        assert!(err.contains("BadCrc"), "{err}");
+    }
+}
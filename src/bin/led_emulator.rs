@@ -0,0 +1,527 @@
+/*
+ * @file led_emulator.rs
+ * @brief Host device emulator serving the control protocol
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: led_emulator.rs
+//!
+//! DESCRIPTION:
+//! DAY001 Host Device Emulator.
+//!
+//! BRIEF:
+//! Stands in for the board when none is attached. Runs the
+//! firmware's console, LED command handler and settings store
+//! from emulator.rs behind a pseudo-terminal or a Unix socket,
+//! so ledctl, terminal programs and CI jobs talk to it exactly
+//! as they would to the board's USB serial port. Optionally
+//! keeps the settings flash in a file and traces the LED.
+//! Requires the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use day001_blink_led::emulator::Emulator;
+use day001_blink_led::led::LedState;
+use day001_blink_led::pty::open_pty;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Longest wait between LED updates and reconnect checks.
+const POLL_MS: u64 = 50;
+
+/// Command line usage text.
+const USAGE: &str = "\
+usage: led-emulator [OPTIONS]
+
+Emulates the board's USB serial port. Without --socket, opens a
+pseudo-terminal and prints its path; point ledctl --port or a
+terminal program at it.
+
+options:
+  --link PATH      also make PATH a symlink to the pseudo-terminal
+  --socket PATH    listen on a Unix socket instead
+  --flash PATH     load and keep the settings flash in PATH
+  --trace          print every LED change
+  --help           show this message";
+
+/// Where the emulator listens.
+///
+/// # Variants
+/// * `Pty` - Pseudo-terminal, with an optional symlink to it
+/// * `Socket` - Unix socket at this path
+#[derive(Debug, PartialEq)]
+enum Endpoint {
+    Pty(Option<String>),
+    Socket(String),
+}
+
+/// Emulator options.
+///
+/// # Fields
+/// * `endpoint` - Where to listen
+/// * `flash` - Flash image file
+/// * `trace` - Print LED changes
+#[derive(Debug, PartialEq)]
+struct Options {
+    endpoint: Endpoint,
+    flash: Option<String>,
+    trace: bool,
+}
+
+/// Parses command line arguments.
+///
+/// # Arguments
+/// * `args` - Arguments without the program name
+///
+/// # Returns
+/// * `Result<Option<Options>, String>` - Options, None for help, or error message
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut link = None;
+    let mut socket = None;
+    let mut flash = None;
+    let mut trace = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut path = |name: &str| -> Result<String, String> {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--link" => link = Some(path("--link")?),
+            "--socket" => socket = Some(path("--socket")?),
+            "--flash" => flash = Some(path("--flash")?),
+            "--trace" => trace = true,
+            other => return Err(format!("unknown argument '{other}'")),
+        }
+    }
+    let endpoint = match (link, socket) {
+        (Some(_), Some(_)) => return Err(String::from("--link and --socket are exclusive")),
+        (link, None) => Endpoint::Pty(link),
+        (None, Some(socket)) => Endpoint::Socket(socket),
+    };
+    Ok(Some(Options {
+        endpoint,
+        flash,
+        trace,
+    }))
+}
+
+/// Emulated board on the wall clock.
+///
+/// # Fields
+/// * `emulator` - Board logic
+/// * `start` - Time the emulator started
+/// * `flash` - Flash image file
+/// * `saved` - Image as of the last load or write
+/// * `trace` - Output for LED changes
+/// * `led` - LED state last traced
+struct Device<W> {
+    emulator: Emulator,
+    start: Instant,
+    flash: Option<String>,
+    saved: Option<Vec<u8>>,
+    trace: Option<W>,
+    led: Option<LedState>,
+}
+
+impl<W: Write> Device<W> {
+    /// Boots the board.
+    ///
+    /// # Arguments
+    /// * `flash` - Flash image file, created on first save if missing
+    /// * `trace` - Output for LED changes
+    ///
+    /// # Returns
+    /// * `Result<Self, String>` - Device or error message
+    fn new(flash: Option<String>, trace: Option<W>) -> Result<Self, String> {
+        let image = match &flash {
+            Some(path) => match fs::read(path) {
+                Ok(image) => Some(image),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(format!("cannot read '{path}': {e}")),
+            },
+            None => None,
+        };
+        let emulator = match &image {
+            Some(image) => Emulator::from_image(image, 0),
+            None => Emulator::new(0),
+        };
+        let saved = emulator.image().map(<[u8]>::to_vec);
+        Ok(Self {
+            emulator,
+            start: Instant::now(),
+            flash,
+            saved,
+            trace,
+            led: None,
+        })
+    }
+
+    /// Returns milliseconds since start.
+    ///
+    /// # Returns
+    /// * `u64` - Emulator time
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    /// Feeds received bytes and saves the flash if it changed.
+    ///
+    /// # Arguments
+    /// * `bytes` - Bytes from the host
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, String>` - Bytes for the host, or error message
+    fn feed(&mut self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let out = self.emulator.feed(bytes, self.now());
+        if let (Some(path), Some(image)) = (&self.flash, self.emulator.image())
+            && self.saved.as_deref() != Some(image)
+        {
+            fs::write(path, image).map_err(|e| format!("cannot write '{path}': {e}"))?;
+            self.saved = Some(image.to_vec());
+        }
+        self.tick();
+        Ok(out)
+    }
+
+    /// Brings the LED up to date and traces a change.
+    ///
+    /// # Returns
+    /// * `Duration` - Time until the LED next needs updating
+    fn tick(&mut self) -> Duration {
+        let now = self.now();
+        let state = self.emulator.advance(now);
+        if self.led != Some(state) {
+            self.led = Some(state);
+            if let Some(trace) = &mut self.trace {
+                let name = match state {
+                    LedState::On => "on",
+                    LedState::Off => "off",
+                };
+                let _ = writeln!(trace, "{now:>8} ms  LED {name}");
+                let _ = trace.flush();
+            }
+        }
+        let wait = self
+            .emulator
+            .next_deadline()
+            .map_or(POLL_MS, |at| at.saturating_sub(now).clamp(1, POLL_MS));
+        Duration::from_millis(wait)
+    }
+}
+
+/// Serves one connection until the host closes it.
+///
+/// # Arguments
+/// * `device` - Emulated board
+/// * `stream` - Connection whose reads time out
+///
+/// # Returns
+/// * `Result<(), String>` - Ok when closed, or error message
+fn serve_stream<W: Write>(device: &mut Device<W>, stream: &mut UnixStream) -> Result<(), String> {
+    let mut buf = [0u8; 256];
+    device.emulator.disconnect();
+    loop {
+        let wait = device.tick();
+        stream
+            .set_read_timeout(Some(wait))
+            .map_err(|e| format!("socket error: {e}"))?;
+        let n = match stream.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) if e.kind() == io::ErrorKind::ConnectionReset => return Ok(()),
+            Err(e) => return Err(format!("read failed: {e}")),
+        };
+        let out = device.feed(&buf[..n])?;
+        if let Err(e) = stream.write_all(&out) {
+            return match e.kind() {
+                io::ErrorKind::BrokenPipe | io::ErrorKind::ConnectionReset => Ok(()),
+                _ => Err(format!("write failed: {e}")),
+            };
+        }
+    }
+}
+
+/// Serves connections on a Unix socket, one at a time.
+///
+/// # Arguments
+/// * `device` - Emulated board
+/// * `path` - Socket path, replaced if a stale socket is there
+///
+/// # Returns
+/// * `Result<(), String>` - Error message; runs until killed
+fn serve_socket<W: Write>(device: &mut Device<W>, path: &str) -> Result<(), String> {
+    if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        let _ = fs::remove_file(path);
+    }
+    let listener = UnixListener::bind(path).map_err(|e| format!("cannot bind '{path}': {e}"))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("socket error: {e}"))?;
+    println!("led-emulator: listening on {path}");
+    loop {
+        match listener.accept() {
+            Ok((mut stream, _)) => {
+                stream
+                    .set_nonblocking(false)
+                    .map_err(|e| format!("socket error: {e}"))?;
+                serve_stream(device, &mut stream)?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(device.tick());
+            }
+            Err(e) => return Err(format!("accept failed: {e}")),
+        }
+    }
+}
+
+/// Serves the host on a pseudo-terminal.
+///
+/// # Details
+/// The master reports a hang-up while no program has the
+/// terminal open; each new open starts a new console session,
+/// as reopening the board's port does.
+///
+/// # Arguments
+/// * `device` - Emulated board
+/// * `link` - Symlink to create to the terminal
+///
+/// # Returns
+/// * `Result<(), String>` - Error message; runs until killed
+fn serve_pty<W: Write>(device: &mut Device<W>, link: Option<&str>) -> Result<(), String> {
+    let (mut master, path) = open_pty().map_err(|e| format!("cannot open a pty: {e}"))?;
+    if let Some(link) = link {
+        if fs::symlink_metadata(link).is_ok_and(|m| m.file_type().is_symlink()) {
+            let _ = fs::remove_file(link);
+        }
+        std::os::unix::fs::symlink(&path, link)
+            .map_err(|e| format!("cannot link '{link}': {e}"))?;
+    }
+    println!("led-emulator: serving on {path}");
+    let _ = io::stdout().flush();
+    let mut buf = [0u8; 256];
+    let mut connected = false;
+    loop {
+        let wait = device.tick();
+        let mut fds = libc::pollfd {
+            fd: master.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fds` is a single valid pollfd for the call.
+        if unsafe { libc::poll(&mut fds, 1, wait.as_millis() as libc::c_int) } < 0 {
+            let e = io::Error::last_os_error();
+            if e.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(format!("poll failed: {e}"));
+        }
+        if fds.revents & libc::POLLHUP != 0 {
+            if connected {
+                device.emulator.disconnect();
+                connected = false;
+            }
+            std::thread::sleep(wait);
+            continue;
+        }
+        if fds.revents & libc::POLLIN == 0 {
+            continue;
+        }
+        connected = true;
+        let n = master
+            .read(&mut buf)
+            .map_err(|e| format!("read failed: {e}"))?;
+        let out = device.feed(&buf[..n])?;
+        master
+            .write_all(&out)
+            .map_err(|e| format!("write failed: {e}"))?;
+    }
+}
+
+/// Emulator entry point.
+///
+/// # Returns
+/// * `ExitCode` - Failure on bad arguments or I/O errors; otherwise runs until killed
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let trace = options.trace.then(io::stdout);
+    let result = Device::new(options.flash, trace).and_then(|mut device| match &options.endpoint {
+        Endpoint::Pty(link) => serve_pty(&mut device, link.as_deref()),
+        Endpoint::Socket(path) => serve_socket(&mut device, path),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day001_blink_led::protocol::{
+        FrameDecoder, MAX_BODY_LEN, MAX_FRAME_LEN, PROTOCOL_VERSION, Request, Response,
+        encode_frame,
+    };
+    use std::thread;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| String::from(*s)).collect()
+    }
+
+    fn request(stream: &mut UnixStream, seq: u8, request: &Request) -> Response {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, request, &mut frame).unwrap();
+        stream.write_all(&frame[..len]).unwrap();
+        let mut decoder: FrameDecoder<MAX_BODY_LEN> = FrameDecoder::new();
+        let mut byte = [0u8];
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            if let Some(Ok(packet)) = decoder.decode::<Response>(byte[0]) {
+                assert_eq!(packet.seq, seq);
+                return packet.message;
+            }
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        let dir = std::env::temp_dir();
+        format!(
+            "{}/led-emulator-{}-{name}",
+            dir.display(),
+            std::process::id()
+        )
+    }
+
+    // ==================== parse_args() Tests ====================
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse_args(&[]).unwrap().unwrap();
+        assert_eq!(options.endpoint, Endpoint::Pty(None));
+        assert_eq!(options.flash, None);
+        assert!(!options.trace);
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse_args(&args(&[
+            "--socket",
+            "/tmp/led.sock",
+            "--flash",
+            "flash.bin",
+            "--trace",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            options.endpoint,
+            Endpoint::Socket(String::from("/tmp/led.sock"))
+        );
+        assert_eq!(options.flash.as_deref(), Some("flash.bin"));
+        assert!(options.trace);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_args(&args(&["--help"])), Ok(None));
+        assert!(parse_args(&args(&["--flash"])).is_err());
+        assert!(parse_args(&args(&["--bogus"])).is_err());
+        assert!(parse_args(&args(&["--link", "a", "--socket", "b"])).is_err());
+    }
+
+    // ==================== Serve Tests ====================
+
+    #[test]
+    fn test_stream_speaks_protocol_and_shell() {
+        let (mut host, mut board) = UnixStream::pair().unwrap();
+        let server = thread::spawn(move || {
+            let mut device = Device::new(None, Some(Vec::new())).unwrap();
+            serve_stream(&mut device, &mut board).unwrap();
+            device
+        });
+        assert_eq!(
+            request(&mut host, 1, &Request::Ping),
+            Response::Pong(PROTOCOL_VERSION)
+        );
+        host.write_all(b"off\r").unwrap();
+        let mut echo = [0u8; 10];
+        host.read_exact(&mut echo).unwrap();
+        assert_eq!(&echo, b"off\r\r\nok\r\n");
+        assert_eq!(
+            request(&mut host, 2, &Request::GetState),
+            Response::State(LedState::Off)
+        );
+        drop(host);
+        let device = server.join().unwrap();
+        let trace = String::from_utf8(device.trace.unwrap()).unwrap();
+        assert!(trace.ends_with("LED off\n"));
+    }
+
+    #[test]
+    fn test_flash_file_keeps_saved_settings() {
+        let path = temp_path("flash");
+        let _ = fs::remove_file(&path);
+        let mut device: Device<Vec<u8>> = Device::new(Some(path.clone()), None).unwrap();
+        device.feed(b"rate 250\r").unwrap();
+        assert!(fs::metadata(&path).is_err());
+        device.feed(b"save\r").unwrap();
+        let device: Device<Vec<u8>> = Device::new(Some(path.clone()), None).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(device.emulator.settings().blink.on_ms(), 250);
+    }
+}
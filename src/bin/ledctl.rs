@@ -0,0 +1,977 @@
+/*
+ * @file ledctl.rs
+ * @brief Host control tool for the LED firmware
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: ledctl.rs
+//!
+//! DESCRIPTION:
+//! DAY001 Host Control Tool.
+//!
+//! BRIEF:
+//! Drives the LED firmware from a host over the board's serial
+//! port using the framed control protocol in protocol.rs. Sets
+//! the blink rate, uploads patterns, reads the LED state and the
+//! stored settings, and watches the LED for changes. Resends a
+//! request with the same sequence number when no reply arrives.
+//! Requires the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use day001_blink_led::codec;
+use day001_blink_led::config::BlinkConfig;
+use day001_blink_led::dsl;
+use day001_blink_led::led::{LedState, Polarity};
+use day001_blink_led::pattern::Pattern;
+use day001_blink_led::protocol::{
+    self, FrameDecoder, MAX_BODY_LEN, MAX_FRAME_LEN, Request, Response,
+};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Serial device used when `--port` is not given.
+const DEFAULT_PORT: &str = "/dev/ttyACM0";
+
+/// Command line usage text.
+const USAGE: &str = "\
+usage: ledctl [OPTIONS] COMMAND [ARGS]
+
+commands:
+  ping                     check the link and protocol version
+  set-rate ON_MS [OFF_MS]  blink ON_MS on, ON_MS or OFF_MS off
+  upload-pattern SOURCE    play a pattern in the pattern language,
+                           e.g. \"on 100ms, off 900ms\"
+  get-status               show the LED state and blink rate
+  dump-settings            show rate, pattern and polarity
+  watch                    print every LED state change
+
+options:
+  --port PATH      serial device (default /dev/ttyACM0)
+  --timeout MS     reply timeout per attempt (default 500)
+  --retries N      resends after a timeout (default 3)
+  --file PATH      upload-pattern: read the source from a file
+  --load PATH      upload-pattern: send a binary pattern file
+  --interval MS    watch: poll interval (default 50)
+  --count N        watch: stop after N changes
+  --help           show this message";
+
+/// Tool command.
+///
+/// # Variants
+/// * `Ping` - Check the link
+/// * `SetRate` - Blink with these delays
+/// * `UploadPattern` - Play this pattern
+/// * `GetStatus` - Show LED state and blink rate
+/// * `DumpSettings` - Show the full settings
+/// * `Watch` - Print LED state changes
+#[derive(Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+enum Command {
+    Ping,
+    SetRate(BlinkConfig),
+    UploadPattern(Pattern),
+    GetStatus,
+    DumpSettings,
+    Watch,
+}
+
+/// Tool options.
+///
+/// # Fields
+/// * `port` - Serial device path
+/// * `timeout_ms` - Reply timeout per attempt
+/// * `retries` - Resends after a timeout
+/// * `interval_ms` - Watch poll interval
+/// * `count` - Watch stops after this many changes
+/// * `command` - Command to run
+#[derive(Debug, PartialEq)]
+struct Options {
+    port: String,
+    timeout_ms: u64,
+    retries: u32,
+    interval_ms: u64,
+    count: Option<u64>,
+    command: Command,
+}
+
+/// Parses command line arguments.
+///
+/// # Arguments
+/// * `args` - Arguments without the program name
+///
+/// # Returns
+/// * `Result<Option<Options>, String>` - Options, None for help, or error message
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut port = String::from(DEFAULT_PORT);
+    let mut timeout_ms = 500;
+    let mut retries = 3;
+    let mut interval_ms = 50;
+    let mut count = None;
+    let mut source = None;
+    let mut blob = None;
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| -> Result<u64, String> {
+            let raw = iter.next().ok_or_else(|| format!("{name} needs a value"))?;
+            raw.parse()
+                .map_err(|_| format!("{name}: invalid number '{raw}'"))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--port" => {
+                let path = iter.next().ok_or("--port needs a value")?;
+                port = path.clone();
+            }
+            "--file" => {
+                let path = iter.next().ok_or("--file needs a value")?;
+                source = Some(
+                    fs::read_to_string(path).map_err(|e| format!("cannot read '{path}': {e}"))?,
+                );
+            }
+            "--load" => {
+                let path = iter.next().ok_or("--load needs a value")?;
+                blob = Some(fs::read(path).map_err(|e| format!("cannot read '{path}': {e}"))?);
+            }
+            "--timeout" => timeout_ms = value("--timeout")?,
+            "--retries" => {
+                retries = u32::try_from(value("--retries")?).map_err(|_| "--retries: too large")?;
+            }
+            "--interval" => interval_ms = value("--interval")?,
+            "--count" => count = Some(value("--count")?),
+            name if name.starts_with('-') => return Err(format!("unknown option '{name}'")),
+            word => words.push(word),
+        }
+    }
+    let command = parse_command(&words, source, blob)?;
+    Ok(Some(Options {
+        port,
+        timeout_ms,
+        retries,
+        interval_ms,
+        count,
+        command,
+    }))
+}
+
+/// Builds the command from the positional arguments.
+///
+/// # Arguments
+/// * `words` - Command name and its arguments
+/// * `source` - Pattern source read with `--file`
+/// * `blob` - Binary pattern read with `--load`
+///
+/// # Returns
+/// * `Result<Command, String>` - Command or error message
+fn parse_command(
+    words: &[&str],
+    source: Option<String>,
+    blob: Option<Vec<u8>>,
+) -> Result<Command, String> {
+    let number = |raw: &str| -> Result<u64, String> {
+        raw.parse().map_err(|_| format!("invalid number '{raw}'"))
+    };
+    match words {
+        [] => Err(String::from("missing command")),
+        ["ping"] => Ok(Command::Ping),
+        ["set-rate", on] | ["set-rate", on, _] => {
+            let on_ms = number(on)?;
+            let off_ms = words.get(2).map_or(Ok(on_ms), |off| number(off))?;
+            BlinkConfig::new(on_ms, off_ms)
+                .map(Command::SetRate)
+                .map_err(|e| format!("invalid rate: {e:?}"))
+        }
+        ["upload-pattern", text @ ..] => {
+            let pattern = match (text.is_empty(), source, blob) {
+                (true, None, Some(blob)) => codec::decode(&blob)
+                    .map_err(|e| format!("cannot decode pattern file: {e:?}"))?,
+                (true, Some(source), None) => parse_pattern(&source)?,
+                (false, None, None) => parse_pattern(&text.join(" "))?,
+                _ => {
+                    return Err(String::from(
+                        "upload-pattern needs one of SOURCE, --file or --load",
+                    ));
+                }
+            };
+            Ok(Command::UploadPattern(pattern))
+        }
+        ["get-status"] => Ok(Command::GetStatus),
+        ["dump-settings"] => Ok(Command::DumpSettings),
+        ["watch"] => Ok(Command::Watch),
+        [name, ..] if is_command(name) => Err(format!("wrong arguments for '{name}'")),
+        [name, ..] => Err(format!("unknown command '{name}'")),
+    }
+}
+
+/// Returns whether a word names a command.
+///
+/// # Arguments
+/// * `name` - Word to check
+///
+/// # Returns
+/// * `bool` - True for a known command
+fn is_command(name: &str) -> bool {
+    matches!(
+        name,
+        "ping" | "set-rate" | "upload-pattern" | "get-status" | "dump-settings" | "watch"
+    )
+}
+
+/// Parses pattern language source.
+///
+/// # Arguments
+/// * `source` - Pattern source
+///
+/// # Returns
+/// * `Result<Pattern, String>` - Pattern or error message
+fn parse_pattern(source: &str) -> Result<Pattern, String> {
+    dsl::parse(source).map_err(|e| {
+        format!(
+            "pattern error at line {}, column {}: {:?}",
+            e.line, e.column, e.kind
+        )
+    })
+}
+
+/// Opens a serial device in raw mode.
+///
+/// # Details
+/// Reads return after 100 ms without data so the link can
+/// enforce its timeouts.
+///
+/// # Arguments
+/// * `path` - Device path
+///
+/// # Returns
+/// * `io::Result<File>` - Open device or error
+fn open_port(path: &str) -> io::Result<File> {
+    let port = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY)
+        .open(path)?;
+    let fd = port.as_raw_fd();
+    // SAFETY: termios is plain data, and `fd` stays open for the
+    // duration of the calls.
+    unsafe {
+        let mut tty: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut tty) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        libc::cfmakeraw(&mut tty);
+        tty.c_cc[libc::VMIN] = 0;
+        tty.c_cc[libc::VTIME] = 1;
+        if libc::tcsetattr(fd, libc::TCSANOW, &tty) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(port)
+}
+
+/// Request/response link to a device.
+///
+/// # Details
+/// Numbers every request, resends it unchanged when no reply
+/// arrives in time and drops replies to other sequence numbers,
+/// so a late reply to an earlier attempt is never mistaken for
+/// the current one.
+///
+/// # Fields
+/// * `port` - Byte stream to the device
+/// * `seq` - Sequence number of the last request
+/// * `timeout` - Reply timeout per attempt
+/// * `retries` - Resends after a timeout
+/// * `decoder` - Splitter for received frames
+struct Link<T> {
+    port: T,
+    seq: u8,
+    timeout: Duration,
+    retries: u32,
+    decoder: FrameDecoder<MAX_BODY_LEN>,
+}
+
+impl<T: Read + Write> Link<T> {
+    /// Creates a link over a byte stream.
+    ///
+    /// # Arguments
+    /// * `port` - Byte stream whose reads time out
+    /// * `timeout` - Reply timeout per attempt
+    /// * `retries` - Resends after a timeout
+    /// * `seq` - Sequence number before the first request
+    ///
+    /// # Returns
+    /// * `Self` - New Link instance
+    fn new(port: T, timeout: Duration, retries: u32, seq: u8) -> Self {
+        Self {
+            port,
+            seq,
+            timeout,
+            retries,
+            decoder: FrameDecoder::new(),
+        }
+    }
+
+    /// Sends a request and waits for its reply.
+    ///
+    /// # Arguments
+    /// * `request` - Request to send
+    ///
+    /// # Returns
+    /// * `Result<Response, String>` - Reply or error message
+    fn request(&mut self, request: &Request) -> Result<Response, String> {
+        self.seq = self.seq.wrapping_add(1);
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = protocol::encode_frame(self.seq, request, &mut frame)
+            .map_err(|e| format!("cannot encode request: {e:?}"))?;
+        for _ in 0..=self.retries {
+            self.port
+                .write_all(&frame[..len])
+                .map_err(|e| format!("write failed: {e}"))?;
+            let deadline = Instant::now() + self.timeout;
+            if let Some(response) = self.receive(deadline)? {
+                return Ok(response);
+            }
+        }
+        Err(format!("no reply after {} attempts", self.retries + 1))
+    }
+
+    /// Waits for the reply to the current request.
+    ///
+    /// # Arguments
+    /// * `deadline` - Time to give up
+    ///
+    /// # Returns
+    /// * `Result<Option<Response>, String>` - Reply, None on timeout, or error message
+    fn receive(&mut self, deadline: Instant) -> Result<Option<Response>, String> {
+        let mut buf = [0u8; 256];
+        while Instant::now() < deadline {
+            let n = match self.port.read(&mut buf) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => 0,
+                Err(e) => return Err(format!("read failed: {e}")),
+            };
+            for &byte in &buf[..n] {
+                if let Some(Ok(packet)) = self.decoder.decode::<Response>(byte)
+                    && packet.seq == self.seq
+                {
+                    return Ok(Some(packet.message));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Picks the first sequence number of a session.
+///
+/// # Details
+/// Taken from the clock, so back-to-back runs of the tool do not
+/// reuse the sequence numbers of the previous run.
+///
+/// # Returns
+/// * `u8` - Starting sequence number
+fn session_seq() -> u8 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |t| t.subsec_nanos() as u8)
+}
+
+/// Describes a reply the command did not expect.
+///
+/// # Arguments
+/// * `response` - Reply received
+///
+/// # Returns
+/// * `String` - Error message
+fn unexpected(response: Response) -> String {
+    match response {
+        Response::Error(code) => format!("device refused the request: {code:?}"),
+        other => format!("unexpected reply: {other:?}"),
+    }
+}
+
+/// Returns the display name of an LED state.
+///
+/// # Arguments
+/// * `state` - LED state
+///
+/// # Returns
+/// * `&'static str` - "on" or "off"
+fn state_name(state: LedState) -> &'static str {
+    match state {
+        LedState::On => "on",
+        LedState::Off => "off",
+    }
+}
+
+/// Runs a command and prints its result.
+///
+/// # Arguments
+/// * `options` - Parsed options with the command
+/// * `link` - Link to the device
+/// * `out` - Output sink
+///
+/// # Returns
+/// * `Result<(), String>` - Ok or error message
+fn run<T: Read + Write>(
+    options: &Options,
+    link: &mut Link<T>,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    match &options.command {
+        Command::Ping => {
+            let start = Instant::now();
+            let Response::Pong(version) = link.request(&Request::Ping)? else {
+                return Err(String::from("unexpected reply to ping"));
+            };
+            let _ = writeln!(
+                out,
+                "pong: protocol {version}, {} ms",
+                start.elapsed().as_millis()
+            );
+        }
+        Command::SetRate(config) => {
+            match link.request(&Request::SetConfig(*config))? {
+                Response::Ack => {}
+                other => return Err(unexpected(other)),
+            }
+            let _ = writeln!(out, "ok");
+        }
+        Command::UploadPattern(pattern) => {
+            match link.request(&Request::UploadPattern(*pattern))? {
+                Response::Ack => {}
+                other => return Err(unexpected(other)),
+            }
+            let _ = writeln!(out, "ok");
+        }
+        Command::GetStatus => {
+            let state = match link.request(&Request::GetState)? {
+                Response::State(state) => state,
+                other => return Err(unexpected(other)),
+            };
+            let config = match link.request(&Request::GetConfig)? {
+                Response::Config(config) => config,
+                other => return Err(unexpected(other)),
+            };
+            let _ = writeln!(out, "led {}", state_name(state));
+            let _ = writeln!(out, "rate {} {}", config.on_ms(), config.off_ms());
+        }
+        Command::DumpSettings => {
+            let settings = match link.request(&Request::GetSettings)? {
+                Response::Settings(settings) => settings,
+                other => return Err(unexpected(other)),
+            };
+            let _ = writeln!(
+                out,
+                "rate {} {}",
+                settings.blink.on_ms(),
+                settings.blink.off_ms()
+            );
+            match settings.pattern {
+                Some(pattern) => {
+                    let _ = writeln!(
+                        out,
+                        "pattern {} segments, {} ms",
+                        pattern.segments().len(),
+                        pattern.period_ms()
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "pattern none");
+                }
+            }
+            let polarity = match settings.polarity {
+                Polarity::ActiveHigh => "active-high",
+                Polarity::ActiveLow => "active-low",
+            };
+            let _ = writeln!(out, "polarity {polarity}");
+        }
+        Command::Watch => watch(options, link, out)?,
+    }
+    Ok(())
+}
+
+/// Polls the LED state and prints every change.
+///
+/// # Arguments
+/// * `options` - Interval and optional change count
+/// * `link` - Link to the device
+/// * `out` - Output sink
+///
+/// # Returns
+/// * `Result<(), String>` - Ok after `count` changes, or error message
+fn watch<T: Read + Write>(
+    options: &Options,
+    link: &mut Link<T>,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let start = Instant::now();
+    let mut last = None;
+    let mut changes = 0;
+    while options.count.is_none_or(|count| changes < count) {
+        let state = match link.request(&Request::GetState)? {
+            Response::State(state) => state,
+            other => return Err(unexpected(other)),
+        };
+        if last != Some(state) {
+            let elapsed = start.elapsed().as_millis();
+            let _ = writeln!(out, "{elapsed:>8} ms  LED {}", state_name(state));
+            let _ = out.flush();
+            last = Some(state);
+            changes += 1;
+        }
+        std::thread::sleep(Duration::from_millis(options.interval_ms));
+    }
+    Ok(())
+}
+
+/// Tool entry point.
+///
+/// # Returns
+/// * `ExitCode` - Success, or failure on bad arguments or link errors
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let port = match open_port(&options.port) {
+        Ok(port) => port,
+        Err(e) => {
+            eprintln!("error: cannot open '{}': {e}", options.port);
+            return ExitCode::FAILURE;
+        }
+    };
+    let timeout = Duration::from_millis(options.timeout_ms);
+    let mut link = Link::new(port, timeout, options.retries, session_seq());
+    match run(&options, &mut link, &mut io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day001_blink_led::emulator::Emulator;
+    use day001_blink_led::protocol::{ErrorCode, PROTOCOL_VERSION, Packet};
+    use day001_blink_led::pty::open_pty;
+    use std::thread::{self, JoinHandle};
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| String::from(*s)).collect()
+    }
+
+    fn command(list: &[&str]) -> Result<Command, String> {
+        parse_args(&args(list)).map(|options| options.unwrap().command)
+    }
+
+    /// Emulated board on the far side of a pseudo-terminal.
+    ///
+    /// Runs on a virtual clock that moves `step_ms` forward for
+    /// every request frame the board receives, so LED timing is
+    /// the same on every run. `ignore` drops whole requests and
+    /// `mute` drops replies, to exercise the link's retries.
+    struct Bench {
+        emulator: Emulator,
+        now_ms: u64,
+        step_ms: u64,
+        ignore: usize,
+        mute: usize,
+        seqs: Vec<u8>,
+        states: Vec<(u64, LedState)>,
+    }
+
+    impl Bench {
+        fn new(step_ms: u64) -> Self {
+            Self {
+                emulator: Emulator::new(0),
+                now_ms: 0,
+                step_ms,
+                ignore: 0,
+                mute: 0,
+                seqs: Vec::new(),
+                states: Vec::new(),
+            }
+        }
+
+        fn handle(&mut self, frame: &[u8]) -> Vec<u8> {
+            if let Ok(packet) = protocol::decode_frame::<Request>(&frame[1..frame.len() - 1]) {
+                self.seqs.push(packet.seq);
+            }
+            if self.ignore > 0 {
+                self.ignore -= 1;
+                return Vec::new();
+            }
+            self.now_ms += self.step_ms;
+            let out = self.emulator.feed(frame, self.now_ms);
+            if let Ok(Packet {
+                message: Response::State(state),
+                ..
+            }) = protocol::decode_frame::<Response>(&out[1..out.len() - 1])
+            {
+                self.states.push((self.now_ms, state));
+            }
+            if self.mute > 0 {
+                self.mute -= 1;
+                return Vec::new();
+            }
+            out
+        }
+
+        fn serve(mut self, mut master: File) -> Self {
+            let mut decoder: FrameDecoder<MAX_BODY_LEN> = FrameDecoder::new();
+            let mut frame = Vec::new();
+            let mut buf = [0u8; 256];
+            while let Ok(n @ 1..) = master.read(&mut buf) {
+                for &byte in &buf[..n] {
+                    frame.push(byte);
+                    if decoder.push(byte).is_some() {
+                        let out = self.handle(&frame);
+                        master.write_all(&out).unwrap();
+                        frame.clear();
+                    }
+                }
+            }
+            self
+        }
+
+        /// Returns the polled LED states at each change.
+        fn changes(&self) -> Vec<(u64, LedState)> {
+            let mut changes: Vec<(u64, LedState)> = Vec::new();
+            for &(ms, state) in &self.states {
+                if changes.last().is_none_or(|&(_, last)| last != state) {
+                    changes.push((ms, state));
+                }
+            }
+            changes
+        }
+    }
+
+    fn connect(bench: Bench, retries: u32) -> (Link<File>, JoinHandle<Bench>) {
+        let (master, path) = open_pty().unwrap();
+        let port = open_port(&path).unwrap();
+        let handle = thread::spawn(move || bench.serve(master));
+        (
+            Link::new(port, Duration::from_millis(200), retries, 0),
+            handle,
+        )
+    }
+
+    fn run_command(link: &mut Link<File>, list: &[&str]) -> Result<String, String> {
+        let options = parse_args(&args(list)).unwrap().unwrap();
+        let mut out = Vec::new();
+        run(&options, link, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    // ==================== parse_args() Tests ====================
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse_args(&args(&["ping"])).unwrap().unwrap();
+        assert_eq!(options.port, DEFAULT_PORT);
+        assert_eq!(options.timeout_ms, 500);
+        assert_eq!(options.retries, 3);
+        assert_eq!(options.count, None);
+        assert_eq!(options.command, Command::Ping);
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse_args(&args(&[
+            "--port",
+            "/dev/ttyACM1",
+            "watch",
+            "--timeout",
+            "100",
+            "--retries",
+            "0",
+            "--interval",
+            "10",
+            "--count",
+            "5",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.port, "/dev/ttyACM1");
+        assert_eq!(options.timeout_ms, 100);
+        assert_eq!(options.retries, 0);
+        assert_eq!(options.interval_ms, 10);
+        assert_eq!(options.count, Some(5));
+        assert_eq!(options.command, Command::Watch);
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert_eq!(parse_args(&args(&["--help"])), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_set_rate() {
+        assert_eq!(
+            command(&["set-rate", "250"]),
+            Ok(Command::SetRate(BlinkConfig::new(250, 250).unwrap()))
+        );
+        assert_eq!(
+            command(&["set-rate", "100", "900"]),
+            Ok(Command::SetRate(BlinkConfig::new(100, 900).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_parse_set_rate_errors() {
+        assert!(command(&["set-rate"]).is_err());
+        assert!(command(&["set-rate", "fast"]).is_err());
+        assert!(
+            command(&["set-rate", "1"])
+                .unwrap_err()
+                .contains("TooShort")
+        );
+        assert!(command(&["set-rate", "100", "100", "100"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_upload_pattern() {
+        let expected = dsl::parse("on 100ms, off 900ms").unwrap();
+        assert_eq!(
+            command(&["upload-pattern", "on", "100ms,", "off", "900ms"]),
+            Ok(Command::UploadPattern(expected))
+        );
+    }
+
+    #[test]
+    fn test_parse_upload_pattern_errors() {
+        assert!(command(&["upload-pattern"]).is_err());
+        assert!(
+            command(&["upload-pattern", "on 5xs"])
+                .unwrap_err()
+                .contains("column")
+        );
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        assert_eq!(command(&[]), Err(String::from("missing command")));
+        assert!(command(&["blink"]).unwrap_err().contains("unknown command"));
+        assert!(
+            command(&["ping", "now"])
+                .unwrap_err()
+                .contains("wrong arguments")
+        );
+        assert!(
+            command(&["--bogus"])
+                .unwrap_err()
+                .contains("unknown option")
+        );
+    }
+
+    // ==================== Link Tests ====================
+
+    #[test]
+    fn test_ping() {
+        let (mut link, _bench) = connect(Bench::new(10), 0);
+        let out = run_command(&mut link, &["ping"]).unwrap();
+        assert!(out.starts_with("pong: protocol 1,"));
+    }
+
+    #[test]
+    fn test_set_rate_then_status() {
+        let (mut link, _bench) = connect(Bench::new(10), 0);
+        assert_eq!(
+            run_command(&mut link, &["set-rate", "100", "900"]),
+            Ok(String::from("ok\n"))
+        );
+        assert_eq!(
+            run_command(&mut link, &["get-status"]),
+            Ok(String::from("led on\nrate 100 900\n"))
+        );
+    }
+
+    #[test]
+    fn test_set_rate_then_dump() {
+        let (mut link, _bench) = connect(Bench::new(10), 0);
+        run_command(&mut link, &["set-rate", "100", "900"]).unwrap();
+        assert_eq!(
+            run_command(&mut link, &["dump-settings"]),
+            Ok(String::from(
+                "rate 100 900\npattern none\npolarity active-high\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_upload_pattern_then_dump() {
+        let (mut link, _bench) = connect(Bench::new(10), 0);
+        let upload = ["upload-pattern", "on 100ms, off 400ms, on 100ms, off 400ms"];
+        assert_eq!(run_command(&mut link, &upload), Ok(String::from("ok\n")));
+        assert_eq!(
+            run_command(&mut link, &["dump-settings"]),
+            Ok(String::from(
+                "rate 500 500\npattern 4 segments, 1000 ms\npolarity active-high\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_dump_default_settings() {
+        let (mut link, _bench) = connect(Bench::new(10), 0);
+        let out = run_command(&mut link, &["dump-settings"]).unwrap();
+        assert!(out.contains("pattern none\n"));
+    }
+
+    #[test]
+    fn test_watch_follows_default_blink() {
+        let (mut link, handle) = connect(Bench::new(100), 0);
+        let out = run_command(&mut link, &["watch", "--interval", "1", "--count", "4"]).unwrap();
+        let states: Vec<&str> = out.lines().map(|l| l.rsplit(' ').next().unwrap()).collect();
+        assert_eq!(states, ["on", "off", "on", "off"]);
+        drop(link);
+        assert_eq!(
+            handle.join().unwrap().changes(),
+            [
+                (100, LedState::On),
+                (500, LedState::Off),
+                (1000, LedState::On),
+                (1500, LedState::Off),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_watch_follows_new_rate() {
+        let (mut link, handle) = connect(Bench::new(50), 0);
+        run_command(&mut link, &["set-rate", "100", "300"]).unwrap();
+        let out = run_command(&mut link, &["watch", "--interval", "1", "--count", "4"]).unwrap();
+        let states: Vec<&str> = out.lines().map(|l| l.rsplit(' ').next().unwrap()).collect();
+        assert_eq!(states, ["on", "off", "on", "off"]);
+        drop(link);
+        assert_eq!(
+            handle.join().unwrap().changes(),
+            [
+                (100, LedState::On),
+                (150, LedState::Off),
+                (450, LedState::On),
+                (550, LedState::Off),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retry_resends_same_sequence() {
+        let mut bench = Bench::new(10);
+        bench.ignore = 2;
+        let (mut link, handle) = connect(bench, 3);
+        assert_eq!(
+            link.request(&Request::Ping),
+            Ok(Response::Pong(PROTOCOL_VERSION))
+        );
+        assert_eq!(
+            link.request(&Request::Ping),
+            Ok(Response::Pong(PROTOCOL_VERSION))
+        );
+        drop(link);
+        assert_eq!(handle.join().unwrap().seqs, [1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_lost_reply_is_replayed_not_rerun() {
+        // Requests land at 300, 600 and 900 ms: on, off, off.
+        let mut bench = Bench::new(300);
+        bench.mute = 1;
+        let (mut link, handle) = connect(bench, 2);
+        assert_eq!(
+            link.request(&Request::GetState),
+            Ok(Response::State(LedState::On))
+        );
+        assert_eq!(
+            link.request(&Request::GetState),
+            Ok(Response::State(LedState::Off))
+        );
+        drop(link);
+        let bench = handle.join().unwrap();
+        assert_eq!(bench.seqs, [1, 1, 2]);
+        assert_eq!(
+            bench.states,
+            [
+                (300, LedState::On),
+                (600, LedState::On),
+                (900, LedState::Off)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gives_up_after_retries() {
+        let mut bench = Bench::new(10);
+        bench.ignore = usize::MAX;
+        let (mut link, handle) = connect(bench, 1);
+        assert_eq!(
+            link.request(&Request::GetState),
+            Err(String::from("no reply after 2 attempts"))
+        );
+        drop(link);
+        assert_eq!(handle.join().unwrap().seqs, [1, 1]);
+    }
+
+    #[test]
+    fn test_empty_pattern_refused_before_sending() {
+        let (mut link, handle) = connect(Bench::new(10), 0);
+        let mut options = parse_args(&args(&["ping"])).unwrap().unwrap();
+        options.command = Command::UploadPattern(Pattern::new());
+        assert_eq!(
+            run(&options, &mut link, &mut Vec::new()),
+            Err(String::from("cannot encode request: InvalidPayload"))
+        );
+        drop(link);
+        assert!(handle.join().unwrap().seqs.is_empty());
+    }
+
+    #[test]
+    fn test_device_error_reported() {
+        assert_eq!(
+            unexpected(Response::Error(ErrorCode::InvalidPattern)),
+            "device refused the request: InvalidPattern"
+        );
+    }
+}
@@ -0,0 +1,385 @@
+/*
+ * @file blink_code.rs
+ * @brief Numeric blink-code encoder
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: blink_code.rs
+//!
+//! DESCRIPTION:
+//! Numeric Blink-Code Encoder for RP2350 LED Patterns.
+//!
+//! BRIEF:
+//! Turns a number from 0 to 99 into a looping Pattern: one long
+//! flash per ten, a separator, then one short flash per unit. A
+//! zero digit is shown as a distinct extra-long flash, and a
+//! repeat gap marks where the code starts over.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::pattern::{MAX_SEGMENT_MS, Pattern, Segment};
+
+/// Largest value a blink code can show.
+///
+/// # Value
+/// 99
+#[allow(dead_code)]
+pub const MAX_BLINK_CODE: u16 = 99;
+
+/// Blink-code encoding errors.
+///
+/// # Variants
+/// * `OutOfRange` - Value above `MAX_BLINK_CODE`
+/// * `InvalidTiming` - A duration is zero or too long, or the symbols are not distinct
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum BlinkCodeError {
+    OutOfRange(u16),
+    InvalidTiming,
+}
+
+/// Flash and gap durations of a blink code.
+///
+/// # Details
+/// Symbols must be told apart by eye, so `short_ms` must be
+/// below `long_ms`, which must be below `zero_ms`, and gaps must
+/// grow from `gap_ms` to `separator_ms` to `repeat_gap_ms`.
+/// No duration may exceed `MAX_SEGMENT_MS`.
+///
+/// # Fields
+/// * `long_ms` - Flash for each ten
+/// * `short_ms` - Flash for each unit
+/// * `zero_ms` - Flash for a zero digit
+/// * `gap_ms` - Gap between flashes of one digit
+/// * `separator_ms` - Gap between the tens and the units
+/// * `repeat_gap_ms` - Gap before the code repeats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BlinkCodeTiming {
+    pub long_ms: u64,
+    pub short_ms: u64,
+    pub zero_ms: u64,
+    pub gap_ms: u64,
+    pub separator_ms: u64,
+    pub repeat_gap_ms: u64,
+}
+
+impl Default for BlinkCodeTiming {
+    /// Returns timing readable from across a room.
+    ///
+    /// # Returns
+    /// * `Self` - 600/200/1200 ms flashes with 300/1000/3000 ms gaps
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self {
+            long_ms: 600,
+            short_ms: 200,
+            zero_ms: 1200,
+            gap_ms: 300,
+            separator_ms: 1000,
+            repeat_gap_ms: 3000,
+        }
+    }
+}
+
+impl BlinkCodeTiming {
+    /// Checks that every duration is set and in range and symbols are distinct.
+    ///
+    /// # Returns
+    /// * `Result<(), BlinkCodeError>` - Ok or `InvalidTiming`
+    #[allow(dead_code)]
+    pub fn validate(&self) -> Result<(), BlinkCodeError> {
+        let flashes =
+            0 < self.short_ms && self.short_ms < self.long_ms && self.long_ms < self.zero_ms;
+        let gaps = 0 < self.gap_ms
+            && self.gap_ms < self.separator_ms
+            && self.separator_ms < self.repeat_gap_ms;
+        let bounded = [
+            self.long_ms,
+            self.short_ms,
+            self.zero_ms,
+            self.gap_ms,
+            self.separator_ms,
+            self.repeat_gap_ms,
+        ]
+        .iter()
+        .all(|&ms| ms <= MAX_SEGMENT_MS);
+        if flashes && gaps && bounded {
+            Ok(())
+        } else {
+            Err(BlinkCodeError::InvalidTiming)
+        }
+    }
+}
+
+/// Encodes a number as a blink code.
+///
+/// # Details
+/// Values below ten show only the units. A zero units digit,
+/// or the value zero itself, shows the zero flash. The pattern
+/// loops; chain `with_repeat` and `with_end_action` to show it a
+/// fixed number of times.
+///
+/// # Arguments
+/// * `value` - Number to show, 0 to `MAX_BLINK_CODE`
+/// * `timing` - Flash and gap durations
+///
+/// # Returns
+/// * `Result<Pattern, BlinkCodeError>` - Looping pattern or encoding error
+#[allow(dead_code)]
+pub fn encode(value: u16, timing: BlinkCodeTiming) -> Result<Pattern, BlinkCodeError> {
+    if value > MAX_BLINK_CODE {
+        return Err(BlinkCodeError::OutOfRange(value));
+    }
+    timing.validate()?;
+    let mut pattern = Pattern::new();
+    let tens = value / 10;
+    let units = value % 10;
+    if tens > 0 {
+        push_digit(
+            &mut pattern,
+            tens,
+            timing.long_ms,
+            &timing,
+            timing.separator_ms,
+        )?;
+    }
+    push_digit(
+        &mut pattern,
+        units,
+        timing.short_ms,
+        &timing,
+        timing.repeat_gap_ms,
+    )?;
+    Ok(pattern)
+}
+
+/// Appends the flashes of one digit.
+///
+/// # Details
+/// At most 9 + 9 flashes, 36 segments, are ever pushed, well
+/// within pattern capacity.
+///
+/// # Arguments
+/// * `pattern` - Pattern to extend
+/// * `digit` - Digit to show, 0 to 9
+/// * `flash_ms` - Flash length for a non-zero digit
+/// * `timing` - Flash and gap durations
+/// * `end_gap_ms` - Gap after the digit's last flash
+///
+/// # Returns
+/// * `Result<(), BlinkCodeError>` - Ok or `InvalidTiming`
+fn push_digit(
+    pattern: &mut Pattern,
+    digit: u16,
+    flash_ms: u64,
+    timing: &BlinkCodeTiming,
+    end_gap_ms: u64,
+) -> Result<(), BlinkCodeError> {
+    let (count, flash_ms) = if digit == 0 {
+        (1, timing.zero_ms)
+    } else {
+        (digit, flash_ms)
+    };
+    for i in 0..count {
+        let gap_ms = if i + 1 == count {
+            end_gap_ms
+        } else {
+            timing.gap_ms
+        };
+        pattern
+            .push(Segment::on(flash_ms))
+            .and_then(|()| pattern.push(Segment::off(gap_ms)))
+            .map_err(|_| BlinkCodeError::InvalidTiming)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::led::LedState;
+
+    fn timing() -> BlinkCodeTiming {
+        BlinkCodeTiming::default()
+    }
+
+    fn flashes(pattern: &Pattern) -> Vec<u64> {
+        pattern
+            .segments()
+            .iter()
+            .filter(|s| s.state == LedState::On)
+            .map(|s| s.duration_ms)
+            .collect()
+    }
+
+    // ==================== Timing Tests ====================
+
+    #[test]
+    fn test_default_timing_valid() {
+        assert_eq!(timing().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_timing_rejects_oversized_field() {
+        let bad = BlinkCodeTiming {
+            repeat_gap_ms: 5_000_000_000,
+            ..timing()
+        };
+        assert_eq!(bad.validate(), Err(BlinkCodeError::InvalidTiming));
+        assert_eq!(encode(3, bad), Err(BlinkCodeError::InvalidTiming));
+        let limit = BlinkCodeTiming {
+            repeat_gap_ms: MAX_SEGMENT_MS,
+            ..timing()
+        };
+        let pattern = encode(3, limit).unwrap();
+        assert_eq!(
+            pattern.segments().last(),
+            Some(&Segment::off(MAX_SEGMENT_MS))
+        );
+    }
+
+    #[test]
+    fn test_timing_rejects_zero() {
+        let bad = BlinkCodeTiming {
+            gap_ms: 0,
+            ..timing()
+        };
+        assert_eq!(bad.validate(), Err(BlinkCodeError::InvalidTiming));
+    }
+
+    #[test]
+    fn test_timing_rejects_short_not_shorter() {
+        let bad = BlinkCodeTiming {
+            short_ms: 600,
+            ..timing()
+        };
+        assert_eq!(bad.validate(), Err(BlinkCodeError::InvalidTiming));
+    }
+
+    #[test]
+    fn test_timing_rejects_zero_symbol_not_longest() {
+        let bad = BlinkCodeTiming {
+            zero_ms: 600,
+            ..timing()
+        };
+        assert_eq!(bad.validate(), Err(BlinkCodeError::InvalidTiming));
+    }
+
+    #[test]
+    fn test_timing_rejects_separator_not_between() {
+        let bad = BlinkCodeTiming {
+            separator_ms: 3000,
+            ..timing()
+        };
+        assert_eq!(bad.validate(), Err(BlinkCodeError::InvalidTiming));
+    }
+
+    // ==================== Encode Tests ====================
+
+    #[test]
+    fn test_encode_units_only() {
+        let pattern = encode(3, timing()).unwrap();
+        assert_eq!(flashes(&pattern), vec![200, 200, 200]);
+    }
+
+    #[test]
+    fn test_encode_tens_and_units() {
+        let pattern = encode(23, timing()).unwrap();
+        assert_eq!(flashes(&pattern), vec![600, 600, 200, 200, 200]);
+    }
+
+    #[test]
+    fn test_encode_zero() {
+        let pattern = encode(0, timing()).unwrap();
+        assert_eq!(pattern.segments(), &[Segment::on(1200), Segment::off(3000)]);
+    }
+
+    #[test]
+    fn test_encode_zero_units() {
+        let pattern = encode(20, timing()).unwrap();
+        assert_eq!(flashes(&pattern), vec![600, 600, 1200]);
+    }
+
+    #[test]
+    fn test_encode_gaps() {
+        let pattern = encode(12, timing()).unwrap();
+        assert_eq!(
+            pattern.segments(),
+            &[
+                Segment::on(600),
+                Segment::off(1000),
+                Segment::on(200),
+                Segment::off(300),
+                Segment::on(200),
+                Segment::off(3000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_max() {
+        let pattern = encode(MAX_BLINK_CODE, timing()).unwrap();
+        assert_eq!(pattern.len(), 36);
+        assert_eq!(flashes(&pattern).len(), 18);
+    }
+
+    #[test]
+    fn test_encode_out_of_range() {
+        assert_eq!(encode(100, timing()), Err(BlinkCodeError::OutOfRange(100)));
+    }
+
+    #[test]
+    fn test_encode_rejects_invalid_timing() {
+        let bad = BlinkCodeTiming {
+            long_ms: 100,
+            ..timing()
+        };
+        assert_eq!(encode(5, bad), Err(BlinkCodeError::InvalidTiming));
+    }
+
+    #[test]
+    fn test_encode_custom_timing() {
+        let fast = BlinkCodeTiming {
+            long_ms: 300,
+            short_ms: 100,
+            zero_ms: 500,
+            gap_ms: 100,
+            separator_ms: 400,
+            repeat_gap_ms: 1000,
+        };
+        let pattern = encode(11, fast).unwrap();
+        assert_eq!(pattern.period_ms(), 300 + 400 + 100 + 1000);
+    }
+
+    #[test]
+    fn test_encode_loops() {
+        let pattern = encode(7, timing()).unwrap();
+        assert_eq!(pattern.end_action(), crate::pattern::EndAction::Loop);
+    }
+}
@@ -0,0 +1,593 @@
+/*
+ * @file brightness.rs
+ * @brief LED brightness and CIE 1931 gamma correction
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: brightness.rs
+//!
+//! DESCRIPTION:
+//! LED Brightness Control for RP2350 PWM.
+//!
+//! BRIEF:
+//! Provides a perceptual brightness level, a CIE 1931 lightness
+//! correction table generated at compile time, and a PWM-backed
+//! output pin that dims the LED when on.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::LedState;
+use embedded_hal::digital::{self, ErrorType, OutputPin};
+use embedded_hal::pwm::{self, SetDutyCycle};
+
+/// Number of entries in the CIE 1931 correction table.
+///
+/// # Details
+/// One entry per high byte of a brightness level plus a final
+/// entry for full scale, so every step can be interpolated.
+///
+/// # Value
+/// 257 entries
+#[allow(dead_code)]
+pub const CIE_TABLE_SIZE: usize = 257;
+
+/// CIE 1931 lightness to luminance table.
+///
+/// # Details
+/// Maps evenly spaced perceived lightness L* (0-100%) to relative
+/// luminance Y scaled to 0-65535. Generated at compile time.
+#[allow(dead_code)]
+pub static CIE_TABLE: [u16; CIE_TABLE_SIZE] = build_cie_table();
+
+/// Builds the CIE 1931 correction table.
+///
+/// # Details
+/// Y = L* / 903.3 for L* <= 8, otherwise ((L* + 16) / 116)^3.
+///
+/// # Returns
+/// * `[u16; CIE_TABLE_SIZE]` - Luminance for each lightness step
+const fn build_cie_table() -> [u16; CIE_TABLE_SIZE] {
+    let mut table = [0u16; CIE_TABLE_SIZE];
+    let mut i = 0;
+    while i < CIE_TABLE_SIZE {
+        let l = 100.0 * i as f64 / (CIE_TABLE_SIZE - 1) as f64;
+        let y = if l <= 8.0 {
+            l / 903.3
+        } else {
+            let t = (l + 16.0) / 116.0;
+            t * t * t
+        };
+        table[i] = (y * u16::MAX as f64 + 0.5) as u16;
+        i += 1;
+    }
+    table
+}
+
+/// Perceived LED brightness.
+///
+/// # Details
+/// Linear in perceived lightness: 0 is off, 65535 is full.
+/// Converted to PWM duty through the CIE 1931 table.
+///
+/// # Fields
+/// * `0` - Raw brightness level
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(dead_code)]
+pub struct Brightness(u16);
+
+impl Default for Brightness {
+    /// Returns full brightness.
+    ///
+    /// # Returns
+    /// * `Self` - Full brightness
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl Brightness {
+    /// LED off.
+    #[allow(dead_code)]
+    pub const OFF: Self = Self(0);
+
+    /// LED at full brightness.
+    #[allow(dead_code)]
+    pub const FULL: Self = Self(u16::MAX);
+
+    /// Creates a brightness from a raw level.
+    ///
+    /// # Arguments
+    /// * `level` - Raw level, 0-65535
+    ///
+    /// # Returns
+    /// * `Self` - Brightness
+    #[allow(dead_code)]
+    pub const fn new(level: u16) -> Self {
+        Self(level)
+    }
+
+    /// Creates a brightness from a percentage.
+    ///
+    /// # Details
+    /// Values above 100 are clamped to 100.
+    ///
+    /// # Arguments
+    /// * `percent` - Brightness in percent, 0-100
+    ///
+    /// # Returns
+    /// * `Self` - Brightness
+    #[allow(dead_code)]
+    pub const fn from_percent(percent: u8) -> Self {
+        let percent = if percent > 100 { 100 } else { percent };
+        Self((percent as u32 * u16::MAX as u32 / 100) as u16)
+    }
+
+    /// Returns the raw level.
+    ///
+    /// # Returns
+    /// * `u16` - Raw level, 0-65535
+    #[allow(dead_code)]
+    pub const fn level(&self) -> u16 {
+        self.0
+    }
+
+    /// Returns the brightness as a rounded percentage.
+    ///
+    /// # Returns
+    /// * `u8` - Brightness in percent, 0-100
+    #[allow(dead_code)]
+    pub const fn percent(&self) -> u8 {
+        ((self.0 as u32 * 100 + u16::MAX as u32 / 2) / u16::MAX as u32) as u8
+    }
+}
+
+/// Applies CIE 1931 lightness correction.
+///
+/// # Details
+/// Interpolates the CIE 1931 table at the brightness level.
+///
+/// # Arguments
+/// * `brightness` - Perceived brightness
+///
+/// # Returns
+/// * `u16` - Relative luminance, 0-65535
+#[allow(dead_code)]
+pub fn gamma_correct(brightness: Brightness) -> u16 {
+    interpolate(&CIE_TABLE, brightness.level())
+}
+
+/// Looks up an evenly spaced table with linear interpolation.
+///
+/// # Details
+/// Scales `x` onto the table's N-1 intervals in 1/256 steps and
+/// interpolates between the two nearest entries.
+/// Entries must be non-decreasing.
+///
+/// # Arguments
+/// * `table` - Lookup table covering inputs 0-65535
+/// * `x` - Input, 0-65535
+///
+/// # Returns
+/// * `u16` - Interpolated output
+pub(crate) fn interpolate<const N: usize>(table: &[u16; N], x: u16) -> u16 {
+    let max = u32::from(u16::MAX);
+    let steps = (N as u32 - 1) * 256;
+    let pos = (u32::from(x) * steps + max / 2) / max;
+    let index = (pos >> 8) as usize;
+    if index >= N - 1 {
+        return table[N - 1];
+    }
+    let frac = pos & 0xFF;
+    let lo = u32::from(table[index]);
+    let hi = u32::from(table[index + 1]);
+    (lo + ((hi - lo) * frac + 128) / 256) as u16
+}
+
+/// Converts a brightness to a PWM duty cycle.
+///
+/// # Arguments
+/// * `brightness` - Perceived brightness
+/// * `max_duty` - PWM duty at 100%
+///
+/// # Returns
+/// * `u16` - Gamma-corrected duty, 0-`max_duty`
+#[allow(dead_code)]
+pub fn brightness_to_duty(brightness: Brightness, max_duty: u16) -> u16 {
+    let luminance = u32::from(gamma_correct(brightness));
+    ((luminance * u32::from(max_duty) + u32::from(u16::MAX) / 2) / u32::from(u16::MAX)) as u16
+}
+
+/// Converts an LedState to a PWM duty cycle.
+///
+/// # Details
+/// Maps Off to zero duty and On to the gamma-corrected brightness,
+/// the PWM counterpart of `led_state_to_level`.
+///
+/// # Arguments
+/// * `state` - LED state to convert
+/// * `brightness` - Brightness while on
+/// * `max_duty` - PWM duty at 100%
+///
+/// # Returns
+/// * `u16` - Duty cycle to apply
+#[allow(dead_code)]
+pub fn led_state_to_duty(state: LedState, brightness: Brightness, max_duty: u16) -> u16 {
+    match state {
+        LedState::On => brightness_to_duty(brightness, max_duty),
+        LedState::Off => 0,
+    }
+}
+
+/// Error from the PWM channel behind a DimmedPin.
+///
+/// # Fields
+/// * `0` - Underlying PWM error
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PwmPinError<E>(pub E);
+
+impl<E: pwm::Error> digital::Error for PwmPinError<E> {
+    /// Returns the generic digital error kind.
+    ///
+    /// # Returns
+    /// * `digital::ErrorKind` - Always `Other`
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+/// Output pin backed by a PWM channel.
+///
+/// # Details
+/// High drives the channel at the configured brightness, low turns
+/// it off, so any OutputPin user such as BlinkRunner gets dimming.
+///
+/// # Fields
+/// * `pwm` - PWM channel driving the LED
+/// * `brightness` - Brightness while high
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct DimmedPin<P> {
+    pwm: P,
+    brightness: Brightness,
+}
+
+impl<P: SetDutyCycle> DimmedPin<P> {
+    /// Creates a dimmed pin.
+    ///
+    /// # Arguments
+    /// * `pwm` - PWM channel driving the LED
+    /// * `brightness` - Brightness while high
+    ///
+    /// # Returns
+    /// * `Self` - New DimmedPin instance
+    #[allow(dead_code)]
+    pub fn new(pwm: P, brightness: Brightness) -> Self {
+        Self { pwm, brightness }
+    }
+
+    /// Returns the brightness used while high.
+    ///
+    /// # Returns
+    /// * `Brightness` - Current brightness
+    #[allow(dead_code)]
+    pub fn brightness(&self) -> Brightness {
+        self.brightness
+    }
+
+    /// Sets the brightness used while high.
+    ///
+    /// # Details
+    /// Takes effect on the next high write.
+    ///
+    /// # Arguments
+    /// * `brightness` - New brightness
+    #[allow(dead_code)]
+    pub fn set_brightness(&mut self, brightness: Brightness) {
+        self.brightness = brightness;
+    }
+
+    /// Applies an LED state at the configured brightness.
+    ///
+    /// # Arguments
+    /// * `state` - LED state to apply
+    ///
+    /// # Returns
+    /// * `Result<(), P::Error>` - Ok or PWM error
+    #[allow(dead_code)]
+    pub fn apply(&mut self, state: LedState) -> Result<(), P::Error> {
+        let duty = led_state_to_duty(state, self.brightness, self.pwm.max_duty_cycle());
+        self.pwm.set_duty_cycle(duty)
+    }
+
+    /// Consumes the pin and returns the PWM channel.
+    ///
+    /// # Returns
+    /// * `P` - PWM channel
+    #[allow(dead_code)]
+    pub fn release(self) -> P {
+        self.pwm
+    }
+}
+
+impl<P: SetDutyCycle> ErrorType for DimmedPin<P> {
+    type Error = PwmPinError<P::Error>;
+}
+
+impl<P: SetDutyCycle> OutputPin for DimmedPin<P> {
+    /// Turns the LED off.
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.apply(LedState::Off).map_err(PwmPinError)
+    }
+
+    /// Turns the LED on at the configured brightness.
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.apply(LedState::On).map_err(PwmPinError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+
+    struct TestPwm {
+        max: u16,
+        duties: Vec<u16>,
+    }
+
+    impl TestPwm {
+        fn new(max: u16) -> Self {
+            Self {
+                max,
+                duties: Vec::new(),
+            }
+        }
+    }
+
+    impl pwm::ErrorType for TestPwm {
+        type Error = Infallible;
+    }
+
+    impl SetDutyCycle for TestPwm {
+        fn max_duty_cycle(&self) -> u16 {
+            self.max
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
+            self.duties.push(duty);
+            Ok(())
+        }
+    }
+
+    // ==================== CIE_TABLE Tests ====================
+
+    #[test]
+    fn test_table_endpoints() {
+        assert_eq!(CIE_TABLE[0], 0);
+        assert_eq!(CIE_TABLE[CIE_TABLE_SIZE - 1], u16::MAX);
+    }
+
+    #[test]
+    fn test_table_monotonic() {
+        for pair in CIE_TABLE.windows(2) {
+            assert!(pair[0] <= pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_table_half_lightness() {
+        // L* = 50 is about 18.4% luminance.
+        let mid = CIE_TABLE[128];
+        assert!((12_000..12_200).contains(&mid), "{mid}");
+    }
+
+    #[test]
+    fn test_table_linear_segment() {
+        // L* = 100 * 4 / 256 = 1.5625 lies in the linear part.
+        let expected = (1.5625 / 903.3 * 65535.0 + 0.5) as u16;
+        assert_eq!(CIE_TABLE[4], expected);
+    }
+
+    #[test]
+    fn test_table_usable_in_const_context() {
+        const TABLE: [u16; CIE_TABLE_SIZE] = build_cie_table();
+        assert_eq!(TABLE, CIE_TABLE);
+    }
+
+    // ==================== interpolate() Tests ====================
+
+    #[test]
+    fn test_interpolate_endpoints() {
+        let table = [0, 100, 1000];
+        assert_eq!(interpolate(&table, 0), 0);
+        assert_eq!(interpolate(&table, u16::MAX), 1000);
+    }
+
+    #[test]
+    fn test_interpolate_midpoints() {
+        let table = [0, 100, 1000];
+        assert_eq!(interpolate(&table, u16::MAX / 2), 100);
+        assert_eq!(interpolate(&table, u16::MAX / 4), 50);
+    }
+
+    // ==================== Brightness Tests ====================
+
+    #[test]
+    fn test_brightness_constants() {
+        assert_eq!(Brightness::OFF.level(), 0);
+        assert_eq!(Brightness::FULL.level(), u16::MAX);
+        assert_eq!(Brightness::default(), Brightness::FULL);
+    }
+
+    #[test]
+    fn test_brightness_from_percent() {
+        assert_eq!(Brightness::from_percent(0), Brightness::OFF);
+        assert_eq!(Brightness::from_percent(100), Brightness::FULL);
+        assert_eq!(Brightness::from_percent(50).level(), 32767);
+    }
+
+    #[test]
+    fn test_brightness_from_percent_clamps() {
+        assert_eq!(Brightness::from_percent(250), Brightness::FULL);
+    }
+
+    #[test]
+    fn test_brightness_percent_round_trip() {
+        for p in 0..=100 {
+            assert_eq!(Brightness::from_percent(p).percent(), p);
+        }
+    }
+
+    #[test]
+    fn test_brightness_ordering() {
+        assert!(Brightness::from_percent(10) < Brightness::from_percent(20));
+    }
+
+    // ==================== gamma_correct() Tests ====================
+
+    #[test]
+    fn test_gamma_endpoints() {
+        assert_eq!(gamma_correct(Brightness::OFF), 0);
+        assert_eq!(gamma_correct(Brightness::FULL), u16::MAX);
+    }
+
+    #[test]
+    fn test_gamma_follows_table() {
+        for (i, entry) in CIE_TABLE.iter().enumerate() {
+            let level = (i as u32 * u32::from(u16::MAX) / 256) as u16;
+            let y = gamma_correct(Brightness::new(level));
+            assert!(y.abs_diff(*entry) <= 4, "{i}: {y} vs {entry}");
+        }
+    }
+
+    #[test]
+    fn test_gamma_monotonic() {
+        let mut prev = 0;
+        for level in (0..=u16::MAX).step_by(97) {
+            let y = gamma_correct(Brightness::new(level));
+            assert!(y >= prev);
+            prev = y;
+        }
+    }
+
+    #[test]
+    fn test_gamma_below_linear() {
+        let b = Brightness::from_percent(50);
+        assert!(gamma_correct(b) < b.level());
+    }
+
+    // ==================== brightness_to_duty() Tests ====================
+
+    #[test]
+    fn test_duty_full_scale() {
+        assert_eq!(brightness_to_duty(Brightness::FULL, 1000), 1000);
+        assert_eq!(brightness_to_duty(Brightness::OFF, 1000), 0);
+    }
+
+    #[test]
+    fn test_duty_scales_to_top() {
+        let b = Brightness::from_percent(50);
+        let full = brightness_to_duty(b, u16::MAX);
+        let small = brightness_to_duty(b, 1000);
+        assert_eq!(small, ((u32::from(full) * 1000 + 32767) / 65535) as u16);
+    }
+
+    #[test]
+    fn test_duty_zero_top() {
+        assert_eq!(brightness_to_duty(Brightness::FULL, 0), 0);
+    }
+
+    // ==================== led_state_to_duty() Tests ====================
+
+    #[test]
+    fn test_state_to_duty_off() {
+        assert_eq!(led_state_to_duty(LedState::Off, Brightness::FULL, 1000), 0);
+    }
+
+    #[test]
+    fn test_state_to_duty_on_full() {
+        assert_eq!(
+            led_state_to_duty(LedState::On, Brightness::FULL, 1000),
+            1000
+        );
+    }
+
+    #[test]
+    fn test_state_to_duty_on_dimmed() {
+        let b = Brightness::from_percent(25);
+        assert_eq!(
+            led_state_to_duty(LedState::On, b, 1000),
+            brightness_to_duty(b, 1000)
+        );
+    }
+
+    // ==================== DimmedPin Tests ====================
+
+    #[test]
+    fn test_dimmed_pin_high_low() {
+        let mut pin = DimmedPin::new(TestPwm::new(1000), Brightness::FULL);
+        pin.set_high().unwrap();
+        pin.set_low().unwrap();
+        assert_eq!(pin.release().duties, vec![1000, 0]);
+    }
+
+    #[test]
+    fn test_dimmed_pin_brightness() {
+        let b = Brightness::from_percent(50);
+        let mut pin = DimmedPin::new(TestPwm::new(u16::MAX), b);
+        pin.set_high().unwrap();
+        assert_eq!(pin.release().duties, vec![gamma_correct(b)]);
+    }
+
+    #[test]
+    fn test_dimmed_pin_set_brightness() {
+        let mut pin = DimmedPin::new(TestPwm::new(1000), Brightness::FULL);
+        pin.set_brightness(Brightness::OFF);
+        assert_eq!(pin.brightness(), Brightness::OFF);
+        pin.set_high().unwrap();
+        assert_eq!(pin.release().duties, vec![0]);
+    }
+
+    #[test]
+    fn test_dimmed_pin_drives_runner() {
+        use crate::led::LedController;
+        use crate::mock::block_on;
+        use crate::runner::BlinkRunner;
+        let clock = crate::mock::VirtualClock::new();
+        let pin = DimmedPin::new(TestPwm::new(1000), Brightness::from_percent(100));
+        let mut runner = BlinkRunner::new(pin, clock.delay());
+        let mut ctrl = LedController::new();
+        for _ in 0..3 {
+            block_on(runner.step(&mut ctrl)).unwrap();
+        }
+        let (pin, _) = runner.release();
+        assert_eq!(pin.release().duties, vec![1000, 0, 1000]);
+    }
+}
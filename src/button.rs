@@ -0,0 +1,774 @@
+/*
+ * @file button.rs
+ * @brief Debounced push-button click classifier
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: button.rs
+//!
+//! DESCRIPTION:
+//! Push-Button Input for RP2350 LED Modes.
+//!
+//! BRIEF:
+//! Pure state machines that debounce raw button samples, classify
+//! single, double, long press and hold-repeat clicks, and map
+//! clicks to LED commands that cycle blink rates and patterns.
+//! Everything is driven by timestamps so it runs on the host.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::command::LedCommand;
+use crate::config::{
+    BUTTON_DEBOUNCE_MS, BUTTON_DOUBLE_CLICK_MS, BUTTON_LONG_PRESS_MS, BUTTON_RATES_MS,
+    BUTTON_REPEAT_MS, BlinkConfig,
+};
+use crate::pattern::Pattern;
+
+/// Classified button gestures.
+///
+/// # Variants
+/// * `Single` - One short press, reported once the double-click window closes
+/// * `Double` - Two short presses within the double-click window
+/// * `LongPress` - Press held past the long-press time
+/// * `Repeat` - Repeats every repeat interval while a long press is held
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ButtonEvent {
+    Single,
+    Double,
+    LongPress,
+    Repeat,
+}
+
+/// Button timing parameters.
+///
+/// # Fields
+/// * `debounce_ms` - Time a level must hold before it is accepted
+/// * `double_click_ms` - Window after a release for a second press
+/// * `long_press_ms` - Hold time before a long press
+/// * `repeat_ms` - Interval between repeats while held
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ButtonTiming {
+    pub debounce_ms: u64,
+    pub double_click_ms: u64,
+    pub long_press_ms: u64,
+    pub repeat_ms: u64,
+}
+
+impl Default for ButtonTiming {
+    /// Returns the timing from config.rs.
+    ///
+    /// # Returns
+    /// * `Self` - Default button timing
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self {
+            debounce_ms: BUTTON_DEBOUNCE_MS,
+            double_click_ms: BUTTON_DOUBLE_CLICK_MS,
+            long_press_ms: BUTTON_LONG_PRESS_MS,
+            repeat_ms: BUTTON_REPEAT_MS,
+        }
+    }
+}
+
+/// Debounces a raw input level.
+///
+/// # Details
+/// A new level is accepted once it has been sampled unchanged
+/// for the debounce time. Any bounce restarts the wait.
+///
+/// # Fields
+/// * `stable` - Accepted level
+/// * `raw` - Last sampled level
+/// * `changed_ms` - Time the raw level last changed
+/// * `debounce_ms` - Required hold time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Debouncer {
+    stable: bool,
+    raw: bool,
+    changed_ms: u64,
+    debounce_ms: u64,
+}
+
+impl Debouncer {
+    /// Creates a debouncer starting released.
+    ///
+    /// # Arguments
+    /// * `debounce_ms` - Required hold time
+    ///
+    /// # Returns
+    /// * `Self` - New Debouncer instance
+    #[allow(dead_code)]
+    pub const fn new(debounce_ms: u64) -> Self {
+        Self {
+            stable: false,
+            raw: false,
+            changed_ms: 0,
+            debounce_ms,
+        }
+    }
+
+    /// Feeds one sample.
+    ///
+    /// # Arguments
+    /// * `pressed` - Raw level, true when pressed
+    /// * `now_ms` - Sample time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<bool>` - New stable level when it changes
+    #[allow(dead_code)]
+    pub fn update(&mut self, pressed: bool, now_ms: u64) -> Option<bool> {
+        if pressed != self.raw {
+            self.raw = pressed;
+            self.changed_ms = now_ms;
+        }
+        if self.raw != self.stable && now_ms.saturating_sub(self.changed_ms) >= self.debounce_ms {
+            self.stable = self.raw;
+            return Some(self.stable);
+        }
+        None
+    }
+
+    /// Returns the accepted level.
+    ///
+    /// # Returns
+    /// * `bool` - True when pressed
+    #[allow(dead_code)]
+    pub fn is_pressed(&self) -> bool {
+        self.stable
+    }
+
+    /// Returns when a pending level change will be accepted.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Time in milliseconds, None when settled
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        (self.raw != self.stable).then_some(self.changed_ms + self.debounce_ms)
+    }
+}
+
+/// Classifier state.
+///
+/// # Variants
+/// * `Idle` - Released, nothing pending
+/// * `Pressed` - First press held since `since_ms`
+/// * `Released` - First press released at `at_ms`, waiting for a second
+/// * `SecondPress` - Second press held, a double click on release
+/// * `Holding` - Long press held, next repeat due at `next_ms`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Click {
+    Idle,
+    Pressed { since_ms: u64 },
+    Released { at_ms: u64 },
+    SecondPress,
+    Holding { next_ms: u64 },
+}
+
+/// Classifies debounced press and release edges.
+///
+/// # Fields
+/// * `state` - Current classifier state
+/// * `timing` - Button timing parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ClickClassifier {
+    state: Click,
+    timing: ButtonTiming,
+}
+
+impl ClickClassifier {
+    /// Creates an idle classifier.
+    ///
+    /// # Arguments
+    /// * `timing` - Button timing parameters
+    ///
+    /// # Returns
+    /// * `Self` - New ClickClassifier instance
+    #[allow(dead_code)]
+    pub const fn new(timing: ButtonTiming) -> Self {
+        Self {
+            state: Click::Idle,
+            timing,
+        }
+    }
+
+    /// Handles a debounced edge.
+    ///
+    /// # Arguments
+    /// * `pressed` - New level, true when pressed
+    /// * `now_ms` - Edge time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<ButtonEvent>` - Double click on the second release
+    #[allow(dead_code)]
+    pub fn on_edge(&mut self, pressed: bool, now_ms: u64) -> Option<ButtonEvent> {
+        let (state, event) = match (self.state, pressed) {
+            (Click::Idle, true) => (Click::Pressed { since_ms: now_ms }, None),
+            (Click::Pressed { .. }, false) => (Click::Released { at_ms: now_ms }, None),
+            (Click::Released { .. }, true) => (Click::SecondPress, None),
+            (Click::SecondPress, false) => (Click::Idle, Some(ButtonEvent::Double)),
+            (Click::Holding { .. }, false) => (Click::Idle, None),
+            (state, _) => (state, None),
+        };
+        self.state = state;
+        event
+    }
+
+    /// Handles the passage of time.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<ButtonEvent>` - Single, long press or repeat when due
+    #[allow(dead_code)]
+    pub fn on_tick(&mut self, now_ms: u64) -> Option<ButtonEvent> {
+        if self.next_deadline().is_none_or(|due| now_ms < due) {
+            return None;
+        }
+        let (state, event) = match self.state {
+            Click::Pressed { since_ms } => (
+                Click::Holding {
+                    next_ms: since_ms + self.timing.long_press_ms + self.timing.repeat_ms,
+                },
+                ButtonEvent::LongPress,
+            ),
+            Click::Holding { next_ms } => (
+                Click::Holding {
+                    next_ms: next_ms + self.timing.repeat_ms,
+                },
+                ButtonEvent::Repeat,
+            ),
+            _ => (Click::Idle, ButtonEvent::Single),
+        };
+        self.state = state;
+        Some(event)
+    }
+
+    /// Returns when the next timed event is due.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Time in milliseconds, None when nothing is pending
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        match self.state {
+            Click::Pressed { since_ms } => Some(since_ms + self.timing.long_press_ms),
+            Click::Released { at_ms } => Some(at_ms + self.timing.double_click_ms),
+            Click::Holding { next_ms } => Some(next_ms),
+            Click::Idle | Click::SecondPress => None,
+        }
+    }
+}
+
+/// Debounced button with click classification.
+///
+/// # Fields
+/// * `debouncer` - Raw level debouncer
+/// * `classifier` - Click classifier fed by debounced edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Button {
+    debouncer: Debouncer,
+    classifier: ClickClassifier,
+}
+
+impl Button {
+    /// Creates a released button.
+    ///
+    /// # Arguments
+    /// * `timing` - Button timing parameters
+    ///
+    /// # Returns
+    /// * `Self` - New Button instance
+    #[allow(dead_code)]
+    pub const fn new(timing: ButtonTiming) -> Self {
+        Self {
+            debouncer: Debouncer::new(timing.debounce_ms),
+            classifier: ClickClassifier::new(timing),
+        }
+    }
+
+    /// Feeds one raw sample.
+    ///
+    /// # Details
+    /// Call on every input edge and whenever `next_deadline`
+    /// passes. Timeouts are handled before the sample's edge, so
+    /// a press after the double-click window starts a new click.
+    ///
+    /// # Arguments
+    /// * `pressed` - Raw level, true when pressed
+    /// * `now_ms` - Sample time in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<ButtonEvent>` - Classified event, if any
+    #[allow(dead_code)]
+    pub fn update(&mut self, pressed: bool, now_ms: u64) -> Option<ButtonEvent> {
+        let timed = self.classifier.on_tick(now_ms);
+        let edge = self
+            .debouncer
+            .update(pressed, now_ms)
+            .and_then(|level| self.classifier.on_edge(level, now_ms));
+        timed.or(edge)
+    }
+
+    /// Returns whether the debounced button is pressed.
+    ///
+    /// # Returns
+    /// * `bool` - True when pressed
+    #[allow(dead_code)]
+    pub fn is_pressed(&self) -> bool {
+        self.debouncer.is_pressed()
+    }
+
+    /// Returns when `update` must next be called without an edge.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Time in milliseconds, None when idle
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        match (
+            self.debouncer.next_deadline(),
+            self.classifier.next_deadline(),
+        ) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Patterns a double click cycles through after the plain blink.
+const MODE_PATTERNS: [Pattern; 3] = [
+    Pattern::heartbeat(),
+    Pattern::double_blink(),
+    Pattern::sos(),
+];
+
+/// Maps button events to LED mode changes.
+///
+/// # Details
+/// A single click steps to the next rate in `BUTTON_RATES_MS`
+/// and returns to the plain blink. A double click steps through
+/// the plain blink and `MODE_PATTERNS`. A long press toggles
+/// standby, which turns the LED off and ignores clicks until the
+/// next long press.
+///
+/// # Fields
+/// * `rate` - Index into `BUTTON_RATES_MS`
+/// * `pattern` - 0 for the plain blink, else 1 + index into `MODE_PATTERNS`
+/// * `standby` - Whether the LED is in standby
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ModeCycler {
+    rate: usize,
+    pattern: usize,
+    standby: bool,
+}
+
+impl ModeCycler {
+    /// Creates a cycler at the first rate with the plain blink.
+    ///
+    /// # Returns
+    /// * `Self` - New ModeCycler instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            rate: 0,
+            pattern: 0,
+            standby: false,
+        }
+    }
+
+    /// Applies a button event.
+    ///
+    /// # Arguments
+    /// * `event` - Classified button event
+    ///
+    /// # Returns
+    /// * `Option<LedCommand>` - Command for the LED task, if any
+    #[allow(dead_code)]
+    pub fn apply(&mut self, event: ButtonEvent) -> Option<LedCommand> {
+        match event {
+            ButtonEvent::LongPress => {
+                self.standby = !self.standby;
+                Some(if self.standby {
+                    LedCommand::ForceOff
+                } else {
+                    LedCommand::Resume
+                })
+            }
+            _ if self.standby => None,
+            ButtonEvent::Single => {
+                self.rate = (self.rate + 1) % BUTTON_RATES_MS.len();
+                self.pattern = 0;
+                Some(LedCommand::SetRate(self.blink()))
+            }
+            ButtonEvent::Double => {
+                self.pattern = (self.pattern + 1) % (MODE_PATTERNS.len() + 1);
+                Some(match self.pattern {
+                    0 => LedCommand::SetRate(self.blink()),
+                    n => LedCommand::SetPattern(MODE_PATTERNS[n - 1]),
+                })
+            }
+            ButtonEvent::Repeat => None,
+        }
+    }
+
+    /// Returns the current blink delay.
+    ///
+    /// # Returns
+    /// * `u64` - Delay in milliseconds
+    #[allow(dead_code)]
+    pub fn rate_ms(&self) -> u64 {
+        BUTTON_RATES_MS[self.rate]
+    }
+
+    /// Returns whether standby is active.
+    ///
+    /// # Returns
+    /// * `bool` - True in standby
+    #[allow(dead_code)]
+    pub fn is_standby(&self) -> bool {
+        self.standby
+    }
+
+    /// Returns the plain blink at the current rate.
+    ///
+    /// # Returns
+    /// * `BlinkConfig` - Symmetric blink configuration
+    fn blink(&self) -> BlinkConfig {
+        let rate = self.rate_ms();
+        BlinkConfig::new(rate, rate).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMING: ButtonTiming = ButtonTiming {
+        debounce_ms: 20,
+        double_click_ms: 300,
+        long_press_ms: 800,
+        repeat_ms: 200,
+    };
+
+    /// Feeds a raw level sampled every millisecond over a time span.
+    fn hold(button: &mut Button, pressed: bool, from: u64, to: u64) -> Vec<(u64, ButtonEvent)> {
+        (from..to)
+            .filter_map(|t| button.update(pressed, t).map(|e| (t, e)))
+            .collect()
+    }
+
+    /// Feeds a bouncy edge: alternating levels for `bounce` ms.
+    fn bounce(button: &mut Button, pressed: bool, at: u64, bounce: u64) {
+        for t in at..at + bounce {
+            button.update((t - at).is_multiple_of(2) == pressed, t);
+        }
+    }
+
+    // ==================== Debouncer Tests ====================
+
+    #[test]
+    fn test_debouncer_starts_released() {
+        let debouncer = Debouncer::new(20);
+        assert!(!debouncer.is_pressed());
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_debouncer_accepts_after_hold() {
+        let mut debouncer = Debouncer::new(20);
+        assert_eq!(debouncer.update(true, 100), None);
+        assert_eq!(debouncer.next_deadline(), Some(120));
+        assert_eq!(debouncer.update(true, 119), None);
+        assert_eq!(debouncer.update(true, 120), Some(true));
+        assert!(debouncer.is_pressed());
+    }
+
+    #[test]
+    fn test_debouncer_bounce_restarts_wait() {
+        let mut debouncer = Debouncer::new(20);
+        debouncer.update(true, 100);
+        debouncer.update(false, 110);
+        debouncer.update(true, 115);
+        assert_eq!(debouncer.update(true, 130), None);
+        assert_eq!(debouncer.update(true, 135), Some(true));
+    }
+
+    #[test]
+    fn test_debouncer_ignores_glitch() {
+        let mut debouncer = Debouncer::new(20);
+        debouncer.update(true, 100);
+        debouncer.update(false, 105);
+        assert_eq!(debouncer.next_deadline(), None);
+        assert_eq!(debouncer.update(false, 200), None);
+        assert!(!debouncer.is_pressed());
+    }
+
+    #[test]
+    fn test_debouncer_accepts_on_late_sample() {
+        let mut debouncer = Debouncer::new(20);
+        debouncer.update(true, 100);
+        assert_eq!(debouncer.update(true, 500), Some(true));
+    }
+
+    // ==================== ClickClassifier Tests ====================
+
+    #[test]
+    fn test_classifier_single() {
+        let mut classifier = ClickClassifier::new(TIMING);
+        assert_eq!(classifier.on_edge(true, 0), None);
+        assert_eq!(classifier.on_edge(false, 100), None);
+        assert_eq!(classifier.next_deadline(), Some(400));
+        assert_eq!(classifier.on_tick(399), None);
+        assert_eq!(classifier.on_tick(400), Some(ButtonEvent::Single));
+        assert_eq!(classifier.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_classifier_double() {
+        let mut classifier = ClickClassifier::new(TIMING);
+        classifier.on_edge(true, 0);
+        classifier.on_edge(false, 100);
+        classifier.on_edge(true, 250);
+        assert_eq!(classifier.next_deadline(), None);
+        assert_eq!(classifier.on_edge(false, 350), Some(ButtonEvent::Double));
+        assert_eq!(classifier.on_tick(1000), None);
+    }
+
+    #[test]
+    fn test_classifier_long_press_and_repeat() {
+        let mut classifier = ClickClassifier::new(TIMING);
+        classifier.on_edge(true, 0);
+        assert_eq!(classifier.on_tick(799), None);
+        assert_eq!(classifier.on_tick(800), Some(ButtonEvent::LongPress));
+        assert_eq!(classifier.on_tick(999), None);
+        assert_eq!(classifier.on_tick(1000), Some(ButtonEvent::Repeat));
+        assert_eq!(classifier.on_tick(1200), Some(ButtonEvent::Repeat));
+        assert_eq!(classifier.on_edge(false, 1300), None);
+        assert_eq!(classifier.on_tick(5000), None);
+    }
+
+    #[test]
+    fn test_classifier_release_before_long_press() {
+        let mut classifier = ClickClassifier::new(TIMING);
+        classifier.on_edge(true, 0);
+        classifier.on_edge(false, 799);
+        assert_eq!(classifier.on_tick(1098), None);
+        assert_eq!(classifier.on_tick(1099), Some(ButtonEvent::Single));
+    }
+
+    #[test]
+    fn test_classifier_ignores_repeated_edges() {
+        let mut classifier = ClickClassifier::new(TIMING);
+        classifier.on_edge(true, 0);
+        assert_eq!(classifier.on_edge(true, 10), None);
+        assert_eq!(classifier.next_deadline(), Some(800));
+    }
+
+    // ==================== Button Tests ====================
+
+    #[test]
+    fn test_button_clean_single_click() {
+        let mut button = Button::new(TIMING);
+        let mut events = hold(&mut button, true, 0, 100);
+        events.extend(hold(&mut button, false, 100, 1000));
+        assert_eq!(events, vec![(420, ButtonEvent::Single)]);
+    }
+
+    #[test]
+    fn test_button_bouncy_single_click() {
+        let mut button = Button::new(TIMING);
+        bounce(&mut button, true, 0, 8);
+        let mut events = hold(&mut button, true, 8, 100);
+        bounce(&mut button, false, 100, 8);
+        events.extend(hold(&mut button, false, 108, 1000));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1, ButtonEvent::Single);
+    }
+
+    #[test]
+    fn test_button_double_click() {
+        let mut button = Button::new(TIMING);
+        let mut events = hold(&mut button, true, 0, 80);
+        events.extend(hold(&mut button, false, 80, 200));
+        events.extend(hold(&mut button, true, 200, 280));
+        events.extend(hold(&mut button, false, 280, 1000));
+        assert_eq!(events, vec![(300, ButtonEvent::Double)]);
+    }
+
+    #[test]
+    fn test_button_slow_clicks_are_singles() {
+        let mut button = Button::new(TIMING);
+        let mut events = hold(&mut button, true, 0, 80);
+        events.extend(hold(&mut button, false, 80, 600));
+        events.extend(hold(&mut button, true, 600, 680));
+        events.extend(hold(&mut button, false, 680, 1200));
+        let kinds: Vec<ButtonEvent> = events.iter().map(|&(_, e)| e).collect();
+        assert_eq!(kinds, vec![ButtonEvent::Single, ButtonEvent::Single]);
+    }
+
+    #[test]
+    fn test_button_hold_repeats() {
+        let mut button = Button::new(TIMING);
+        let mut events = hold(&mut button, true, 0, 1500);
+        events.extend(hold(&mut button, false, 1500, 2500));
+        assert_eq!(
+            events,
+            vec![
+                (820, ButtonEvent::LongPress),
+                (1020, ButtonEvent::Repeat),
+                (1220, ButtonEvent::Repeat),
+                (1420, ButtonEvent::Repeat),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_button_glitch_is_ignored() {
+        let mut button = Button::new(TIMING);
+        button.update(true, 0);
+        let events = hold(&mut button, false, 5, 1000);
+        assert!(events.is_empty());
+        assert!(!button.is_pressed());
+    }
+
+    #[test]
+    fn test_button_deadline_tracks_pending_work() {
+        let mut button = Button::new(TIMING);
+        assert_eq!(button.next_deadline(), None);
+        button.update(true, 0);
+        assert_eq!(button.next_deadline(), Some(20));
+        button.update(true, 20);
+        assert_eq!(button.next_deadline(), Some(820));
+    }
+
+    #[test]
+    fn test_button_driven_by_deadlines_only() {
+        let mut button = Button::new(TIMING);
+        let mut events = Vec::new();
+        button.update(true, 0);
+        while let Some(due) = button.next_deadline() {
+            if due > 1000 {
+                break;
+            }
+            events.extend(button.update(true, due));
+        }
+        assert_eq!(events, vec![ButtonEvent::LongPress]);
+    }
+
+    // ==================== ModeCycler Tests ====================
+
+    #[test]
+    fn test_cycler_single_steps_rate() {
+        let mut modes = ModeCycler::new();
+        let command = modes.apply(ButtonEvent::Single);
+        let rate = BUTTON_RATES_MS[1];
+        assert_eq!(
+            command,
+            Some(LedCommand::SetRate(BlinkConfig::new(rate, rate).unwrap()))
+        );
+        assert_eq!(modes.rate_ms(), rate);
+    }
+
+    #[test]
+    fn test_cycler_rate_wraps() {
+        let mut modes = ModeCycler::new();
+        for _ in 0..BUTTON_RATES_MS.len() {
+            modes.apply(ButtonEvent::Single);
+        }
+        assert_eq!(modes.rate_ms(), BUTTON_RATES_MS[0]);
+    }
+
+    #[test]
+    fn test_cycler_double_steps_patterns() {
+        let mut modes = ModeCycler::new();
+        assert_eq!(
+            modes.apply(ButtonEvent::Double),
+            Some(LedCommand::SetPattern(Pattern::heartbeat()))
+        );
+        assert_eq!(
+            modes.apply(ButtonEvent::Double),
+            Some(LedCommand::SetPattern(Pattern::double_blink()))
+        );
+        assert_eq!(
+            modes.apply(ButtonEvent::Double),
+            Some(LedCommand::SetPattern(Pattern::sos()))
+        );
+        let rate = BUTTON_RATES_MS[0];
+        assert_eq!(
+            modes.apply(ButtonEvent::Double),
+            Some(LedCommand::SetRate(BlinkConfig::new(rate, rate).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_cycler_single_returns_to_blink() {
+        let mut modes = ModeCycler::new();
+        modes.apply(ButtonEvent::Double);
+        modes.apply(ButtonEvent::Single);
+        assert_eq!(
+            modes.apply(ButtonEvent::Double),
+            Some(LedCommand::SetPattern(Pattern::heartbeat()))
+        );
+    }
+
+    #[test]
+    fn test_cycler_long_press_toggles_standby() {
+        let mut modes = ModeCycler::new();
+        assert_eq!(
+            modes.apply(ButtonEvent::LongPress),
+            Some(LedCommand::ForceOff)
+        );
+        assert!(modes.is_standby());
+        assert_eq!(
+            modes.apply(ButtonEvent::LongPress),
+            Some(LedCommand::Resume)
+        );
+        assert!(!modes.is_standby());
+    }
+
+    #[test]
+    fn test_cycler_standby_ignores_clicks() {
+        let mut modes = ModeCycler::new();
+        modes.apply(ButtonEvent::LongPress);
+        assert_eq!(modes.apply(ButtonEvent::Single), None);
+        assert_eq!(modes.apply(ButtonEvent::Double), None);
+        assert_eq!(modes.rate_ms(), BUTTON_RATES_MS[0]);
+    }
+
+    #[test]
+    fn test_cycler_repeat_changes_nothing() {
+        let mut modes = ModeCycler::new();
+        assert_eq!(modes.apply(ButtonEvent::Repeat), None);
+        assert_eq!(modes, ModeCycler::new());
+    }
+}
@@ -0,0 +1,691 @@
+/*
+ * @file codec.rs
+ * @brief Compact binary pattern format
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: codec.rs
+//!
+//! DESCRIPTION:
+//! Binary Pattern Format for RP2350 Storage and Transfer.
+//!
+//! BRIEF:
+//! Versioned, compact encoding of a Pattern shared by flash
+//! storage, the serial link and host tools. Identical consecutive
+//! segments are run-length encoded, durations are LEB128 varints
+//! and the whole record is protected by a CRC-32.
+//!
+//! LAYOUT (version 1, multi-byte fields little-endian):
+//!   0  magic "LP"          2 bytes
+//!   2  version             1 byte
+//!   3  end action          1 byte (0 loop, 1 hold, 2 stop)
+//!   4  repeat count        2 bytes
+//!   6  segment count       1 byte
+//!   7  runs                per run: control byte (bit 7 = ON,
+//!                          bits 0-6 = run length - 1) then the
+//!                          duration in ms as a LEB128 varint
+//!   n  CRC-32              4 bytes over bytes 0..n
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::crc::crc32;
+use crate::led::LedState;
+use crate::pattern::{EndAction, MAX_SEGMENTS, Pattern, PatternError, Segment};
+
+/// Record magic bytes.
+///
+/// # Value
+/// "LP" (LED Pattern)
+#[allow(dead_code)]
+pub const MAGIC: [u8; 2] = *b"LP";
+
+/// Current format version.
+///
+/// # Value
+/// 1
+#[allow(dead_code)]
+pub const VERSION: u8 = 1;
+
+/// Size of the fixed header.
+///
+/// # Value
+/// 7 bytes
+#[allow(dead_code)]
+pub const HEADER_LEN: usize = 7;
+
+/// Size of the CRC trailer.
+///
+/// # Value
+/// 4 bytes
+#[allow(dead_code)]
+pub const CRC_LEN: usize = 4;
+
+/// Longest LEB128 encoding of a u64.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Longest segment run one control byte can describe.
+const MAX_RUN: usize = 128;
+
+/// Largest possible encoded pattern.
+///
+/// # Details
+/// Every segment in its own run with a full-width u64 varint.
+/// Buffers of this size always fit any pattern.
+///
+/// # Value
+/// 715 bytes
+#[allow(dead_code)]
+pub const MAX_ENCODED_LEN: usize = HEADER_LEN + MAX_SEGMENTS * (1 + MAX_VARINT_LEN) + CRC_LEN;
+
+/// Encoding and decoding errors.
+///
+/// # Variants
+/// * `BufferTooSmall` - Output buffer cannot hold the record
+/// * `Truncated` - Input ends before the record does
+/// * `BadMagic` - Input does not start with `MAGIC`
+/// * `UnsupportedVersion` - Record from an unknown format version
+/// * `BadCrc` - Checksum mismatch
+/// * `InvalidEndAction` - Unknown end action code
+/// * `InvalidSegments` - Count or runs do not describe a valid pattern
+/// * `Overflow` - Duration exceeds `MAX_SEGMENT_MS` or 64 bits
+/// * `TrailingBytes` - Extra bytes after the CRC
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum CodecError {
+    BufferTooSmall,
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u8),
+    BadCrc,
+    InvalidEndAction(u8),
+    InvalidSegments,
+    Overflow,
+    TrailingBytes,
+}
+
+/// Returns the encoded size of a pattern.
+///
+/// # Arguments
+/// * `pattern` - Pattern to measure
+///
+/// # Returns
+/// * `usize` - Bytes `encode` will write
+#[allow(dead_code)]
+pub fn encoded_len(pattern: &Pattern) -> usize {
+    let body: usize = runs(pattern.segments())
+        .map(|(segment, _)| 1 + varint_len(segment.duration_ms))
+        .sum();
+    HEADER_LEN + body + CRC_LEN
+}
+
+/// Encodes a pattern.
+///
+/// # Details
+/// An empty pattern is rejected, since `decode` refuses a zero
+/// segment count and the bytes could never be read back.
+///
+/// # Arguments
+/// * `pattern` - Pattern to encode
+/// * `out` - Output buffer, `MAX_ENCODED_LEN` always suffices
+///
+/// # Returns
+/// * `Result<usize, CodecError>` - Bytes written, `InvalidSegments` or `BufferTooSmall`
+#[allow(dead_code)]
+pub fn encode(pattern: &Pattern, out: &mut [u8]) -> Result<usize, CodecError> {
+    if pattern.is_empty() {
+        return Err(CodecError::InvalidSegments);
+    }
+    let len = encoded_len(pattern);
+    if out.len() < len {
+        return Err(CodecError::BufferTooSmall);
+    }
+    out[..2].copy_from_slice(&MAGIC);
+    out[2] = VERSION;
+    out[3] = end_action_code(pattern.end_action());
+    out[4..6].copy_from_slice(&pattern.repeat().to_le_bytes());
+    out[6] = pattern.len() as u8;
+    let mut pos = HEADER_LEN;
+    for (segment, count) in runs(pattern.segments()) {
+        let on = if segment.state == LedState::On {
+            0x80
+        } else {
+            0
+        };
+        out[pos] = on | (count - 1) as u8;
+        pos += 1;
+        pos += write_varint(segment.duration_ms, &mut out[pos..]);
+    }
+    let crc = crc32(&out[..pos]);
+    out[pos..pos + CRC_LEN].copy_from_slice(&crc.to_le_bytes());
+    Ok(len)
+}
+
+/// Decodes a pattern.
+///
+/// # Details
+/// The input must be exactly one record. The CRC is checked
+/// before any field is interpreted.
+///
+/// # Arguments
+/// * `bytes` - Encoded record
+///
+/// # Returns
+/// * `Result<Pattern, CodecError>` - Pattern or decoding error
+#[allow(dead_code)]
+pub fn decode(bytes: &[u8]) -> Result<Pattern, CodecError> {
+    if bytes.len() < 2 {
+        return Err(CodecError::Truncated);
+    }
+    if bytes[..2] != MAGIC {
+        return Err(CodecError::BadMagic);
+    }
+    if bytes.len() < HEADER_LEN + CRC_LEN {
+        return Err(CodecError::Truncated);
+    }
+    if bytes[2] != VERSION {
+        return Err(CodecError::UnsupportedVersion(bytes[2]));
+    }
+    let (body, trailer) = bytes.split_at(bytes.len() - CRC_LEN);
+    let mut crc = [0u8; CRC_LEN];
+    crc.copy_from_slice(trailer);
+    if crc32(body) != u32::from_le_bytes(crc) {
+        return Err(CodecError::BadCrc);
+    }
+    let end = end_action_from_code(body[3])?;
+    let repeat = u16::from_le_bytes([body[4], body[5]]);
+    let count = usize::from(body[6]);
+    if count == 0 {
+        return Err(CodecError::InvalidSegments);
+    }
+    let mut pattern = Pattern::new().with_repeat(repeat).with_end_action(end);
+    let mut pos = HEADER_LEN;
+    while pattern.len() < count {
+        let control = *body.get(pos).ok_or(CodecError::Truncated)?;
+        pos += 1;
+        let (duration_ms, used) = read_varint(&body[pos..])?;
+        pos += used;
+        let state = if control & 0x80 != 0 {
+            LedState::On
+        } else {
+            LedState::Off
+        };
+        for _ in 0..=(control & 0x7F) {
+            if pattern.len() == count {
+                return Err(CodecError::InvalidSegments);
+            }
+            pattern
+                .push(Segment { state, duration_ms })
+                .map_err(|e| match e {
+                    PatternError::DurationTooLong => CodecError::Overflow,
+                    _ => CodecError::InvalidSegments,
+                })?;
+        }
+    }
+    if pos != body.len() {
+        return Err(CodecError::TrailingBytes);
+    }
+    Ok(pattern)
+}
+
+/// Groups identical consecutive segments into runs.
+///
+/// # Arguments
+/// * `segments` - Segments in play order
+///
+/// # Returns
+/// * `impl Iterator<Item = (Segment, usize)>` - Segment and run length, at most `MAX_RUN`
+fn runs(segments: &[Segment]) -> impl Iterator<Item = (Segment, usize)> + '_ {
+    let mut rest = segments;
+    core::iter::from_fn(move || {
+        let first = *rest.first()?;
+        let count = rest
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|s| **s == first)
+            .count();
+        rest = &rest[count..];
+        Some((first, count))
+    })
+}
+
+/// Returns the LEB128 length of a value.
+///
+/// # Arguments
+/// * `value` - Value to measure
+///
+/// # Returns
+/// * `usize` - Encoded bytes, 1-10
+fn varint_len(value: u64) -> usize {
+    let bits = 64 - value.leading_zeros() as usize;
+    bits.div_ceil(7).max(1)
+}
+
+/// Writes a LEB128 varint.
+///
+/// # Arguments
+/// * `value` - Value to write
+/// * `out` - Buffer with at least `varint_len(value)` bytes
+///
+/// # Returns
+/// * `usize` - Bytes written
+fn write_varint(mut value: u64, out: &mut [u8]) -> usize {
+    let mut i = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[i] = byte;
+            return i + 1;
+        }
+        out[i] = byte | 0x80;
+        i += 1;
+    }
+}
+
+/// Reads a LEB128 varint.
+///
+/// # Arguments
+/// * `bytes` - Input starting at the varint
+///
+/// # Returns
+/// * `Result<(u64, usize), CodecError>` - Value and bytes read
+fn read_varint(bytes: &[u8]) -> Result<(u64, usize), CodecError> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate() {
+        let bits = u64::from(byte & 0x7F);
+        let shift = 7 * i as u32;
+        if shift >= 64 || (shift > 0 && bits >> (64 - shift) != 0) {
+            return Err(CodecError::Overflow);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(CodecError::Truncated)
+}
+
+/// Maps an end action to its wire code.
+///
+/// # Arguments
+/// * `end` - End action
+///
+/// # Returns
+/// * `u8` - Wire code
+fn end_action_code(end: EndAction) -> u8 {
+    match end {
+        EndAction::Loop => 0,
+        EndAction::Hold => 1,
+        EndAction::Stop => 2,
+    }
+}
+
+/// Maps a wire code to its end action.
+///
+/// # Arguments
+/// * `code` - Wire code
+///
+/// # Returns
+/// * `Result<EndAction, CodecError>` - End action or `InvalidEndAction`
+fn end_action_from_code(code: u8) -> Result<EndAction, CodecError> {
+    match code {
+        0 => Ok(EndAction::Loop),
+        1 => Ok(EndAction::Hold),
+        2 => Ok(EndAction::Stop),
+        other => Err(CodecError::InvalidEndAction(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morse::{self, MorseConfig};
+    use crate::pattern::MAX_SEGMENT_MS;
+
+    fn round_trip(pattern: &Pattern) -> Pattern {
+        let mut buf = [0u8; MAX_ENCODED_LEN];
+        let len = encode(pattern, &mut buf).unwrap();
+        assert_eq!(len, encoded_len(pattern));
+        decode(&buf[..len]).unwrap()
+    }
+
+    fn encoded(pattern: &Pattern) -> Vec<u8> {
+        let mut buf = [0u8; MAX_ENCODED_LEN];
+        let len = encode(pattern, &mut buf).unwrap();
+        buf[..len].to_vec()
+    }
+
+    fn reseal(bytes: &mut [u8]) {
+        let n = bytes.len() - CRC_LEN;
+        let crc = crc32(&bytes[..n]);
+        bytes[n..].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    // ==================== Round-Trip Tests ====================
+
+    #[test]
+    fn test_round_trip_presets() {
+        for p in [
+            Pattern::blink(500),
+            Pattern::on_off(1, 9999),
+            Pattern::heartbeat(),
+            Pattern::double_blink(),
+            Pattern::sos(),
+        ] {
+            assert_eq!(round_trip(&p), p);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_end_actions_and_repeat() {
+        for end in [EndAction::Loop, EndAction::Hold, EndAction::Stop] {
+            let p = Pattern::heartbeat().with_repeat(65535).with_end_action(end);
+            assert_eq!(round_trip(&p), p);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_extreme_durations() {
+        let p = Pattern::from_segments(&[
+            Segment::on(1),
+            Segment::off(127),
+            Segment::on(128),
+            Segment::off(16_384),
+            Segment::on(MAX_SEGMENT_MS),
+        ])
+        .unwrap();
+        assert_eq!(round_trip(&p), p);
+    }
+
+    #[test]
+    fn test_round_trip_full_pattern() {
+        let segments: Vec<Segment> = (0..MAX_SEGMENTS as u64)
+            .map(|i| {
+                if i % 2 == 0 {
+                    Segment::on(i + 1)
+                } else {
+                    Segment::off(MAX_SEGMENT_MS - i)
+                }
+            })
+            .collect();
+        let p = Pattern::from_segments(&segments).unwrap();
+        assert_eq!(round_trip(&p), p);
+    }
+
+    #[test]
+    fn test_round_trip_morse() {
+        let p = morse::encode("CQ DE PICO", MorseConfig::default()).unwrap();
+        assert_eq!(round_trip(&p), p);
+    }
+
+    #[test]
+    fn test_round_trip_pseudo_random() {
+        let mut seed = 0x1234_5678u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        for _ in 0..200 {
+            let mut p = Pattern::new().with_repeat((next() % 5) as u16);
+            for _ in 0..1 + next() % MAX_SEGMENTS as u32 {
+                let state = if next() % 2 == 0 {
+                    LedState::On
+                } else {
+                    LedState::Off
+                };
+                let duration_ms = u64::from((next() >> (next() % 32)).max(1));
+                p.push(Segment { state, duration_ms }).unwrap();
+            }
+            assert_eq!(round_trip(&p), p);
+        }
+    }
+
+    // ==================== Encoding Tests ====================
+
+    #[test]
+    fn test_encode_layout() {
+        let bytes = encoded(&Pattern::on_off(100, 300).with_repeat(2));
+        assert_eq!(&bytes[..7], &[b'L', b'P', 1, 0, 2, 0, 2]);
+        assert_eq!(&bytes[7..12], &[0x80, 100, 0x00, 0xAC, 0x02]);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(&bytes[12..], &crc32(&bytes[..12]).to_le_bytes());
+    }
+
+    #[test]
+    fn test_encode_run_length() {
+        let p = Pattern::from_segments(&[Segment::off(50); 10]).unwrap();
+        let bytes = encoded(&p);
+        assert_eq!(&bytes[7..9], &[0x09, 50]);
+        assert_eq!(bytes.len(), HEADER_LEN + 2 + CRC_LEN);
+    }
+
+    #[test]
+    fn test_encode_sos_is_compact() {
+        // Under half of a raw state byte plus u64 per segment.
+        let p = Pattern::sos();
+        assert!(encoded_len(&p) < p.len() * 9 / 2);
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let p = Pattern::heartbeat();
+        let mut buf = vec![0u8; encoded_len(&p) - 1];
+        assert_eq!(encode(&p, &mut buf), Err(CodecError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_encode_rejects_empty() {
+        let mut buf = [0u8; MAX_ENCODED_LEN];
+        assert_eq!(
+            encode(&Pattern::new(), &mut buf),
+            Err(CodecError::InvalidSegments)
+        );
+    }
+
+    #[test]
+    fn test_max_encoded_len_bounds_worst_case() {
+        let p = Pattern::from_segments(
+            &(0..MAX_SEGMENTS)
+                .map(|i| {
+                    if i % 2 == 0 {
+                        Segment::on(MAX_SEGMENT_MS)
+                    } else {
+                        Segment::off(MAX_SEGMENT_MS)
+                    }
+                })
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert!(encoded_len(&p) <= MAX_ENCODED_LEN);
+        let widest = HEADER_LEN + MAX_SEGMENTS * (1 + varint_len(u64::MAX)) + CRC_LEN;
+        assert_eq!(widest, MAX_ENCODED_LEN);
+    }
+
+    // ==================== Varint Tests ====================
+
+    #[test]
+    fn test_varint_lengths() {
+        assert_eq!(varint_len(0), 1);
+        assert_eq!(varint_len(127), 1);
+        assert_eq!(varint_len(128), 2);
+        assert_eq!(varint_len(u64::MAX), 10);
+    }
+
+    #[test]
+    fn test_varint_round_trip() {
+        let mut buf = [0u8; MAX_VARINT_LEN];
+        for v in [0, 1, 127, 128, 300, 1 << 35, u64::MAX - 1, u64::MAX] {
+            let n = write_varint(v, &mut buf);
+            assert_eq!(n, varint_len(v));
+            assert_eq!(read_varint(&buf[..n]), Ok((v, n)));
+        }
+    }
+
+    #[test]
+    fn test_varint_overflow() {
+        let mut bytes = [0xFF; 10];
+        bytes[9] = 0x02;
+        assert_eq!(read_varint(&bytes), Err(CodecError::Overflow));
+        assert_eq!(read_varint(&[0x80; 11]), Err(CodecError::Overflow));
+    }
+
+    #[test]
+    fn test_varint_truncated() {
+        assert_eq!(read_varint(&[0x80, 0x80]), Err(CodecError::Truncated));
+    }
+
+    // ==================== Decoding Error Tests ====================
+
+    #[test]
+    fn test_decode_empty_input() {
+        assert_eq!(decode(&[]), Err(CodecError::Truncated));
+    }
+
+    #[test]
+    fn test_decode_bad_magic() {
+        let mut bytes = encoded(&Pattern::heartbeat());
+        bytes[0] = b'X';
+        assert_eq!(decode(&bytes), Err(CodecError::BadMagic));
+    }
+
+    #[test]
+    fn test_decode_unsupported_version() {
+        let mut bytes = encoded(&Pattern::heartbeat());
+        bytes[2] = 2;
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn test_decode_bad_crc() {
+        let mut bytes = encoded(&Pattern::heartbeat());
+        bytes[8] ^= 0x01;
+        assert_eq!(decode(&bytes), Err(CodecError::BadCrc));
+    }
+
+    #[test]
+    fn test_decode_detects_every_single_bit_flip() {
+        let good = encoded(&Pattern::sos());
+        for i in 2 * 8..good.len() * 8 {
+            let mut bytes = good.clone();
+            bytes[i / 8] ^= 1 << (i % 8);
+            assert!(decode(&bytes).is_err(), "bit {i}");
+        }
+    }
+
+    #[test]
+    fn test_decode_every_truncation_fails() {
+        let good = encoded(&Pattern::double_blink());
+        for len in 0..good.len() {
+            assert!(decode(&good[..len]).is_err(), "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        let mut bytes = encoded(&Pattern::heartbeat());
+        let n = bytes.len() - CRC_LEN;
+        bytes.insert(n, 0);
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::TrailingBytes));
+    }
+
+    #[test]
+    fn test_decode_invalid_end_action() {
+        let mut bytes = encoded(&Pattern::heartbeat());
+        bytes[3] = 7;
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::InvalidEndAction(7)));
+    }
+
+    #[test]
+    fn test_decode_run_exceeds_count() {
+        let mut bytes = encoded(&Pattern::from_segments(&[Segment::on(5); 3]).unwrap());
+        bytes[6] = 2;
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::InvalidSegments));
+    }
+
+    #[test]
+    fn test_decode_count_beyond_capacity() {
+        let mut bytes = encoded(&Pattern::from_segments(&[Segment::on(5); 64]).unwrap());
+        bytes[6] = 65;
+        reseal(&mut bytes);
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_zero_count() {
+        let mut bytes = vec![MAGIC[0], MAGIC[1], VERSION, 0, 1, 0, 0, 0, 0, 0, 0];
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::InvalidSegments));
+    }
+
+    #[test]
+    fn test_decode_duration_too_long() {
+        let mut bytes = vec![MAGIC[0], MAGIC[1], VERSION, 0, 1, 0, 1, 0x80];
+        let mut varint = [0u8; MAX_VARINT_LEN];
+        let len = write_varint(MAX_SEGMENT_MS + 1, &mut varint);
+        bytes.extend_from_slice(&varint[..len]);
+        bytes.extend_from_slice(&[0; CRC_LEN]);
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::Overflow));
+    }
+
+    #[test]
+    fn test_decode_zero_duration() {
+        let mut bytes = encoded(&Pattern::on_off(1, 1));
+        bytes[8] = 0;
+        reseal(&mut bytes);
+        assert_eq!(decode(&bytes), Err(CodecError::InvalidSegments));
+    }
+
+    #[test]
+    fn test_decode_garbage_never_panics() {
+        let mut seed = 0xDEAD_BEEFu32;
+        for _ in 0..2000 {
+            let mut bytes = vec![0u8; (seed % 40) as usize];
+            for b in bytes.iter_mut() {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                *b = seed as u8;
+            }
+            if bytes.len() >= 2 {
+                bytes[..2].copy_from_slice(&MAGIC);
+            }
+            if bytes.len() >= HEADER_LEN + CRC_LEN {
+                bytes[2] = VERSION;
+                reseal(&mut bytes);
+            }
+            let _ = decode(&bytes);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+/*
+ * @file command.rs
+ * @brief LED command handling for inter-task control
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: command.rs
+//!
+//! DESCRIPTION:
+//! LED Command Handling for RP2350.
+//!
+//! BRIEF:
+//! Defines the commands other tasks send to the LED task and a
+//! pure handler that applies them. The handler owns no hardware,
+//! so the LED task's behaviour is testable on the host.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::arbiter::{Arbiter, ArbiterError, Priority, Request, RequestId};
+use crate::config::BlinkConfig;
+use crate::led::LedState;
+use crate::pattern::Pattern;
+
+/// Commands accepted by the LED task.
+///
+/// # Variants
+/// * `SetPattern` - Replace the background pattern
+/// * `SetRate` - Replace the background pattern with a blink
+/// * `ForceOn` - Hold the LED on, ignoring patterns
+/// * `ForceOff` - Hold the LED off, ignoring patterns
+/// * `Pause` - Freeze the LED and pattern time
+/// * `Resume` - Clear any force or pause and continue playback
+/// * `Indicate` - Submit a prioritized request to the arbiter
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub enum LedCommand {
+    SetPattern(Pattern),
+    SetRate(BlinkConfig),
+    ForceOn,
+    ForceOff,
+    Pause,
+    Resume,
+    Indicate(Request),
+}
+
+/// Output mode of the handler.
+///
+/// # Variants
+/// * `Playing` - Arbiter output drives the LED
+/// * `Forced` - LED held at a fixed state while patterns keep time
+/// * `Paused` - LED and pattern time frozen since `since_ms`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Playing,
+    Forced(LedState),
+    Paused { state: LedState, since_ms: u64 },
+}
+
+/// Pure LED command handler.
+///
+/// # Details
+/// Wraps an arbiter whose background request is the pattern set
+/// by `SetPattern` or `SetRate`. Pausing stops pattern time, so
+/// on resume every request continues exactly where it left off.
+/// All times passed in are on the caller's clock; the handler
+/// shifts them by the total paused time internally.
+///
+/// # Fields
+/// * `arbiter` - Request arbitration
+/// * `background` - Handle of the background pattern request
+/// * `mode` - Current output mode
+/// * `paused_ms` - Total time spent paused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct CommandHandler<const N: usize> {
+    arbiter: Arbiter<N>,
+    background: Option<RequestId>,
+    mode: Mode,
+    paused_ms: u64,
+}
+
+impl<const N: usize> CommandHandler<N> {
+    /// Creates a handler playing a background pattern.
+    ///
+    /// # Arguments
+    /// * `pattern` - Initial background pattern
+    ///
+    /// # Returns
+    /// * `Self` - New CommandHandler instance
+    #[allow(dead_code)]
+    pub fn new(pattern: Pattern) -> Self {
+        let mut handler = Self {
+            arbiter: Arbiter::new(),
+            background: None,
+            mode: Mode::Playing,
+            paused_ms: 0,
+        };
+        handler.background = handler.arbiter.submit(background(pattern)).ok();
+        handler
+    }
+
+    /// Applies a command.
+    ///
+    /// # Details
+    /// Brings pattern time up to `now_ms` first so the command
+    /// takes effect at the moment it was received.
+    ///
+    /// # Arguments
+    /// * `command` - Command to apply
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<(), ArbiterError>` - Ok, or full when an indication cannot be queued
+    #[allow(dead_code)]
+    pub fn handle(&mut self, command: LedCommand, now_ms: u64) -> Result<(), ArbiterError> {
+        self.advance(now_ms);
+        match command {
+            LedCommand::SetPattern(pattern) => self.set_background(pattern)?,
+            LedCommand::SetRate(config) => {
+                self.set_background(Pattern::on_off(config.on_ms(), config.off_ms()))?
+            }
+            LedCommand::ForceOn => self.force(LedState::On, now_ms),
+            LedCommand::ForceOff => self.force(LedState::Off, now_ms),
+            LedCommand::Pause => {
+                if !matches!(self.mode, Mode::Paused { .. }) {
+                    self.mode = Mode::Paused {
+                        state: self.state(),
+                        since_ms: now_ms,
+                    };
+                }
+            }
+            LedCommand::Resume => {
+                self.resume(now_ms);
+                self.mode = Mode::Playing;
+            }
+            LedCommand::Indicate(request) => {
+                self.arbiter.submit(request)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Advances pattern time.
+    ///
+    /// # Details
+    /// Does nothing while paused.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `LedState` - State the LED should show
+    #[allow(dead_code)]
+    pub fn advance(&mut self, now_ms: u64) -> LedState {
+        if !matches!(self.mode, Mode::Paused { .. }) {
+            self.arbiter.advance(now_ms.saturating_sub(self.paused_ms));
+        }
+        self.state()
+    }
+
+    /// Returns the state the LED should show.
+    ///
+    /// # Returns
+    /// * `LedState` - Forced, paused or arbitrated state
+    #[allow(dead_code)]
+    pub fn state(&self) -> LedState {
+        match self.mode {
+            Mode::Playing => self.arbiter.state(),
+            Mode::Forced(state) | Mode::Paused { state, .. } => state,
+        }
+    }
+
+    /// Returns when `advance` next needs to run.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Time on the caller's clock, None while paused or idle
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<u64> {
+        match self.mode {
+            Mode::Paused { .. } => None,
+            _ => self
+                .arbiter
+                .next_deadline()
+                .map(|t| t.saturating_add(self.paused_ms)),
+        }
+    }
+
+    /// Returns true while paused.
+    ///
+    /// # Returns
+    /// * `bool` - true if paused
+    #[allow(dead_code)]
+    pub fn is_paused(&self) -> bool {
+        matches!(self.mode, Mode::Paused { .. })
+    }
+
+    /// Returns the forced state, if any.
+    ///
+    /// # Returns
+    /// * `Option<LedState>` - Forced state, None unless forced
+    #[allow(dead_code)]
+    pub fn forced(&self) -> Option<LedState> {
+        match self.mode {
+            Mode::Forced(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying arbiter.
+    ///
+    /// # Returns
+    /// * `&Arbiter<N>` - Request arbitration state
+    #[allow(dead_code)]
+    pub fn arbiter(&self) -> &Arbiter<N> {
+        &self.arbiter
+    }
+
+    /// Replaces the background request.
+    ///
+    /// # Details
+    /// Swaps the new pattern into the old background's slot, so a
+    /// full arbiter cannot leave the handler with no background.
+    ///
+    /// # Arguments
+    /// * `pattern` - New background pattern
+    ///
+    /// # Returns
+    /// * `Result<(), ArbiterError>` - Ok or full
+    fn set_background(&mut self, pattern: Pattern) -> Result<(), ArbiterError> {
+        let id = match self.background {
+            Some(old) => self.arbiter.replace(old, background(pattern))?,
+            None => self.arbiter.submit(background(pattern))?,
+        };
+        self.background = Some(id);
+        Ok(())
+    }
+
+    /// Forces a state, leaving any pause first.
+    ///
+    /// # Arguments
+    /// * `state` - State to hold
+    /// * `now_ms` - Current time in milliseconds
+    fn force(&mut self, state: LedState, now_ms: u64) {
+        self.resume(now_ms);
+        self.mode = Mode::Forced(state);
+        self.advance(now_ms);
+    }
+
+    /// Accounts for time spent paused.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    fn resume(&mut self, now_ms: u64) {
+        if let Mode::Paused { since_ms, .. } = self.mode {
+            self.paused_ms = self
+                .paused_ms
+                .saturating_add(now_ms.saturating_sub(since_ms));
+            self.mode = Mode::Playing;
+        }
+    }
+}
+
+/// Wraps a pattern as the background request.
+///
+/// # Arguments
+/// * `pattern` - Background pattern
+///
+/// # Returns
+/// * `Request` - Lowest-priority request without expiry
+fn background(pattern: Pattern) -> Request {
+    Request::new(Priority::Background, pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::EndAction;
+
+    fn handler() -> CommandHandler<4> {
+        CommandHandler::new(Pattern::on_off(100, 100))
+    }
+
+    // ==================== new() Tests ====================
+
+    #[test]
+    fn test_new_plays_background() {
+        let h = handler();
+        assert_eq!(h.state(), LedState::On);
+        assert_eq!(h.next_deadline(), Some(100));
+        assert!(!h.is_paused());
+        assert_eq!(h.forced(), None);
+    }
+
+    #[test]
+    fn test_advance_follows_pattern() {
+        let mut h = handler();
+        assert_eq!(h.advance(100), LedState::Off);
+        assert_eq!(h.advance(200), LedState::On);
+    }
+
+    // ==================== SetPattern / SetRate Tests ====================
+
+    #[test]
+    fn test_set_pattern_replaces_background() {
+        let mut h = handler();
+        h.handle(LedCommand::SetPattern(Pattern::heartbeat()), 50)
+            .unwrap();
+        assert_eq!(h.arbiter().len(), 1);
+        assert_eq!(h.state(), LedState::On);
+        assert_eq!(h.next_deadline(), Some(150));
+    }
+
+    #[test]
+    fn test_set_rate_uses_config_timing() {
+        let mut h = handler();
+        let config = BlinkConfig::new(30, 70).unwrap();
+        h.handle(LedCommand::SetRate(config), 0).unwrap();
+        assert_eq!(h.advance(30), LedState::Off);
+        assert_eq!(h.advance(100), LedState::On);
+    }
+
+    #[test]
+    fn test_set_pattern_when_full_keeps_indication() {
+        let mut h: CommandHandler<2> = CommandHandler::new(Pattern::heartbeat());
+        let request = Request::new(Priority::Normal, Pattern::sos());
+        h.handle(LedCommand::Indicate(request), 0).unwrap();
+        assert!(
+            h.handle(LedCommand::SetPattern(Pattern::blink(50)), 10)
+                .is_ok()
+        );
+        assert_eq!(h.arbiter().len(), 2);
+        assert_eq!(h.arbiter().active().unwrap().1, Priority::Normal);
+    }
+
+    #[test]
+    fn test_set_pattern_repeatedly_does_not_fill() {
+        let mut h: CommandHandler<1> = CommandHandler::new(Pattern::heartbeat());
+        for t in 0..10 {
+            assert!(h.handle(LedCommand::SetPattern(Pattern::sos()), t).is_ok());
+        }
+    }
+
+    // ==================== Force Tests ====================
+
+    #[test]
+    fn test_force_on_overrides_pattern() {
+        let mut h = handler();
+        h.handle(LedCommand::ForceOn, 0).unwrap();
+        assert_eq!(h.advance(100), LedState::On);
+        assert_eq!(h.forced(), Some(LedState::On));
+    }
+
+    #[test]
+    fn test_force_off_overrides_pattern() {
+        let mut h = handler();
+        h.handle(LedCommand::ForceOff, 0).unwrap();
+        assert_eq!(h.state(), LedState::Off);
+        assert_eq!(h.advance(200), LedState::Off);
+    }
+
+    #[test]
+    fn test_force_keeps_pattern_time() {
+        let mut h = handler();
+        h.handle(LedCommand::ForceOff, 0).unwrap();
+        h.handle(LedCommand::Resume, 150).unwrap();
+        assert_eq!(h.state(), LedState::Off);
+        assert_eq!(h.next_deadline(), Some(200));
+    }
+
+    // ==================== Pause / Resume Tests ====================
+
+    #[test]
+    fn test_pause_freezes_state() {
+        let mut h = handler();
+        h.handle(LedCommand::Pause, 50).unwrap();
+        assert!(h.is_paused());
+        assert_eq!(h.advance(10_000), LedState::On);
+        assert_eq!(h.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_resume_continues_where_paused() {
+        let mut h = handler();
+        h.handle(LedCommand::Pause, 50).unwrap();
+        h.handle(LedCommand::Resume, 1050).unwrap();
+        assert!(!h.is_paused());
+        assert_eq!(h.state(), LedState::On);
+        assert_eq!(h.next_deadline(), Some(1100));
+        assert_eq!(h.advance(1100), LedState::Off);
+    }
+
+    #[test]
+    fn test_pause_twice_keeps_first_instant() {
+        let mut h = handler();
+        h.handle(LedCommand::Pause, 50).unwrap();
+        h.handle(LedCommand::Pause, 80).unwrap();
+        h.handle(LedCommand::Resume, 100).unwrap();
+        assert_eq!(h.next_deadline(), Some(150));
+    }
+
+    #[test]
+    fn test_force_while_paused_unpauses() {
+        let mut h = handler();
+        h.handle(LedCommand::Pause, 50).unwrap();
+        h.handle(LedCommand::ForceOff, 150).unwrap();
+        assert!(!h.is_paused());
+        assert_eq!(h.state(), LedState::Off);
+        assert_eq!(h.next_deadline(), Some(200));
+    }
+
+    #[test]
+    fn test_resume_without_pause_clears_force() {
+        let mut h = handler();
+        h.handle(LedCommand::ForceOff, 0).unwrap();
+        h.handle(LedCommand::Resume, 10).unwrap();
+        assert_eq!(h.forced(), None);
+        assert_eq!(h.state(), LedState::On);
+    }
+
+    // ==================== Indicate Tests ====================
+
+    #[test]
+    fn test_indicate_preempts_background() {
+        let mut h = handler();
+        let alert = Pattern::on_off(10, 10).with_end_action(EndAction::Stop);
+        h.handle(
+            LedCommand::Indicate(Request::new(Priority::Critical, alert)),
+            100,
+        )
+        .unwrap();
+        assert_eq!(h.state(), LedState::On);
+        assert_eq!(h.advance(110), LedState::Off);
+        assert_eq!(h.advance(120), LedState::Off);
+        assert_eq!(h.arbiter().len(), 1);
+        assert_eq!(h.advance(200), LedState::On);
+    }
+
+    #[test]
+    fn test_indicate_when_full() {
+        let mut h: CommandHandler<1> = CommandHandler::new(Pattern::heartbeat());
+        let request = Request::new(Priority::High, Pattern::sos());
+        assert_eq!(
+            h.handle(LedCommand::Indicate(request), 0),
+            Err(ArbiterError::Full)
+        );
+    }
+
+    #[test]
+    fn test_indicate_expiry_shifted_by_pause() {
+        let mut h = handler();
+        let busy = Request::new(Priority::Normal, Pattern::on_off(1000, 1000)).with_ttl(300);
+        h.handle(LedCommand::Indicate(busy), 0).unwrap();
+        h.handle(LedCommand::Pause, 100).unwrap();
+        h.handle(LedCommand::Resume, 600).unwrap();
+        assert_eq!(h.next_deadline(), Some(700));
+        h.advance(799);
+        assert_eq!(h.arbiter().len(), 2);
+        h.advance(800);
+        assert_eq!(h.arbiter().len(), 1);
+    }
+}
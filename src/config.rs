@@ -34,11 +34,11 @@
 //!
 //! BRIEF:
 //! Defines configuration constants for LED blink timing.
-//! Contains delay intervals and GPIO pin configuration.
+//! Contains delay intervals, duty cycle timing, brightness and GPIO pin configuration.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 6, 2025
-//! UPDATE DATE: December 6, 2025
+//! UPDATE DATE: October 17, 2026
 
 /// Default LED blink delay in milliseconds.
 ///
@@ -71,6 +71,563 @@ pub const MIN_BLINK_DELAY_MS: u64 = 10;
 #[allow(dead_code)]
 pub const MAX_BLINK_DELAY_MS: u64 = 10000;
 
+/// LED brightness while on, in percent.
+///
+/// # Details
+/// Perceived brightness; converted to PWM duty through the
+/// CIE 1931 correction table.
+///
+/// # Value
+/// 100 percent
+#[allow(dead_code)]
+pub const LED_BRIGHTNESS_PERCENT: u8 = 100;
+const _: () = assert!(LED_BRIGHTNESS_PERCENT > 0 && LED_BRIGHTNESS_PERCENT <= 100);
+
+/// LED wiring polarity.
+///
+/// # Details
+/// Set when the LED lights with the pin driven low. Used when no
+/// polarity has been saved in the settings store.
+///
+/// # Value
+/// false (active high)
+#[allow(dead_code)]
+pub const LED_ACTIVE_LOW: bool = false;
+
+/// PWM counter wrap value for the LED slice.
+///
+/// # Details
+/// Sets the PWM resolution; duty cycles range from 0 to this value.
+/// At the 150 MHz system clock this gives roughly a 2.3 kHz PWM.
+///
+/// # Value
+/// 65535 (16-bit resolution)
+#[allow(dead_code)]
+pub const LED_PWM_TOP: u16 = 0xFFFF;
+
+/// Full-scale duty cycle in permille.
+///
+/// # Details
+/// Duty cycles are expressed in tenths of a percent.
+///
+/// # Value
+/// 1000 permille (100%)
+#[allow(dead_code)]
+pub const DUTY_PERMILLE_MAX: u16 = 1000;
+
+/// Selects the breathing effect instead of blinking.
+///
+/// # Details
+/// When true the firmware fades the LED up and down
+/// continuously rather than playing a blink pattern.
+///
+/// # Value
+/// false (blink)
+#[allow(dead_code)]
+pub const BREATHING_ENABLED: bool = false;
+
+/// Length of one full breath in milliseconds.
+///
+/// # Details
+/// Split evenly between fading up and fading down.
+///
+/// # Value
+/// 3000 milliseconds
+#[allow(dead_code)]
+pub const BREATHING_PERIOD_MS: u64 = 3000;
+
+/// Interval between brightness updates during a fade.
+///
+/// # Details
+/// Smaller steps give smoother fades at the cost of more wakeups.
+///
+/// # Value
+/// 10 milliseconds (100 Hz)
+#[allow(dead_code)]
+pub const FADE_STEP_MS: u64 = 10;
+const _: () = assert!(FADE_STEP_MS > 0 && BREATHING_PERIOD_MS >= FADE_STEP_MS * 10);
+
+/// Number of indicator LEDs in the LED bank.
+///
+/// # Details
+/// Indicators sit on consecutive GPIOs starting at GPIO 17.
+///
+/// # Value
+/// 4 LEDs (GPIO 17-20)
+#[allow(dead_code)]
+pub const INDICATOR_COUNT: usize = 4;
+const _: () = assert!(INDICATOR_COUNT > 0);
+
+/// Phase offset between neighbouring indicator LEDs.
+///
+/// # Details
+/// Each indicator starts its pattern this long after the
+/// previous one, giving a chase across the bank.
+///
+/// # Value
+/// 250 milliseconds
+#[allow(dead_code)]
+pub const INDICATOR_PHASE_MS: u64 = 250;
+
+/// Maximum number of live LED requests.
+///
+/// # Details
+/// Sizes the arbiter in front of the main LED. Submissions
+/// beyond this many live requests are rejected.
+///
+/// # Value
+/// 8 requests
+#[allow(dead_code)]
+pub const ARBITER_SLOTS: usize = 8;
+const _: () = assert!(ARBITER_SLOTS >= 4);
+
+/// Depth of the LED command channel.
+///
+/// # Details
+/// Senders wait once this many commands are queued for the
+/// LED task.
+///
+/// # Value
+/// 4 commands
+#[allow(dead_code)]
+pub const LED_COMMAND_DEPTH: usize = 4;
+const _: () = assert!(LED_COMMAND_DEPTH > 0);
+
+/// Total size of the on-board QSPI flash.
+///
+/// # Details
+/// Must match the FLASH length plus the settings region in memory.x.
+///
+/// # Value
+/// 4 MB
+#[allow(dead_code)]
+pub const FLASH_SIZE: usize = 4 * 1024 * 1024;
+
+/// Size of the settings region at the end of flash.
+///
+/// # Details
+/// Excluded from the FLASH region in memory.x so firmware never
+/// overlaps it. Split into erase sectors that the settings store
+/// rotates through for wear leveling.
+///
+/// # Value
+/// 64 KB (16 sectors of 4 KB)
+#[allow(dead_code)]
+pub const SETTINGS_SIZE: u32 = 64 * 1024;
+const _: () = assert!(SETTINGS_SIZE / 4096 >= 2);
+
+/// Offset of the settings region from the start of flash.
+///
+/// # Value
+/// 0x3F0000
+#[allow(dead_code)]
+pub const SETTINGS_OFFSET: u32 = FLASH_SIZE as u32 - SETTINGS_SIZE;
+
+/// Time a button level must hold before it is accepted.
+///
+/// # Details
+/// Longer than typical contact bounce, short enough that fast
+/// double clicks still register.
+///
+/// # Value
+/// 20 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_DEBOUNCE_MS: u64 = 20;
+
+/// Window after a release in which a second press is a double click.
+///
+/// # Value
+/// 300 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_DOUBLE_CLICK_MS: u64 = 300;
+
+/// Hold time before a press becomes a long press.
+///
+/// # Value
+/// 800 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_LONG_PRESS_MS: u64 = 800;
+
+/// Interval between repeat events while a long press is held.
+///
+/// # Value
+/// 200 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_REPEAT_MS: u64 = 200;
+const _: () = assert!(
+    BUTTON_DEBOUNCE_MS < BUTTON_DOUBLE_CLICK_MS
+        && BUTTON_LONG_PRESS_MS > BUTTON_DOUBLE_CLICK_MS
+        && BUTTON_REPEAT_MS > 0
+);
+
+/// Blink delays the button cycles through.
+///
+/// # Details
+/// A single click moves to the next entry, wrapping around.
+///
+/// # Value
+/// 100, 250, 500 and 1000 milliseconds
+#[allow(dead_code)]
+pub const BUTTON_RATES_MS: [u64; 4] = [100, 250, 500, 1000];
+
+/// Fault code flashed by the panic-blink handler.
+///
+/// # Details
+/// Shown as this many slow flashes after the fast triple flash,
+/// so products can tell their firmware's faults apart.
+///
+/// # Value
+/// 1
+#[allow(dead_code)]
+pub const PANIC_BLINK_CODE: u8 = 1;
+
+/// Longest command line the serial shell accepts.
+///
+/// # Details
+/// Sized for a `pattern` command with a few dozen segments;
+/// longer lines are rejected as a whole.
+///
+/// # Value
+/// 256 bytes
+#[allow(dead_code)]
+pub const SHELL_LINE_LEN: usize = 256;
+const _: () = assert!(SHELL_LINE_LEN >= 64);
+
+/// USB vendor ID of the serial console.
+///
+/// # Details
+/// Test ID also used by the Embassy examples; products must
+/// use their own.
+///
+/// # Value
+/// 0xC0DE
+#[allow(dead_code)]
+pub const USB_VID: u16 = 0xc0de;
+
+/// USB product ID of the serial console.
+///
+/// # Value
+/// 0xCAFE
+#[allow(dead_code)]
+pub const USB_PID: u16 = 0xcafe;
+
+/// Blink configuration errors.
+///
+/// # Variants
+/// * `TooShort` - A delay is below `MIN_BLINK_DELAY_MS`
+/// * `TooLong` - A delay is above `MAX_BLINK_DELAY_MS`
+/// * `ZeroPeriod` - Period or frequency is zero
+/// * `Overflow` - Arithmetic overflowed while deriving delays
+/// * `InvalidDuty` - Duty cycle exceeds `DUTY_PERMILLE_MAX`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ConfigError {
+    TooShort,
+    TooLong,
+    ZeroPeriod,
+    Overflow,
+    InvalidDuty,
+}
+
+/// Validated blink configuration.
+///
+/// # Details
+/// Holds separate ON and OFF durations, each guaranteed to lie
+/// within `MIN_BLINK_DELAY_MS..=MAX_BLINK_DELAY_MS`.
+/// Built directly, from a period or frequency plus a duty cycle,
+/// or through `BlinkConfigBuilder`.
+///
+/// # Fields
+/// * `on_ms` - ON duration in milliseconds
+/// * `off_ms` - OFF duration in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BlinkConfig {
+    on_ms: u64,
+    off_ms: u64,
+}
+
+impl Default for BlinkConfig {
+    /// Returns symmetric configuration using `BLINK_DELAY_MS`.
+    ///
+    /// # Returns
+    /// * `Self` - Default blink configuration
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self {
+            on_ms: BLINK_DELAY_MS,
+            off_ms: BLINK_DELAY_MS,
+        }
+    }
+}
+
+impl BlinkConfig {
+    /// Creates configuration from explicit ON and OFF durations.
+    ///
+    /// # Arguments
+    /// * `on_ms` - ON duration in milliseconds
+    /// * `off_ms` - OFF duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Result<Self, ConfigError>` - Configuration or validation error
+    #[allow(dead_code)]
+    pub const fn new(on_ms: u64, off_ms: u64) -> Result<Self, ConfigError> {
+        if let Err(e) = check_delay(on_ms) {
+            return Err(e);
+        }
+        if let Err(e) = check_delay(off_ms) {
+            return Err(e);
+        }
+        Ok(Self { on_ms, off_ms })
+    }
+
+    /// Creates configuration from a period and duty cycle.
+    ///
+    /// # Details
+    /// ON time is `period_ms * duty_permille / 1000`,
+    /// OFF time is the remainder of the period.
+    ///
+    /// # Arguments
+    /// * `period_ms` - Full ON+OFF period in milliseconds
+    /// * `duty_permille` - ON share in tenths of a percent
+    ///
+    /// # Returns
+    /// * `Result<Self, ConfigError>` - Configuration or validation error
+    #[allow(dead_code)]
+    pub const fn from_period(period_ms: u64, duty_permille: u16) -> Result<Self, ConfigError> {
+        if period_ms == 0 {
+            return Err(ConfigError::ZeroPeriod);
+        }
+        if duty_permille > DUTY_PERMILLE_MAX {
+            return Err(ConfigError::InvalidDuty);
+        }
+        let on_ms = match period_ms.checked_mul(duty_permille as u64) {
+            Some(scaled) => scaled / DUTY_PERMILLE_MAX as u64,
+            None => return Err(ConfigError::Overflow),
+        };
+        Self::new(on_ms, period_ms - on_ms)
+    }
+
+    /// Creates configuration from a frequency and duty cycle.
+    ///
+    /// # Details
+    /// Frequency is in millihertz so sub-hertz blinking is
+    /// expressible, e.g. 500 mHz for one blink every 2 seconds.
+    ///
+    /// # Arguments
+    /// * `millihertz` - Blink frequency in millihertz
+    /// * `duty_permille` - ON share in tenths of a percent
+    ///
+    /// # Returns
+    /// * `Result<Self, ConfigError>` - Configuration or validation error
+    #[allow(dead_code)]
+    pub const fn from_frequency(millihertz: u32, duty_permille: u16) -> Result<Self, ConfigError> {
+        if millihertz == 0 {
+            return Err(ConfigError::ZeroPeriod);
+        }
+        Self::from_period(1_000_000 / millihertz as u64, duty_permille)
+    }
+
+    /// Returns a builder starting from the defaults.
+    ///
+    /// # Returns
+    /// * `BlinkConfigBuilder` - New builder
+    #[allow(dead_code)]
+    pub const fn builder() -> BlinkConfigBuilder {
+        BlinkConfigBuilder::new()
+    }
+
+    /// Returns the ON duration.
+    ///
+    /// # Returns
+    /// * `u64` - ON duration in milliseconds
+    #[allow(dead_code)]
+    pub const fn on_ms(&self) -> u64 {
+        self.on_ms
+    }
+
+    /// Returns the OFF duration.
+    ///
+    /// # Returns
+    /// * `u64` - OFF duration in milliseconds
+    #[allow(dead_code)]
+    pub const fn off_ms(&self) -> u64 {
+        self.off_ms
+    }
+
+    /// Returns the full period.
+    ///
+    /// # Returns
+    /// * `u64` - ON plus OFF duration in milliseconds
+    #[allow(dead_code)]
+    pub const fn period_ms(&self) -> u64 {
+        self.on_ms + self.off_ms
+    }
+}
+
+/// Timing source selected on a builder.
+///
+/// # Variants
+/// * `OnOff` - Explicit ON and OFF durations
+/// * `Period` - Period in milliseconds
+/// * `Frequency` - Frequency in millihertz
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Timing {
+    OnOff { on_ms: u64, off_ms: u64 },
+    Period(u64),
+    Frequency(u32),
+}
+
+/// Builder for `BlinkConfig`.
+///
+/// # Details
+/// The most recent timing setter wins. Period and frequency
+/// use the duty cycle, which defaults to 50%.
+/// Validation happens in `build()`.
+///
+/// # Fields
+/// * `timing` - Selected timing source
+/// * `duty_permille` - Duty cycle for period and frequency timing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct BlinkConfigBuilder {
+    timing: Timing,
+    duty_permille: u16,
+}
+
+impl Default for BlinkConfigBuilder {
+    /// Returns a builder starting from the defaults.
+    ///
+    /// # Returns
+    /// * `Self` - New builder
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlinkConfigBuilder {
+    /// Creates a builder with symmetric `BLINK_DELAY_MS` timing.
+    ///
+    /// # Returns
+    /// * `Self` - New builder
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            timing: Timing::OnOff {
+                on_ms: BLINK_DELAY_MS,
+                off_ms: BLINK_DELAY_MS,
+            },
+            duty_permille: DUTY_PERMILLE_MAX / 2,
+        }
+    }
+
+    /// Sets the ON duration.
+    ///
+    /// # Arguments
+    /// * `on_ms` - ON duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Updated builder
+    #[allow(dead_code)]
+    pub const fn on_ms(mut self, on_ms: u64) -> Self {
+        let off_ms = match self.timing {
+            Timing::OnOff { off_ms, .. } => off_ms,
+            _ => BLINK_DELAY_MS,
+        };
+        self.timing = Timing::OnOff { on_ms, off_ms };
+        self
+    }
+
+    /// Sets the OFF duration.
+    ///
+    /// # Arguments
+    /// * `off_ms` - OFF duration in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Updated builder
+    #[allow(dead_code)]
+    pub const fn off_ms(mut self, off_ms: u64) -> Self {
+        let on_ms = match self.timing {
+            Timing::OnOff { on_ms, .. } => on_ms,
+            _ => BLINK_DELAY_MS,
+        };
+        self.timing = Timing::OnOff { on_ms, off_ms };
+        self
+    }
+
+    /// Sets the period.
+    ///
+    /// # Arguments
+    /// * `period_ms` - Full ON+OFF period in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Updated builder
+    #[allow(dead_code)]
+    pub const fn period_ms(mut self, period_ms: u64) -> Self {
+        self.timing = Timing::Period(period_ms);
+        self
+    }
+
+    /// Sets the frequency.
+    ///
+    /// # Arguments
+    /// * `millihertz` - Blink frequency in millihertz
+    ///
+    /// # Returns
+    /// * `Self` - Updated builder
+    #[allow(dead_code)]
+    pub const fn frequency_millihertz(mut self, millihertz: u32) -> Self {
+        self.timing = Timing::Frequency(millihertz);
+        self
+    }
+
+    /// Sets the duty cycle.
+    ///
+    /// # Arguments
+    /// * `duty_permille` - ON share in tenths of a percent
+    ///
+    /// # Returns
+    /// * `Self` - Updated builder
+    #[allow(dead_code)]
+    pub const fn duty_permille(mut self, duty_permille: u16) -> Self {
+        self.duty_permille = duty_permille;
+        self
+    }
+
+    /// Validates the settings and builds the configuration.
+    ///
+    /// # Returns
+    /// * `Result<BlinkConfig, ConfigError>` - Configuration or validation error
+    #[allow(dead_code)]
+    pub const fn build(self) -> Result<BlinkConfig, ConfigError> {
+        match self.timing {
+            Timing::OnOff { on_ms, off_ms } => BlinkConfig::new(on_ms, off_ms),
+            Timing::Period(period_ms) => BlinkConfig::from_period(period_ms, self.duty_permille),
+            Timing::Frequency(millihertz) => {
+                BlinkConfig::from_frequency(millihertz, self.duty_permille)
+            }
+        }
+    }
+}
+
+/// Checks a delay against the configured limits.
+///
+/// # Arguments
+/// * `delay_ms` - Delay in milliseconds
+///
+/// # Returns
+/// * `Result<(), ConfigError>` - Ok if within `MIN_BLINK_DELAY_MS..=MAX_BLINK_DELAY_MS`
+const fn check_delay(delay_ms: u64) -> Result<(), ConfigError> {
+    if delay_ms < MIN_BLINK_DELAY_MS {
+        Err(ConfigError::TooShort)
+    } else if delay_ms > MAX_BLINK_DELAY_MS {
+        Err(ConfigError::TooLong)
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,4 +772,316 @@ mod tests {
         const DOUBLE_DELAY: u64 = BLINK_DELAY_MS * 2;
         assert_eq!(DOUBLE_DELAY, 1000);
     }
+
+    // ==================== LED Brightness Tests ====================
+
+    #[test]
+    fn test_pwm_top_full_resolution() {
+        assert_eq!(LED_PWM_TOP, u16::MAX);
+    }
+
+    // ==================== Indicator Bank Tests ====================
+
+    #[test]
+    fn test_indicator_chase_fits_heartbeat() {
+        let span = INDICATOR_PHASE_MS * INDICATOR_COUNT as u64;
+        assert!(span <= crate::pattern::Pattern::heartbeat().period_ms());
+    }
+
+    // ==================== Settings Region Tests ====================
+
+    #[test]
+    fn test_settings_region_ends_at_flash_end() {
+        assert_eq!(
+            SETTINGS_OFFSET as usize + SETTINGS_SIZE as usize,
+            FLASH_SIZE
+        );
+    }
+
+    #[test]
+    fn test_settings_region_sector_aligned() {
+        assert_eq!(SETTINGS_OFFSET % 4096, 0);
+        assert_eq!(SETTINGS_SIZE % 4096, 0);
+    }
+
+    // ==================== Button Tests ====================
+
+    #[test]
+    fn test_button_rates_in_range() {
+        for rate in BUTTON_RATES_MS {
+            assert!(BlinkConfig::new(rate, rate).is_ok());
+        }
+    }
+
+    // ==================== BlinkConfig::new() Tests ====================
+
+    #[test]
+    fn test_config_new_valid() {
+        let c = BlinkConfig::new(50, 1950).unwrap();
+        assert_eq!(c.on_ms(), 50);
+        assert_eq!(c.off_ms(), 1950);
+        assert_eq!(c.period_ms(), 2000);
+    }
+
+    #[test]
+    fn test_config_new_at_limits() {
+        assert!(BlinkConfig::new(MIN_BLINK_DELAY_MS, MAX_BLINK_DELAY_MS).is_ok());
+    }
+
+    #[test]
+    fn test_config_new_too_short() {
+        let short = MIN_BLINK_DELAY_MS - 1;
+        assert_eq!(BlinkConfig::new(short, 500), Err(ConfigError::TooShort));
+        assert_eq!(BlinkConfig::new(500, short), Err(ConfigError::TooShort));
+    }
+
+    #[test]
+    fn test_config_new_too_long() {
+        let long = MAX_BLINK_DELAY_MS + 1;
+        assert_eq!(BlinkConfig::new(long, 500), Err(ConfigError::TooLong));
+        assert_eq!(BlinkConfig::new(500, long), Err(ConfigError::TooLong));
+    }
+
+    #[test]
+    fn test_config_new_zero_is_too_short() {
+        assert_eq!(BlinkConfig::new(0, 500), Err(ConfigError::TooShort));
+    }
+
+    #[test]
+    fn test_config_default_symmetric() {
+        let c = BlinkConfig::default();
+        assert_eq!(c.on_ms(), BLINK_DELAY_MS);
+        assert_eq!(c.off_ms(), BLINK_DELAY_MS);
+    }
+
+    #[test]
+    fn test_config_default_is_valid() {
+        let c = BlinkConfig::default();
+        assert_eq!(BlinkConfig::new(c.on_ms(), c.off_ms()), Ok(c));
+    }
+
+    // ==================== BlinkConfig::from_period() Tests ====================
+
+    #[test]
+    fn test_from_period_flash_every_two_seconds() {
+        let c = BlinkConfig::from_period(2000, 25).unwrap();
+        assert_eq!(c.on_ms(), 50);
+        assert_eq!(c.off_ms(), 1950);
+    }
+
+    #[test]
+    fn test_from_period_half_duty_matches_default() {
+        let c = BlinkConfig::from_period(2 * BLINK_DELAY_MS, 500).unwrap();
+        assert_eq!(c, BlinkConfig::default());
+    }
+
+    #[test]
+    fn test_from_period_preserves_period() {
+        let c = BlinkConfig::from_period(1001, 333).unwrap();
+        assert_eq!(c.period_ms(), 1001);
+    }
+
+    #[test]
+    fn test_from_period_zero_duty() {
+        assert_eq!(
+            BlinkConfig::from_period(1000, 0),
+            Err(ConfigError::TooShort)
+        );
+    }
+
+    #[test]
+    fn test_from_period_full_duty() {
+        let result = BlinkConfig::from_period(1000, DUTY_PERMILLE_MAX);
+        assert_eq!(result, Err(ConfigError::TooShort));
+    }
+
+    #[test]
+    fn test_from_period_duty_over_max() {
+        let result = BlinkConfig::from_period(1000, DUTY_PERMILLE_MAX + 1);
+        assert_eq!(result, Err(ConfigError::InvalidDuty));
+    }
+
+    #[test]
+    fn test_from_period_zero() {
+        assert_eq!(
+            BlinkConfig::from_period(0, 500),
+            Err(ConfigError::ZeroPeriod)
+        );
+    }
+
+    #[test]
+    fn test_from_period_too_long() {
+        let result = BlinkConfig::from_period(4 * MAX_BLINK_DELAY_MS, 500);
+        assert_eq!(result, Err(ConfigError::TooLong));
+    }
+
+    #[test]
+    fn test_from_period_overflow() {
+        assert_eq!(
+            BlinkConfig::from_period(u64::MAX, 500),
+            Err(ConfigError::Overflow)
+        );
+    }
+
+    // ==================== BlinkConfig::from_frequency() Tests ====================
+
+    #[test]
+    fn test_from_frequency_one_hz() {
+        let c = BlinkConfig::from_frequency(1000, 500).unwrap();
+        assert_eq!(c, BlinkConfig::default());
+    }
+
+    #[test]
+    fn test_from_frequency_sub_hertz() {
+        let c = BlinkConfig::from_frequency(500, 25).unwrap();
+        assert_eq!(c.on_ms(), 50);
+        assert_eq!(c.off_ms(), 1950);
+    }
+
+    #[test]
+    fn test_from_frequency_zero() {
+        assert_eq!(
+            BlinkConfig::from_frequency(0, 500),
+            Err(ConfigError::ZeroPeriod)
+        );
+    }
+
+    #[test]
+    fn test_from_frequency_above_one_khz() {
+        assert_eq!(
+            BlinkConfig::from_frequency(2_000_000, 500),
+            Err(ConfigError::ZeroPeriod)
+        );
+    }
+
+    #[test]
+    fn test_from_frequency_too_fast() {
+        assert_eq!(
+            BlinkConfig::from_frequency(100_000, 500),
+            Err(ConfigError::TooShort)
+        );
+    }
+
+    #[test]
+    fn test_from_frequency_too_slow() {
+        assert_eq!(
+            BlinkConfig::from_frequency(10, 500),
+            Err(ConfigError::TooLong)
+        );
+    }
+
+    #[test]
+    fn test_config_usable_in_const_context() {
+        const C: Result<BlinkConfig, ConfigError> = BlinkConfig::from_period(1000, 100);
+        assert_eq!(C.unwrap().on_ms(), 100);
+    }
+
+    // ==================== BlinkConfigBuilder Tests ====================
+
+    #[test]
+    fn test_builder_defaults() {
+        assert_eq!(BlinkConfig::builder().build(), Ok(BlinkConfig::default()));
+    }
+
+    #[test]
+    fn test_builder_default_equals_new() {
+        assert_eq!(BlinkConfigBuilder::default(), BlinkConfigBuilder::new());
+    }
+
+    #[test]
+    fn test_builder_on_off() {
+        let c = BlinkConfig::builder()
+            .on_ms(50)
+            .off_ms(1950)
+            .build()
+            .unwrap();
+        assert_eq!(c, BlinkConfig::new(50, 1950).unwrap());
+    }
+
+    #[test]
+    fn test_builder_on_only_keeps_default_off() {
+        let c = BlinkConfig::builder().on_ms(100).build().unwrap();
+        assert_eq!(c.on_ms(), 100);
+        assert_eq!(c.off_ms(), BLINK_DELAY_MS);
+    }
+
+    #[test]
+    fn test_builder_period_default_duty() {
+        let c = BlinkConfig::builder().period_ms(400).build().unwrap();
+        assert_eq!(c.on_ms(), 200);
+        assert_eq!(c.off_ms(), 200);
+    }
+
+    #[test]
+    fn test_builder_period_and_duty() {
+        let c = BlinkConfig::builder()
+            .period_ms(2000)
+            .duty_permille(25)
+            .build()
+            .unwrap();
+        assert_eq!(c.on_ms(), 50);
+    }
+
+    #[test]
+    fn test_builder_frequency_and_duty() {
+        let c = BlinkConfig::builder()
+            .duty_permille(250)
+            .frequency_millihertz(1000)
+            .build()
+            .unwrap();
+        assert_eq!(c.on_ms(), 250);
+        assert_eq!(c.off_ms(), 750);
+    }
+
+    #[test]
+    fn test_builder_last_timing_wins() {
+        let c = BlinkConfig::builder()
+            .period_ms(2000)
+            .on_ms(20)
+            .off_ms(30)
+            .build()
+            .unwrap();
+        assert_eq!(c.period_ms(), 50);
+    }
+
+    #[test]
+    fn test_builder_rejects_too_short() {
+        let result = BlinkConfig::builder().on_ms(1).build();
+        assert_eq!(result, Err(ConfigError::TooShort));
+    }
+
+    #[test]
+    fn test_builder_rejects_too_long() {
+        let result = BlinkConfig::builder()
+            .off_ms(MAX_BLINK_DELAY_MS + 1)
+            .build();
+        assert_eq!(result, Err(ConfigError::TooLong));
+    }
+
+    #[test]
+    fn test_builder_rejects_zero_period() {
+        let result = BlinkConfig::builder().period_ms(0).build();
+        assert_eq!(result, Err(ConfigError::ZeroPeriod));
+    }
+
+    #[test]
+    fn test_builder_rejects_zero_frequency() {
+        let result = BlinkConfig::builder().frequency_millihertz(0).build();
+        assert_eq!(result, Err(ConfigError::ZeroPeriod));
+    }
+
+    #[test]
+    fn test_builder_rejects_overflow() {
+        let result = BlinkConfig::builder().period_ms(u64::MAX).build();
+        assert_eq!(result, Err(ConfigError::Overflow));
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_duty() {
+        let result = BlinkConfig::builder()
+            .period_ms(1000)
+            .duty_permille(1001)
+            .build();
+        assert_eq!(result, Err(ConfigError::InvalidDuty));
+    }
 }
@@ -0,0 +1,304 @@
+/*
+ * @file crc.rs
+ * @brief CRC-32 checksum
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: crc.rs
+//!
+//! DESCRIPTION:
+//! CRC-32 and CRC-16 Checksums for RP2350 Data Integrity.
+//!
+//! BRIEF:
+//! Table-driven CRC-32 (IEEE 802.3, as used by zlib and PNG) and
+//! CRC-16/CCITT-FALSE with the lookup tables generated at compile
+//! time. CRC-32 validates stored and transferred pattern data;
+//! CRC-16 guards the short frames of the control protocol.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+/// Reflected CRC-32 polynomial.
+///
+/// # Value
+/// 0xEDB88320 (IEEE 802.3)
+#[allow(dead_code)]
+pub const CRC32_POLY: u32 = 0xEDB8_8320;
+
+/// Byte-wise CRC-32 lookup table.
+///
+/// # Details
+/// Generated at compile time from `CRC32_POLY`.
+static CRC32_TABLE: [u32; 256] = build_crc32_table();
+
+/// Builds the CRC-32 lookup table.
+///
+/// # Returns
+/// * `[u32; 256]` - Remainder for every byte value
+const fn build_crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Incremental CRC-32 calculator.
+///
+/// # Details
+/// Feed data in any number of `update` calls; the result equals
+/// `crc32` over the concatenated input.
+///
+/// # Fields
+/// * `state` - Running register, pre-inverted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Default for Crc32 {
+    /// Returns default Crc32 instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New Crc32 with no data fed
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    /// Creates a calculator with no data fed.
+    ///
+    /// # Returns
+    /// * `Self` - New Crc32 instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Feeds bytes into the checksum.
+    ///
+    /// # Arguments
+    /// * `data` - Next bytes of input
+    #[allow(dead_code)]
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = ((self.state ^ u32::from(byte)) & 0xFF) as usize;
+            self.state = (self.state >> 8) ^ CRC32_TABLE[index];
+        }
+    }
+
+    /// Returns the checksum of everything fed so far.
+    ///
+    /// # Returns
+    /// * `u32` - CRC-32 value
+    #[allow(dead_code)]
+    pub const fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// Computes the CRC-32 of a byte slice.
+///
+/// # Arguments
+/// * `data` - Input bytes
+///
+/// # Returns
+/// * `u32` - CRC-32 value
+#[allow(dead_code)]
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// CRC-16/CCITT-FALSE polynomial.
+///
+/// # Value
+/// 0x1021 (x^16 + x^12 + x^5 + 1), MSB first
+#[allow(dead_code)]
+pub const CRC16_POLY: u16 = 0x1021;
+
+/// Byte-wise CRC-16 lookup table.
+///
+/// # Details
+/// Generated at compile time from `CRC16_POLY`.
+static CRC16_TABLE: [u16; 256] = build_crc16_table();
+
+/// Builds the CRC-16 lookup table.
+///
+/// # Returns
+/// * `[u16; 256]` - Remainder for every byte value
+const fn build_crc16_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC16_POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Computes the CRC-16/CCITT-FALSE of a byte slice.
+///
+/// # Details
+/// Initial value 0xFFFF, no reflection, no final XOR.
+///
+/// # Arguments
+/// * `data` - Input bytes
+///
+/// # Returns
+/// * `u16` - CRC-16 value
+#[allow(dead_code)]
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, &byte| {
+        (crc << 8) ^ CRC16_TABLE[usize::from((crc >> 8) as u8 ^ byte)]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Table Tests ====================
+
+    #[test]
+    fn test_table_known_entries() {
+        assert_eq!(CRC32_TABLE[0], 0);
+        assert_eq!(CRC32_TABLE[1], 0x7707_3096);
+        assert_eq!(CRC32_TABLE[255], 0x2D02_EF8D);
+    }
+
+    // ==================== crc32() Tests ====================
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_crc32_empty() {
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn test_crc32_known_string() {
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414F_A339
+        );
+    }
+
+    #[test]
+    fn test_crc32_detects_single_bit_flip() {
+        let mut data = *b"blink pattern";
+        let good = crc32(&data);
+        for i in 0..data.len() * 8 {
+            data[i / 8] ^= 1 << (i % 8);
+            assert_ne!(crc32(&data), good, "bit {i}");
+            data[i / 8] ^= 1 << (i % 8);
+        }
+    }
+
+    // ==================== Crc32 Tests ====================
+
+    #[test]
+    fn test_incremental_matches_oneshot() {
+        let data = b"123456789";
+        for split in 0..=data.len() {
+            let mut crc = Crc32::new();
+            crc.update(&data[..split]);
+            crc.update(&data[split..]);
+            assert_eq!(crc.finish(), 0xCBF4_3926, "split {split}");
+        }
+    }
+
+    #[test]
+    fn test_finish_does_not_consume() {
+        let mut crc = Crc32::default();
+        crc.update(b"abc");
+        assert_eq!(crc.finish(), crc.finish());
+        assert_eq!(crc.finish(), crc32(b"abc"));
+    }
+
+    // ==================== crc16() Tests ====================
+
+    #[test]
+    fn test_crc16_table_known_entries() {
+        assert_eq!(CRC16_TABLE[0], 0);
+        assert_eq!(CRC16_TABLE[1], 0x1021);
+        assert_eq!(CRC16_TABLE[255], 0x1EF0);
+    }
+
+    #[test]
+    fn test_crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_crc16_empty() {
+        assert_eq!(crc16(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn test_crc16_detects_single_bit_flip() {
+        let mut data = *b"ping";
+        let good = crc16(&data);
+        for i in 0..data.len() * 8 {
+            data[i / 8] ^= 1 << (i % 8);
+            assert_ne!(crc16(&data), good, "bit {i}");
+            data[i / 8] ^= 1 << (i % 8);
+        }
+    }
+}
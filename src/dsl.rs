@@ -0,0 +1,720 @@
+/*
+ * @file dsl.rs
+ * @brief Text pattern language parser
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: dsl.rs
+//!
+//! DESCRIPTION:
+//! Blink Pattern Text Language for RP2350.
+//!
+//! BRIEF:
+//! Parses a small human-readable pattern language into a Pattern,
+//! e.g. `on 100ms, off 200ms, repeat 3 { on 50ms, off 50ms }, off 1s`.
+//! Items are separated by commas or newlines, `#` starts a comment,
+//! and errors report the line and column they occurred at.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::LedState;
+use crate::pattern::{MAX_SEGMENT_MS, Pattern, PatternError, Segment};
+
+/// Maximum nesting depth of `repeat` blocks.
+///
+/// # Details
+/// Bounds parser recursion, and so stack use, on the target.
+///
+/// # Value
+/// 4 levels
+#[allow(dead_code)]
+pub const MAX_DEPTH: usize = 4;
+
+/// Kinds of parse error.
+///
+/// # Variants
+/// * `UnexpectedChar` - Character that cannot start a token
+/// * `UnexpectedToken` - Token not valid at this point
+/// * `UnknownKeyword` - Word other than `on`, `off` or `repeat`
+/// * `ExpectedNumber` - Duration or repeat count missing
+/// * `ExpectedUnit` - Duration missing its `ms` or `s` unit
+/// * `UnknownUnit` - Unit other than `ms` or `s`
+/// * `ExpectedOpenBrace` - `repeat N` not followed by `{`
+/// * `UnclosedBlock` - `{` without a matching `}`
+/// * `Overflow` - Number too large or duration over `MAX_SEGMENT_MS`
+/// * `ZeroDuration` - Segment lasting 0 ms
+/// * `ZeroRepeat` - `repeat 0`
+/// * `TooDeep` - More than `MAX_DEPTH` nested blocks
+/// * `TooManySegments` - Expansion exceeds the pattern capacity
+/// * `Empty` - Program or block with no segments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    UnexpectedToken,
+    UnknownKeyword,
+    ExpectedNumber,
+    ExpectedUnit,
+    UnknownUnit,
+    ExpectedOpenBrace,
+    UnclosedBlock,
+    Overflow,
+    ZeroDuration,
+    ZeroRepeat,
+    TooDeep,
+    TooManySegments,
+    Empty,
+}
+
+/// Parse error with source position.
+///
+/// # Fields
+/// * `line` - 1-based line number
+/// * `column` - 1-based column, counted in characters
+/// * `kind` - What went wrong
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct ParseError {
+    pub line: u32,
+    pub column: u32,
+    pub kind: ParseErrorKind,
+}
+
+/// Parses pattern text into a looping Pattern.
+///
+/// # Details
+/// `on` and `off` take a duration such as `250ms` or `2s`;
+/// `repeat N { ... }` expands its body N times. Keywords and
+/// units are case-insensitive.
+///
+/// # Arguments
+/// * `source` - Pattern text
+///
+/// # Returns
+/// * `Result<Pattern, ParseError>` - Pattern or the first error found
+#[allow(dead_code)]
+pub fn parse(source: &str) -> Result<Pattern, ParseError> {
+    let mut parser = Parser {
+        lexer: Lexer::new(source),
+        peeked: None,
+    };
+    parser.sequence(0, None)
+}
+
+/// Lexical token.
+///
+/// # Variants
+/// * `Word` - Run of ASCII letters
+/// * `Number` - Run of ASCII digits
+/// * `Open` - `{`
+/// * `Close` - `}`
+/// * `Separator` - `,` or newline
+/// * `End` - End of input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(u64),
+    Open,
+    Close,
+    Separator,
+    End,
+}
+
+/// Token with the position it started at.
+///
+/// # Fields
+/// * `token` - Token
+/// * `line` - 1-based line number
+/// * `column` - 1-based column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Spanned<'a> {
+    token: Token<'a>,
+    line: u32,
+    column: u32,
+}
+
+impl Spanned<'_> {
+    /// Builds an error at this token's position.
+    ///
+    /// # Arguments
+    /// * `kind` - Error kind
+    ///
+    /// # Returns
+    /// * `ParseError` - Positioned error
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            kind,
+        }
+    }
+}
+
+/// Splits source text into tokens.
+///
+/// # Fields
+/// * `source` - Full source text
+/// * `pos` - Byte offset of the next character
+/// * `line` - Line of the next character
+/// * `column` - Column of the next character
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> Lexer<'a> {
+    /// Creates a lexer at the start of the source.
+    ///
+    /// # Arguments
+    /// * `source` - Source text
+    ///
+    /// # Returns
+    /// * `Self` - New Lexer instance
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Returns the next character without consuming it.
+    ///
+    /// # Returns
+    /// * `Option<char>` - Next character, None at end of input
+    fn peek_char(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    /// Consumes one character, tracking line and column.
+    ///
+    /// # Returns
+    /// * `Option<char>` - Consumed character, None at end of input
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Consumes characters while they match a predicate.
+    ///
+    /// # Arguments
+    /// * `pred` - Character predicate
+    ///
+    /// # Returns
+    /// * `&'a str` - Consumed text
+    fn take_while(&mut self, pred: fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek_char().is_some_and(pred) {
+            self.bump();
+        }
+        &self.source[start..self.pos]
+    }
+
+    /// Produces the next token.
+    ///
+    /// # Details
+    /// Skips spaces, tabs, carriage returns and `#` comments.
+    ///
+    /// # Returns
+    /// * `Result<Spanned<'a>, ParseError>` - Token or lexical error
+    fn next_token(&mut self) -> Result<Spanned<'a>, ParseError> {
+        loop {
+            match self.peek_char() {
+                Some(' ' | '\t' | '\r') => {
+                    self.bump();
+                }
+                Some('#') => {
+                    self.take_while(|c| c != '\n');
+                }
+                _ => break,
+            }
+        }
+        let (line, column) = (self.line, self.column);
+        let spanned = |token| Spanned {
+            token,
+            line,
+            column,
+        };
+        let Some(c) = self.peek_char() else {
+            return Ok(spanned(Token::End));
+        };
+        let token = match c {
+            '{' | '}' | ',' | '\n' => {
+                self.bump();
+                match c {
+                    '{' => Token::Open,
+                    '}' => Token::Close,
+                    _ => Token::Separator,
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                let value = digits
+                    .bytes()
+                    .try_fold(0u64, |acc, d| {
+                        acc.checked_mul(10)?.checked_add(u64::from(d - b'0'))
+                    })
+                    .ok_or(spanned(Token::End).error(ParseErrorKind::Overflow))?;
+                Token::Number(value)
+            }
+            c if c.is_ascii_alphabetic() => {
+                Token::Word(self.take_while(|c| c.is_ascii_alphabetic()))
+            }
+            c => return Err(spanned(Token::End).error(ParseErrorKind::UnexpectedChar(c))),
+        };
+        Ok(spanned(token))
+    }
+}
+
+/// Recursive-descent parser with one token of lookahead.
+///
+/// # Fields
+/// * `lexer` - Token source
+/// * `peeked` - Lookahead token
+struct Parser<'a> {
+    lexer: Lexer<'a>,
+    peeked: Option<Spanned<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the next token without consuming it.
+    ///
+    /// # Returns
+    /// * `Result<Spanned<'a>, ParseError>` - Token or lexical error
+    fn peek(&mut self) -> Result<Spanned<'a>, ParseError> {
+        if let Some(token) = self.peeked {
+            return Ok(token);
+        }
+        let token = self.lexer.next_token()?;
+        self.peeked = Some(token);
+        Ok(token)
+    }
+
+    /// Consumes the next token.
+    ///
+    /// # Returns
+    /// * `Result<Spanned<'a>, ParseError>` - Token or lexical error
+    fn next(&mut self) -> Result<Spanned<'a>, ParseError> {
+        let token = self.peek()?;
+        self.peeked = None;
+        Ok(token)
+    }
+
+    /// Parses items up to the end of input or a closing brace.
+    ///
+    /// # Arguments
+    /// * `depth` - Current block nesting
+    /// * `open` - Opening brace of this block, None at top level
+    ///
+    /// # Returns
+    /// * `Result<Pattern, ParseError>` - Segments of the sequence
+    fn sequence(&mut self, depth: usize, open: Option<Spanned<'a>>) -> Result<Pattern, ParseError> {
+        let mut pattern = Pattern::new();
+        loop {
+            let token = self.next()?;
+            match (token.token, open) {
+                (Token::Separator, _) => continue,
+                (Token::End, None) | (Token::Close, Some(_)) => {
+                    if pattern.is_empty() {
+                        return Err(token.error(ParseErrorKind::Empty));
+                    }
+                    return Ok(pattern);
+                }
+                (Token::End, Some(open)) => return Err(open.error(ParseErrorKind::UnclosedBlock)),
+                (Token::Word(word), _) => self.item(&mut pattern, word, token, depth)?,
+                _ => return Err(token.error(ParseErrorKind::UnexpectedToken)),
+            }
+            let after = self.peek()?;
+            if !matches!(after.token, Token::Separator | Token::Close | Token::End) {
+                return Err(after.error(ParseErrorKind::UnexpectedToken));
+            }
+        }
+    }
+
+    /// Parses one `on`, `off` or `repeat` item.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to append to
+    /// * `word` - Keyword already consumed
+    /// * `at` - Keyword token, for error positions
+    /// * `depth` - Current block nesting
+    ///
+    /// # Returns
+    /// * `Result<(), ParseError>` - Ok or parse error
+    fn item(
+        &mut self,
+        pattern: &mut Pattern,
+        word: &str,
+        at: Spanned<'a>,
+        depth: usize,
+    ) -> Result<(), ParseError> {
+        let state = if word.eq_ignore_ascii_case("on") {
+            LedState::On
+        } else if word.eq_ignore_ascii_case("off") {
+            LedState::Off
+        } else if word.eq_ignore_ascii_case("repeat") {
+            return self.repeat(pattern, depth);
+        } else {
+            return Err(at.error(ParseErrorKind::UnknownKeyword));
+        };
+        let duration_ms = self.duration()?;
+        push(pattern, Segment { state, duration_ms }, at)
+    }
+
+    /// Parses a duration such as `250ms` or `2 s`.
+    ///
+    /// # Details
+    /// Durations over `MAX_SEGMENT_MS` are reported as `Overflow`
+    /// at the number.
+    ///
+    /// # Returns
+    /// * `Result<u64, ParseError>` - Duration in milliseconds
+    fn duration(&mut self) -> Result<u64, ParseError> {
+        let number = self.next()?;
+        let Token::Number(value) = number.token else {
+            return Err(number.error(ParseErrorKind::ExpectedNumber));
+        };
+        let unit = self.next()?;
+        let Token::Word(name) = unit.token else {
+            return Err(unit.error(ParseErrorKind::ExpectedUnit));
+        };
+        let scale = if name.eq_ignore_ascii_case("ms") {
+            1
+        } else if name.eq_ignore_ascii_case("s") {
+            1000
+        } else {
+            return Err(unit.error(ParseErrorKind::UnknownUnit));
+        };
+        let ms = value
+            .checked_mul(scale)
+            .filter(|ms| *ms <= MAX_SEGMENT_MS)
+            .ok_or(number.error(ParseErrorKind::Overflow))?;
+        if ms == 0 {
+            return Err(number.error(ParseErrorKind::ZeroDuration));
+        }
+        Ok(ms)
+    }
+
+    /// Parses `N { ... }` after the `repeat` keyword.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to append the expansion to
+    /// * `depth` - Current block nesting
+    ///
+    /// # Returns
+    /// * `Result<(), ParseError>` - Ok or parse error
+    fn repeat(&mut self, pattern: &mut Pattern, depth: usize) -> Result<(), ParseError> {
+        let count = self.next()?;
+        let Token::Number(times) = count.token else {
+            return Err(count.error(ParseErrorKind::ExpectedNumber));
+        };
+        if times == 0 {
+            return Err(count.error(ParseErrorKind::ZeroRepeat));
+        }
+        let open = self.next()?;
+        if open.token != Token::Open {
+            return Err(open.error(ParseErrorKind::ExpectedOpenBrace));
+        }
+        if depth + 1 > MAX_DEPTH {
+            return Err(open.error(ParseErrorKind::TooDeep));
+        }
+        let body = self.sequence(depth + 1, Some(open))?;
+        for _ in 0..times {
+            for segment in body.segments() {
+                push(pattern, *segment, open)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Appends a segment, mapping capacity errors to a position.
+///
+/// # Arguments
+/// * `pattern` - Pattern to append to
+/// * `segment` - Segment to add
+/// * `at` - Token to report on failure
+///
+/// # Returns
+/// * `Result<(), ParseError>` - Ok or `TooManySegments`
+fn push(pattern: &mut Pattern, segment: Segment, at: Spanned<'_>) -> Result<(), ParseError> {
+    pattern.push(segment).map_err(|e| match e {
+        PatternError::ZeroDuration => at.error(ParseErrorKind::ZeroDuration),
+        PatternError::DurationTooLong => at.error(ParseErrorKind::Overflow),
+        _ => at.error(ParseErrorKind::TooManySegments),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(source: &str) -> (u32, u32, ParseErrorKind) {
+        let e = parse(source).unwrap_err();
+        (e.line, e.column, e.kind)
+    }
+
+    // ==================== Basic Parsing Tests ====================
+
+    #[test]
+    fn test_parse_single_on() {
+        let p = parse("on 100ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(100)]);
+    }
+
+    #[test]
+    fn test_parse_on_off() {
+        let p = parse("on 100ms, off 200ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(100), Segment::off(200)]);
+    }
+
+    #[test]
+    fn test_parse_matches_preset() {
+        assert_eq!(parse("on 500ms, off 500ms").unwrap(), Pattern::blink(500));
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        let p = parse("off 2s").unwrap();
+        assert_eq!(p.segments(), &[Segment::off(2000)]);
+    }
+
+    #[test]
+    fn test_parse_space_before_unit() {
+        let p = parse("on 5 ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(5)]);
+    }
+
+    #[test]
+    fn test_parse_case_insensitive() {
+        let p = parse("ON 1S, Off 10Ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(1000), Segment::off(10)]);
+    }
+
+    #[test]
+    fn test_parse_newline_separators() {
+        let p = parse("on 1ms\noff 2ms\r\n\non 3ms\n").unwrap();
+        assert_eq!(p.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_trailing_comma() {
+        assert_eq!(parse("on 1ms,").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let p = parse("# status blink\non 1ms # short\noff 2ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(1), Segment::off(2)]);
+    }
+
+    #[test]
+    fn test_parse_loops_by_default() {
+        let p = parse("on 1ms").unwrap();
+        assert_eq!(p, Pattern::from_segments(&[Segment::on(1)]).unwrap());
+    }
+
+    // ==================== Repeat Tests ====================
+
+    #[test]
+    fn test_parse_example_from_spec() {
+        let p = parse("on 100ms, off 200ms, repeat 3 { on 50ms, off 50ms }, off 1s").unwrap();
+        let mut expected = vec![Segment::on(100), Segment::off(200)];
+        for _ in 0..3 {
+            expected.push(Segment::on(50));
+            expected.push(Segment::off(50));
+        }
+        expected.push(Segment::off(1000));
+        assert_eq!(p.segments(), expected.as_slice());
+    }
+
+    #[test]
+    fn test_parse_repeat_once() {
+        assert_eq!(parse("repeat 1 { on 1ms }").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_nested_repeat() {
+        let p = parse("repeat 2 { repeat 3 { on 1ms, off 1ms }, off 5ms }").unwrap();
+        assert_eq!(p.len(), 14);
+        assert_eq!(p.period_ms(), 22);
+    }
+
+    #[test]
+    fn test_parse_multiline_block() {
+        let p = parse("repeat 2 {\n  on 1ms\n  off 1ms\n}\noff 3ms").unwrap();
+        assert_eq!(p.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_max_depth_allowed() {
+        let src = "repeat 1 { repeat 1 { repeat 1 { repeat 1 { on 1ms } } } }";
+        assert_eq!(parse(src).unwrap().len(), 1);
+    }
+
+    // ==================== Error Tests ====================
+
+    #[test]
+    fn test_error_empty_input() {
+        assert_eq!(err(""), (1, 1, ParseErrorKind::Empty));
+        assert_eq!(err(" # only a comment\n"), (2, 1, ParseErrorKind::Empty));
+    }
+
+    #[test]
+    fn test_error_unknown_keyword() {
+        assert_eq!(
+            err("on 1ms, blink 2ms"),
+            (1, 9, ParseErrorKind::UnknownKeyword)
+        );
+    }
+
+    #[test]
+    fn test_error_unexpected_char() {
+        assert_eq!(err("on 1ms;"), (1, 7, ParseErrorKind::UnexpectedChar(';')));
+    }
+
+    #[test]
+    fn test_error_missing_number() {
+        assert_eq!(err("off ms"), (1, 5, ParseErrorKind::ExpectedNumber));
+    }
+
+    #[test]
+    fn test_error_missing_unit() {
+        assert_eq!(err("on 100"), (1, 7, ParseErrorKind::ExpectedUnit));
+        assert_eq!(err("on 100, off 1ms"), (1, 7, ParseErrorKind::ExpectedUnit));
+    }
+
+    #[test]
+    fn test_error_unknown_unit() {
+        assert_eq!(err("on 3min"), (1, 5, ParseErrorKind::UnknownUnit));
+    }
+
+    #[test]
+    fn test_error_position_on_later_line() {
+        assert_eq!(
+            err("on 1ms\noff 2ms\n  on 0ms"),
+            (3, 6, ParseErrorKind::ZeroDuration)
+        );
+    }
+
+    #[test]
+    fn test_error_missing_separator() {
+        assert_eq!(
+            err("on 1ms off 1ms"),
+            (1, 8, ParseErrorKind::UnexpectedToken)
+        );
+    }
+
+    #[test]
+    fn test_error_stray_close() {
+        assert_eq!(err("on 1ms }"), (1, 8, ParseErrorKind::UnexpectedToken));
+    }
+
+    #[test]
+    fn test_error_unclosed_block() {
+        assert_eq!(
+            err("repeat 2 {\n on 1ms"),
+            (1, 10, ParseErrorKind::UnclosedBlock)
+        );
+    }
+
+    #[test]
+    fn test_error_missing_brace() {
+        assert_eq!(
+            err("repeat 2 on 1ms"),
+            (1, 10, ParseErrorKind::ExpectedOpenBrace)
+        );
+    }
+
+    #[test]
+    fn test_error_zero_repeat() {
+        assert_eq!(
+            err("repeat 0 { on 1ms }"),
+            (1, 8, ParseErrorKind::ZeroRepeat)
+        );
+    }
+
+    #[test]
+    fn test_error_empty_block() {
+        assert_eq!(err("repeat 2 { }"), (1, 12, ParseErrorKind::Empty));
+    }
+
+    #[test]
+    fn test_error_too_deep() {
+        let src = "repeat 1 { repeat 1 { repeat 1 { repeat 1 { repeat 1 { on 1ms } } } } }";
+        assert_eq!(err(src), (1, 54, ParseErrorKind::TooDeep));
+    }
+
+    #[test]
+    fn test_error_too_many_segments() {
+        // 66 segments, two more than fit
+        let src = "repeat 33 { on 1ms, off 1ms }";
+        assert_eq!(err(src), (1, 11, ParseErrorKind::TooManySegments));
+    }
+
+    #[test]
+    fn test_error_number_overflow() {
+        assert_eq!(
+            err("on 99999999999999999999ms"),
+            (1, 4, ParseErrorKind::Overflow)
+        );
+        assert_eq!(
+            err("on 18446744073709552s"),
+            (1, 4, ParseErrorKind::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_error_duration_too_long() {
+        assert_eq!(err("on 4294967296ms"), (1, 4, ParseErrorKind::Overflow));
+        assert_eq!(
+            err("on 1ms, off 4294968s"),
+            (1, 13, ParseErrorKind::Overflow)
+        );
+        let p = parse("on 4294967295ms").unwrap();
+        assert_eq!(p.segments(), &[Segment::on(MAX_SEGMENT_MS)]);
+    }
+
+    #[test]
+    fn test_error_columns_count_chars() {
+        assert_eq!(
+            err("# é\non 1ms, ü"),
+            (2, 9, ParseErrorKind::UnexpectedChar('ü'))
+        );
+    }
+}
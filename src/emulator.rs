@@ -0,0 +1,362 @@
+/*
+ * @file emulator.rs
+ * @brief Host emulator of the board's console, LED handler and settings
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: emulator.rs
+//!
+//! DESCRIPTION:
+//! Host Device Emulator for RP2350 Blink Logic.
+//!
+//! BRIEF:
+//! Wires the firmware's Console, CommandHandler and SettingsStore
+//! together the way the board does, on an in-memory flash and a
+//! caller-supplied clock, so host tools and CI can talk to a
+//! board that is not there over the real control protocol.
+//! Only built for host tests or with the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::command::CommandHandler;
+use crate::config::{ARBITER_SLOTS, SETTINGS_SIZE, SHELL_LINE_LEN};
+use crate::led::LedState;
+use crate::mock::MemFlash;
+use crate::settings::{Settings, SettingsStore};
+use crate::shell::{Console, ShellAction};
+use embedded_storage::nor_flash::NorFlash;
+use std::vec::Vec;
+
+/// Emulated settings region size in flash sectors.
+pub const FLASH_SECTORS: usize = SETTINGS_SIZE as usize / MemFlash::ERASE_SIZE;
+
+/// Emulated board.
+///
+/// # Details
+/// Boots like the firmware: settings are loaded from flash, the
+/// LED starts on the saved pattern and the console serves both
+/// the shell and the control protocol. Times are absolute
+/// milliseconds from the caller's clock; the LED timeline
+/// restarts at every boot. `reset` reboots from the same flash,
+/// so only saved settings survive it.
+///
+/// # Fields
+/// * `console` - Serial console
+/// * `handler` - LED task state
+/// * `store` - Settings store, None if the region is unusable
+/// * `boot_ms` - Time of the last boot
+/// * `boots` - Number of boots so far
+#[derive(Debug)]
+pub struct Emulator {
+    console: Console<SHELL_LINE_LEN>,
+    handler: CommandHandler<ARBITER_SLOTS>,
+    store: Option<SettingsStore<MemFlash>>,
+    boot_ms: u64,
+    boots: u32,
+}
+
+impl Emulator {
+    /// Boots a board with erased flash.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Running emulator with default settings
+    pub fn new(now_ms: u64) -> Self {
+        Self::boot(MemFlash::new(FLASH_SECTORS), now_ms, 1)
+    }
+
+    /// Boots a board from a saved flash image.
+    ///
+    /// # Details
+    /// The image is cut or padded with 0xFF to the settings
+    /// region size.
+    ///
+    /// # Arguments
+    /// * `image` - Settings region contents
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Running emulator with the stored settings
+    pub fn from_image(image: &[u8], now_ms: u64) -> Self {
+        let mut data = image.to_vec();
+        data.resize(SETTINGS_SIZE as usize, 0xFF);
+        Self::boot(MemFlash::from_data(data), now_ms, 1)
+    }
+
+    /// Starts the firmware on a flash device.
+    ///
+    /// # Arguments
+    /// * `flash` - Settings region
+    /// * `now_ms` - Current time in milliseconds
+    /// * `boots` - Boot count including this one
+    ///
+    /// # Returns
+    /// * `Self` - Running emulator
+    fn boot(flash: MemFlash, now_ms: u64, boots: u32) -> Self {
+        let store = SettingsStore::new(flash, 0, SETTINGS_SIZE).ok();
+        let settings = store
+            .as_ref()
+            .map_or_else(Settings::default, |s| *s.settings());
+        Self {
+            console: Console::new(settings),
+            handler: CommandHandler::new(settings.startup_pattern()),
+            store,
+            boot_ms: now_ms,
+            boots,
+        }
+    }
+
+    /// Feeds bytes received from the host.
+    ///
+    /// # Details
+    /// Bytes after a `reset` are dropped, as the board would
+    /// lose them while rebooting.
+    ///
+    /// # Arguments
+    /// * `bytes` - Received bytes
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - Bytes the board sends back
+    pub fn feed(&mut self, bytes: &[u8], now_ms: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        let uptime = now_ms.saturating_sub(self.boot_ms);
+        self.handler.advance(uptime);
+        for &byte in bytes {
+            let store = &mut self.store;
+            let (output, action) = self.console.push(byte, self.handler.state(), |settings| {
+                store.as_mut().is_some_and(|s| s.save(settings).is_ok())
+            });
+            out.extend_from_slice(output);
+            match action {
+                ShellAction::Led(command) => {
+                    let _ = self.handler.handle(command, uptime);
+                }
+                ShellAction::Reset => {
+                    self.reboot(now_ms);
+                    break;
+                }
+                ShellAction::None | ShellAction::Save(_) => {}
+            }
+        }
+        out
+    }
+
+    /// Brings the LED up to a point in time.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `LedState` - LED state at `now_ms`
+    pub fn advance(&mut self, now_ms: u64) -> LedState {
+        self.handler.advance(now_ms.saturating_sub(self.boot_ms))
+    }
+
+    /// Returns when the LED next changes.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Absolute time in milliseconds, None while held
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.handler
+            .next_deadline()
+            .map(|ms| ms.saturating_add(self.boot_ms))
+    }
+
+    /// Reboots the board.
+    ///
+    /// # Details
+    /// Unsaved settings and LED commands are lost; the flash is
+    /// kept. A board whose store failed to open boots from
+    /// erased flash.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Current time in milliseconds
+    pub fn reboot(&mut self, now_ms: u64) {
+        let flash = self
+            .store
+            .take()
+            .map_or_else(|| MemFlash::new(FLASH_SECTORS), SettingsStore::release);
+        *self = Self::boot(flash, now_ms, self.boots + 1);
+    }
+
+    /// Ends the host's session.
+    ///
+    /// # Details
+    /// Call when the host closes the port, as USB does on the
+    /// board.
+    pub fn disconnect(&mut self) {
+        self.console.reset();
+    }
+
+    /// Returns the LED state as of the last update.
+    ///
+    /// # Returns
+    /// * `LedState` - Current LED state
+    pub fn led(&self) -> LedState {
+        self.handler.state()
+    }
+
+    /// Returns the settings the console is working with.
+    ///
+    /// # Returns
+    /// * `&Settings` - Settings including unsaved changes
+    pub fn settings(&self) -> &Settings {
+        self.console.shell().settings()
+    }
+
+    /// Returns the number of boots.
+    ///
+    /// # Returns
+    /// * `u32` - Boots including the first
+    pub fn boots(&self) -> u32 {
+        self.boots
+    }
+
+    /// Returns the settings region contents.
+    ///
+    /// # Returns
+    /// * `Option<&[u8]>` - Flash image, None if the store failed to open
+    pub fn image(&self) -> Option<&[u8]> {
+        self.store.as_ref().map(|s| s.flash().data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BlinkConfig;
+    use crate::protocol::{MAX_FRAME_LEN, Packet, Request, Response, decode_frame, encode_frame};
+
+    fn request(seq: u8, request: &Request) -> Vec<u8> {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, request, &mut frame).unwrap();
+        frame[..len].to_vec()
+    }
+
+    fn response(out: &[u8]) -> Packet<Response> {
+        decode_frame(&out[1..out.len() - 1]).unwrap()
+    }
+
+    // ==================== Boot Tests ====================
+
+    #[test]
+    fn test_boots_with_default_blink() {
+        let mut emulator = Emulator::new(1000);
+        assert_eq!(emulator.advance(1000), LedState::On);
+        assert_eq!(emulator.next_deadline(), Some(1500));
+        assert_eq!(emulator.advance(1500), LedState::Off);
+        assert_eq!(emulator.boots(), 1);
+        assert_eq!(*emulator.settings(), Settings::default());
+    }
+
+    #[test]
+    fn test_deadline_saturates_after_late_boot() {
+        let emulator = Emulator::new(u64::MAX - 10);
+        assert_eq!(emulator.next_deadline(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_blank_image_boots_with_defaults() {
+        let emulator = Emulator::from_image(&[], 0);
+        assert_eq!(*emulator.settings(), Settings::default());
+        assert_eq!(emulator.image().unwrap().len(), SETTINGS_SIZE as usize);
+    }
+
+    // ==================== Console Tests ====================
+
+    #[test]
+    fn test_text_command_changes_rate() {
+        let mut emulator = Emulator::new(0);
+        assert_eq!(emulator.feed(b"rate 100\r", 0), b"rate 100\r\r\nok\r\n");
+        assert_eq!(emulator.next_deadline(), Some(100));
+        assert_eq!(emulator.advance(100), LedState::Off);
+    }
+
+    #[test]
+    fn test_frame_request_is_answered() {
+        let mut emulator = Emulator::new(0);
+        let config = BlinkConfig::new(50, 150).unwrap();
+        let out = emulator.feed(&request(7, &Request::SetConfig(config)), 0);
+        assert_eq!(
+            response(&out),
+            Packet {
+                seq: 7,
+                message: Response::Ack
+            }
+        );
+        assert_eq!(emulator.advance(50), LedState::Off);
+        let out = emulator.feed(&request(8, &Request::GetState), 60);
+        assert_eq!(response(&out).message, Response::State(LedState::Off));
+    }
+
+    // ==================== Persistence Tests ====================
+
+    #[test]
+    fn test_saved_settings_survive_reset() {
+        let mut emulator = Emulator::new(0);
+        emulator.feed(b"rate 200\rsave\r", 0);
+        assert_eq!(emulator.feed(b"reset\rstatus\r", 10), b"reset\r\r\n");
+        assert_eq!(emulator.boots(), 2);
+        assert_eq!(
+            emulator.settings().blink,
+            BlinkConfig::new(200, 200).unwrap()
+        );
+        assert_eq!(emulator.next_deadline(), Some(210));
+    }
+
+    #[test]
+    fn test_unsaved_settings_are_lost_on_reset() {
+        let mut emulator = Emulator::new(0);
+        emulator.feed(b"rate 200\rreset\r", 0);
+        assert_eq!(*emulator.settings(), Settings::default());
+    }
+
+    #[test]
+    fn test_image_restores_settings() {
+        let mut emulator = Emulator::new(0);
+        emulator.feed(b"rate 300 700\rsave\r", 0);
+        let image = emulator.image().unwrap().to_vec();
+        let restored = Emulator::from_image(&image, 0);
+        assert_eq!(
+            restored.settings().blink,
+            BlinkConfig::new(300, 700).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_disconnect_drops_partial_line() {
+        let mut emulator = Emulator::new(0);
+        emulator.feed(b"bogus", 0);
+        emulator.disconnect();
+        assert_eq!(emulator.feed(b"on\r", 0), b"on\r\r\nok\r\n");
+        assert_eq!(emulator.led(), LedState::On);
+    }
+}
@@ -0,0 +1,578 @@
+/*
+ * @file fade.rs
+ * @brief Fade and breathing brightness effects
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: fade.rs
+//!
+//! DESCRIPTION:
+//! Fade and Breathing Effects for RP2350 LED Brightness.
+//!
+//! BRIEF:
+//! Pure brightness sample generators driven by elapsed time.
+//! Ramps between two levels with linear, sine, quadratic or
+//! exponential easing, and loops them into a breathing effect.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::brightness::{Brightness, interpolate};
+
+/// Number of entries in the easing lookup tables.
+///
+/// # Value
+/// 257 entries
+const EASING_TABLE_SIZE: usize = 257;
+
+/// Full-scale fixed-point progress value.
+const PROGRESS_MAX: u64 = u16::MAX as u64;
+
+/// Sine ease-in-out table, (1 - cos(pi * t)) / 2.
+///
+/// # Details
+/// Generated at compile time.
+static SINE_TABLE: [u16; EASING_TABLE_SIZE] = build_table(Easing::Sine);
+
+/// Exponential ease-in-out table.
+///
+/// # Details
+/// 2^(20t - 10) / 2 for the first half, mirrored for the second.
+/// Generated at compile time.
+static EXPO_TABLE: [u16; EASING_TABLE_SIZE] = build_table(Easing::Exponential);
+
+/// Builds an easing lookup table.
+///
+/// # Arguments
+/// * `easing` - Table-backed easing curve, Sine or Exponential
+///
+/// # Returns
+/// * `[u16; EASING_TABLE_SIZE]` - Curve scaled to 0-65535
+const fn build_table(easing: Easing) -> [u16; EASING_TABLE_SIZE] {
+    let mut table = [0u16; EASING_TABLE_SIZE];
+    let mut i = 0;
+    while i < EASING_TABLE_SIZE {
+        let t = i as f64 / (EASING_TABLE_SIZE - 1) as f64;
+        let y = match easing {
+            Easing::Exponential => expo_in_out(t),
+            _ => sine_in_out(t),
+        };
+        table[i] = (y * u16::MAX as f64 + 0.5) as u16;
+        i += 1;
+    }
+    table
+}
+
+/// Cosine by Taylor series, accurate on 0 to pi.
+///
+/// # Arguments
+/// * `x` - Angle in radians
+///
+/// # Returns
+/// * `f64` - cos(x)
+const fn cos(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1;
+    while k <= 15 {
+        term = -term * x * x / ((2 * k - 1) * (2 * k)) as f64;
+        sum += term;
+        k += 1;
+    }
+    sum
+}
+
+/// Power of two for real exponents.
+///
+/// # Details
+/// Splits the exponent into integer and fractional parts and
+/// evaluates the fractional part with an exp Taylor series.
+///
+/// # Arguments
+/// * `x` - Exponent
+///
+/// # Returns
+/// * `f64` - 2^x
+const fn exp2(x: f64) -> f64 {
+    let n = x as i32;
+    let y = (x - n as f64) * core::f64::consts::LN_2;
+    let mut frac = 1.0;
+    let mut term = 1.0;
+    let mut k = 1;
+    while k <= 20 {
+        term = term * y / k as f64;
+        frac += term;
+        k += 1;
+    }
+    let mut scale = 1.0;
+    let mut i = 0;
+    while i < n.unsigned_abs() {
+        scale *= 2.0;
+        i += 1;
+    }
+    if n < 0 { frac / scale } else { frac * scale }
+}
+
+/// Sine ease-in-out curve.
+///
+/// # Arguments
+/// * `t` - Progress, 0.0-1.0
+///
+/// # Returns
+/// * `f64` - Eased progress
+const fn sine_in_out(t: f64) -> f64 {
+    (1.0 - cos(core::f64::consts::PI * t)) / 2.0
+}
+
+/// Exponential ease-in-out curve.
+///
+/// # Details
+/// Pinned to exactly 0 and 1 at the ends.
+///
+/// # Arguments
+/// * `t` - Progress, 0.0-1.0
+///
+/// # Returns
+/// * `f64` - Eased progress
+const fn expo_in_out(t: f64) -> f64 {
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else if t < 0.5 {
+        exp2(20.0 * t - 10.0) / 2.0
+    } else {
+        (2.0 - exp2(10.0 - 20.0 * t)) / 2.0
+    }
+}
+
+/// Easing curve selection.
+///
+/// # Details
+/// All curves ease in and out, starting and ending slowly.
+///
+/// # Variants
+/// * `Linear` - Constant rate
+/// * `Sine` - Half cosine wave
+/// * `Quadratic` - Second-order polynomial
+/// * `Exponential` - Power-of-two ramps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Easing {
+    Linear,
+    Sine,
+    Quadratic,
+    Exponential,
+}
+
+impl Easing {
+    /// Applies the easing curve.
+    ///
+    /// # Arguments
+    /// * `progress` - Linear progress, 0-65535
+    ///
+    /// # Returns
+    /// * `u16` - Eased progress, 0-65535
+    #[allow(dead_code)]
+    pub fn apply(&self, progress: u16) -> u16 {
+        match self {
+            Easing::Linear => progress,
+            Easing::Sine => interpolate(&SINE_TABLE, progress),
+            Easing::Quadratic => {
+                let t = u64::from(progress);
+                if t < PROGRESS_MAX / 2 + 1 {
+                    (2 * t * t / PROGRESS_MAX) as u16
+                } else {
+                    let r = PROGRESS_MAX - t;
+                    (PROGRESS_MAX - 2 * r * r / PROGRESS_MAX) as u16
+                }
+            }
+            Easing::Exponential => interpolate(&EXPO_TABLE, progress),
+        }
+    }
+}
+
+/// Time-driven brightness effect.
+///
+/// # Details
+/// Implemented by sample generators so a single loop can play any
+/// of them by sampling elapsed time.
+#[allow(dead_code)]
+pub trait Effect {
+    /// Returns the brightness at a point in time.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the effect started
+    ///
+    /// # Returns
+    /// * `Brightness` - Brightness to display
+    fn sample(&self, elapsed_ms: u64) -> Brightness;
+
+    /// Returns the total effect length.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - Length in milliseconds, None if endless
+    fn duration_ms(&self) -> Option<u64>;
+}
+
+/// Single ramp between two brightness levels.
+///
+/// # Fields
+/// * `from` - Starting brightness
+/// * `to` - Final brightness
+/// * `duration_ms` - Ramp length in milliseconds
+/// * `easing` - Easing curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Fade {
+    pub from: Brightness,
+    pub to: Brightness,
+    pub duration_ms: u64,
+    pub easing: Easing,
+}
+
+impl Fade {
+    /// Creates a fade.
+    ///
+    /// # Arguments
+    /// * `from` - Starting brightness
+    /// * `to` - Final brightness
+    /// * `duration_ms` - Ramp length in milliseconds
+    /// * `easing` - Easing curve
+    ///
+    /// # Returns
+    /// * `Self` - New Fade instance
+    #[allow(dead_code)]
+    pub const fn new(from: Brightness, to: Brightness, duration_ms: u64, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration_ms,
+            easing,
+        }
+    }
+
+    /// Returns true once the ramp has reached its end.
+    ///
+    /// # Arguments
+    /// * `elapsed_ms` - Time since the fade started
+    ///
+    /// # Returns
+    /// * `bool` - true when complete
+    #[allow(dead_code)]
+    pub fn is_complete(&self, elapsed_ms: u64) -> bool {
+        elapsed_ms >= self.duration_ms
+    }
+}
+
+impl Effect for Fade {
+    /// Returns the eased brightness, holding `to` once complete.
+    fn sample(&self, elapsed_ms: u64) -> Brightness {
+        if self.is_complete(elapsed_ms) {
+            return self.to;
+        }
+        let progress = (elapsed_ms * PROGRESS_MAX / self.duration_ms) as u16;
+        let eased = i64::from(self.easing.apply(progress));
+        let from = i64::from(self.from.level());
+        let to = i64::from(self.to.level());
+        let level = from + (to - from) * eased / PROGRESS_MAX as i64;
+        Brightness::new(level as u16)
+    }
+
+    /// Returns the ramp length.
+    fn duration_ms(&self) -> Option<u64> {
+        Some(self.duration_ms)
+    }
+}
+
+/// Endless rise and fall between two brightness levels.
+///
+/// # Details
+/// The classic "breathing" status LED: fades up over `rise_ms`,
+/// back down over `fall_ms`, and repeats.
+///
+/// # Fields
+/// * `low` - Brightness at the bottom of each breath
+/// * `high` - Brightness at the top of each breath
+/// * `rise_ms` - Fade-up length in milliseconds
+/// * `fall_ms` - Fade-down length in milliseconds
+/// * `easing` - Easing curve for both halves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Breathing {
+    pub low: Brightness,
+    pub high: Brightness,
+    pub rise_ms: u64,
+    pub fall_ms: u64,
+    pub easing: Easing,
+}
+
+impl Breathing {
+    /// Creates a symmetric breathing effect.
+    ///
+    /// # Arguments
+    /// * `low` - Bottom brightness
+    /// * `high` - Top brightness
+    /// * `period_ms` - Length of one full breath
+    /// * `easing` - Easing curve
+    ///
+    /// # Returns
+    /// * `Self` - New Breathing instance
+    #[allow(dead_code)]
+    pub const fn new(low: Brightness, high: Brightness, period_ms: u64, easing: Easing) -> Self {
+        Self {
+            low,
+            high,
+            rise_ms: period_ms / 2,
+            fall_ms: period_ms - period_ms / 2,
+            easing,
+        }
+    }
+
+    /// Returns the length of one full breath.
+    ///
+    /// # Returns
+    /// * `u64` - Rise plus fall time in milliseconds
+    #[allow(dead_code)]
+    pub const fn period_ms(&self) -> u64 {
+        self.rise_ms + self.fall_ms
+    }
+}
+
+impl Effect for Breathing {
+    /// Returns the brightness within the current breath.
+    fn sample(&self, elapsed_ms: u64) -> Brightness {
+        let period = self.period_ms();
+        if period == 0 {
+            return self.high;
+        }
+        let phase = elapsed_ms % period;
+        if phase < self.rise_ms {
+            Fade::new(self.low, self.high, self.rise_ms, self.easing).sample(phase)
+        } else {
+            Fade::new(self.high, self.low, self.fall_ms, self.easing).sample(phase - self.rise_ms)
+        }
+    }
+
+    /// Breathing never ends.
+    fn duration_ms(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 4] = [
+        Easing::Linear,
+        Easing::Sine,
+        Easing::Quadratic,
+        Easing::Exponential,
+    ];
+
+    // ==================== Const Math Tests ====================
+
+    #[test]
+    fn test_cos_accuracy() {
+        for i in 0..=100 {
+            let x = core::f64::consts::PI * i as f64 / 100.0;
+            assert!((cos(x) - x.cos()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_exp2_accuracy() {
+        for i in -100..=100 {
+            let x = i as f64 / 10.0;
+            assert!((exp2(x) / x.exp2() - 1.0).abs() < 1e-9, "{x}");
+        }
+    }
+
+    // ==================== Easing Tests ====================
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0), 0, "{easing:?}");
+            assert_eq!(easing.apply(u16::MAX), u16::MAX, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn test_easing_midpoint() {
+        for easing in ALL {
+            let mid = easing.apply(u16::MAX / 2);
+            assert!(mid.abs_diff(u16::MAX / 2) < 400, "{easing:?}: {mid}");
+        }
+    }
+
+    #[test]
+    fn test_easing_monotonic() {
+        for easing in ALL {
+            let mut prev = 0;
+            for p in (0..=u16::MAX).step_by(61) {
+                let y = easing.apply(p);
+                assert!(y >= prev, "{easing:?} at {p}");
+                prev = y;
+            }
+        }
+    }
+
+    #[test]
+    fn test_easing_linear_identity() {
+        assert_eq!(Easing::Linear.apply(12345), 12345);
+    }
+
+    #[test]
+    fn test_easing_slow_start() {
+        let quarter = u16::MAX / 4;
+        for easing in [Easing::Sine, Easing::Quadratic, Easing::Exponential] {
+            assert!(easing.apply(quarter) < quarter, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn test_easing_quadratic_quarter() {
+        let y = Easing::Quadratic.apply(u16::MAX / 4);
+        assert!(y.abs_diff(u16::MAX / 8) < 4);
+    }
+
+    #[test]
+    fn test_easing_sine_quarter() {
+        // (1 - cos(pi / 4)) / 2 = 0.1464
+        let y = Easing::Sine.apply(u16::MAX / 4);
+        assert!(y.abs_diff(9596) < 20, "{y}");
+    }
+
+    #[test]
+    fn test_easing_exponential_steepest() {
+        let quarter = u16::MAX / 4;
+        assert!(Easing::Exponential.apply(quarter) < Easing::Quadratic.apply(quarter));
+    }
+
+    // ==================== Fade Tests ====================
+
+    #[test]
+    fn test_fade_start_and_end() {
+        let fade = Fade::new(Brightness::OFF, Brightness::FULL, 1000, Easing::Sine);
+        assert_eq!(fade.sample(0), Brightness::OFF);
+        assert_eq!(fade.sample(1000), Brightness::FULL);
+        assert_eq!(fade.sample(5000), Brightness::FULL);
+    }
+
+    #[test]
+    fn test_fade_linear_midpoint() {
+        let fade = Fade::new(Brightness::OFF, Brightness::new(1000), 100, Easing::Linear);
+        assert_eq!(fade.sample(50), Brightness::new(499));
+    }
+
+    #[test]
+    fn test_fade_down() {
+        let fade = Fade::new(Brightness::FULL, Brightness::OFF, 100, Easing::Linear);
+        assert!(fade.sample(25) > fade.sample(75));
+        assert_eq!(fade.sample(100), Brightness::OFF);
+    }
+
+    #[test]
+    fn test_fade_zero_duration() {
+        let fade = Fade::new(Brightness::OFF, Brightness::FULL, 0, Easing::Linear);
+        assert_eq!(fade.sample(0), Brightness::FULL);
+        assert!(fade.is_complete(0));
+    }
+
+    #[test]
+    fn test_fade_duration() {
+        let fade = Fade::new(Brightness::OFF, Brightness::FULL, 750, Easing::Linear);
+        assert_eq!(fade.duration_ms(), Some(750));
+        assert!(!fade.is_complete(749));
+        assert!(fade.is_complete(750));
+    }
+
+    #[test]
+    fn test_fade_stays_between_levels() {
+        let low = Brightness::from_percent(20);
+        let high = Brightness::from_percent(80);
+        for easing in ALL {
+            let fade = Fade::new(low, high, 1000, easing);
+            for t in (0..=1000).step_by(10) {
+                let b = fade.sample(t);
+                assert!(b >= low && b <= high, "{easing:?} at {t}");
+            }
+        }
+    }
+
+    // ==================== Breathing Tests ====================
+
+    #[test]
+    fn test_breathing_new_splits_period() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 3001, Easing::Sine);
+        assert_eq!(b.rise_ms, 1500);
+        assert_eq!(b.fall_ms, 1501);
+        assert_eq!(b.period_ms(), 3001);
+    }
+
+    #[test]
+    fn test_breathing_cycle_shape() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 2000, Easing::Sine);
+        assert_eq!(b.sample(0), Brightness::OFF);
+        assert_eq!(b.sample(1000), Brightness::FULL);
+        assert!(b.sample(500) > Brightness::OFF && b.sample(500) < Brightness::FULL);
+        assert!(b.sample(1999) < Brightness::from_percent(1));
+    }
+
+    #[test]
+    fn test_breathing_repeats() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 2000, Easing::Quadratic);
+        for t in (0..2000).step_by(37) {
+            assert_eq!(b.sample(t), b.sample(t + 2000));
+            assert_eq!(b.sample(t), b.sample(t + 20_000));
+        }
+    }
+
+    #[test]
+    fn test_breathing_symmetric() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 2000, Easing::Sine);
+        for t in (0..=1000).step_by(50) {
+            let up = b.sample(t).level();
+            let down = b.sample(2000 - t).level();
+            assert!(up.abs_diff(down) <= 8, "{t}");
+        }
+    }
+
+    #[test]
+    fn test_breathing_endless() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 2000, Easing::Linear);
+        assert_eq!(b.duration_ms(), None);
+    }
+
+    #[test]
+    fn test_breathing_zero_period() {
+        let b = Breathing::new(Brightness::OFF, Brightness::FULL, 0, Easing::Linear);
+        assert_eq!(b.sample(123), Brightness::FULL);
+    }
+}
@@ -0,0 +1,407 @@
+/*
+ * @file fault.rs
+ * @brief Fault blink pattern and persistent panic record
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: fault.rs
+//!
+//! DESCRIPTION:
+//! Fault Reporting for RP2350 LED Firmware.
+//!
+//! BRIEF:
+//! Builds the fault pattern flashed after a panic, a fast triple
+//! flash followed by a numeric code, and defines the panic record
+//! kept in RAM across soft resets. Both are plain data so they
+//! are tested on the host; the handler itself lives in
+//! panic_blink.rs behind the `panic-blink` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::crc::Crc32;
+use crate::pattern::{MAX_SEGMENTS, Pattern, Segment};
+
+/// Length of each fast flash and the gap between them.
+///
+/// # Value
+/// 60 milliseconds
+#[allow(dead_code)]
+pub const FAULT_FLASH_MS: u64 = 60;
+
+/// Length of each code flash and the gap between them.
+///
+/// # Value
+/// 300 milliseconds
+#[allow(dead_code)]
+pub const FAULT_CODE_MS: u64 = 300;
+
+/// Pause between the triple flash and the code.
+///
+/// # Value
+/// 600 milliseconds
+#[allow(dead_code)]
+pub const FAULT_GAP_MS: u64 = 600;
+
+/// Pause before the fault pattern repeats.
+///
+/// # Value
+/// 1500 milliseconds
+#[allow(dead_code)]
+pub const FAULT_REPEAT_GAP_MS: u64 = 1500;
+
+/// Largest code the fault pattern can show.
+///
+/// # Details
+/// The triple flash takes six segments and each code flash two.
+///
+/// # Value
+/// 29
+#[allow(dead_code)]
+pub const MAX_FAULT_CODE: u8 = ((MAX_SEGMENTS - 6) / 2) as u8;
+const _: () = assert!(crate::config::PANIC_BLINK_CODE <= MAX_FAULT_CODE);
+
+/// Marker of a written panic record.
+///
+/// # Value
+/// 0x50414E43 ("PANC")
+#[allow(dead_code)]
+pub const PANIC_MAGIC: u32 = 0x5041_4E43;
+
+/// Bytes of the source file path kept in a panic record.
+///
+/// # Value
+/// 48 bytes
+#[allow(dead_code)]
+pub const PANIC_FILE_LEN: usize = 48;
+
+/// Builds the looping fault pattern.
+///
+/// # Details
+/// Three fast flashes, a pause, then `code` slow flashes and a
+/// longer pause. Code 0 shows only the triple flash; codes above
+/// `MAX_FAULT_CODE` are clamped.
+///
+/// # Arguments
+/// * `code` - Fault code to show
+///
+/// # Returns
+/// * `Pattern` - Looping fault pattern
+#[allow(dead_code)]
+pub fn fault_pattern(code: u8) -> Pattern {
+    let code = code.min(MAX_FAULT_CODE);
+    let mut segments = [Segment::off(1); MAX_SEGMENTS];
+    let mut len = 0;
+    for _ in 0..3 {
+        segments[len] = Segment::on(FAULT_FLASH_MS);
+        segments[len + 1] = Segment::off(FAULT_FLASH_MS);
+        len += 2;
+    }
+    segments[len - 1] = Segment::off(FAULT_FLASH_MS + FAULT_GAP_MS);
+    for _ in 0..code {
+        segments[len] = Segment::on(FAULT_CODE_MS);
+        segments[len + 1] = Segment::off(FAULT_CODE_MS);
+        len += 2;
+    }
+    segments[len - 1].duration_ms += FAULT_REPEAT_GAP_MS;
+    Pattern::from_segments(&segments[..len]).unwrap_or_default()
+}
+
+/// Location of a panic, kept across soft resets.
+///
+/// # Details
+/// Laid out with `repr(C)` so it can sit in uninitialized RAM
+/// and be read back from a debugger by symbol. A checksum tells
+/// a written record apart from power-on garbage.
+///
+/// # Fields
+/// * `magic` - `PANIC_MAGIC` once written
+/// * `line` - Source line
+/// * `column` - Source column
+/// * `count` - Panics since power-on, including this one
+/// * `reported` - Nonzero once the boot report has shown this panic
+/// * `file_len` - Used bytes of `file`
+/// * `file` - Tail of the source file path
+/// * `check` - CRC-32 over the fields above
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct PanicRecord {
+    magic: u32,
+    line: u32,
+    column: u32,
+    count: u32,
+    reported: u32,
+    file_len: u32,
+    file: [u8; PANIC_FILE_LEN],
+    check: u32,
+}
+
+impl PanicRecord {
+    /// Creates a record.
+    ///
+    /// # Details
+    /// Long paths keep their tail, which holds the file name.
+    ///
+    /// # Arguments
+    /// * `file` - Source file path
+    /// * `line` - Source line
+    /// * `column` - Source column
+    /// * `count` - Panics since power-on
+    ///
+    /// # Returns
+    /// * `Self` - Sealed record
+    #[allow(dead_code)]
+    pub fn new(file: &str, line: u32, column: u32, count: u32) -> Self {
+        let mut start = file.len().saturating_sub(PANIC_FILE_LEN);
+        while !file.is_char_boundary(start) {
+            start += 1;
+        }
+        let tail = &file.as_bytes()[start..];
+        let mut record = Self {
+            magic: PANIC_MAGIC,
+            line,
+            column,
+            count,
+            reported: 0,
+            file_len: tail.len() as u32,
+            file: [0; PANIC_FILE_LEN],
+            check: 0,
+        };
+        record.file[..tail.len()].copy_from_slice(tail);
+        record.check = record.checksum();
+        record
+    }
+
+    /// Returns whether the record was written by `new`.
+    ///
+    /// # Returns
+    /// * `bool` - True for an intact record
+    #[allow(dead_code)]
+    pub fn is_valid(&self) -> bool {
+        self.magic == PANIC_MAGIC
+            && self.file_len as usize <= PANIC_FILE_LEN
+            && self.check == self.checksum()
+    }
+
+    /// Returns the source file path, possibly shortened.
+    ///
+    /// # Returns
+    /// * `&str` - File path, empty if unknown
+    #[allow(dead_code)]
+    pub fn file(&self) -> &str {
+        let len = (self.file_len as usize).min(PANIC_FILE_LEN);
+        core::str::from_utf8(&self.file[..len]).unwrap_or("")
+    }
+
+    /// Returns the source line.
+    ///
+    /// # Returns
+    /// * `u32` - Line number
+    #[allow(dead_code)]
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the source column.
+    ///
+    /// # Returns
+    /// * `u32` - Column number
+    #[allow(dead_code)]
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// Returns the number of panics since power-on.
+    ///
+    /// # Returns
+    /// * `u32` - Panic count
+    #[allow(dead_code)]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns whether the boot report has shown this panic.
+    ///
+    /// # Returns
+    /// * `bool` - True once `take_report` has claimed the record
+    #[allow(dead_code)]
+    pub fn is_reported(&self) -> bool {
+        self.reported != 0
+    }
+
+    /// Claims the record for the boot-time report.
+    ///
+    /// # Details
+    /// Marks the record reported and reseals it, so later soft
+    /// resets keep the location and count but do not replay the
+    /// report. A new panic writes a fresh, unreported record.
+    ///
+    /// # Returns
+    /// * `bool` - True the first time, false once already reported
+    #[allow(dead_code)]
+    pub fn take_report(&mut self) -> bool {
+        if self.is_reported() {
+            return false;
+        }
+        self.reported = 1;
+        self.check = self.checksum();
+        true
+    }
+
+    /// Computes the record checksum.
+    ///
+    /// # Returns
+    /// * `u32` - CRC-32 over every field except `check`
+    fn checksum(&self) -> u32 {
+        let mut crc = Crc32::new();
+        crc.update(&self.magic.to_le_bytes());
+        crc.update(&self.line.to_le_bytes());
+        crc.update(&self.column.to_le_bytes());
+        crc.update(&self.count.to_le_bytes());
+        crc.update(&self.reported.to_le_bytes());
+        crc.update(&self.file_len.to_le_bytes());
+        crc.update(&self.file);
+        crc.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::led::LedState;
+    use crate::pattern::EndAction;
+
+    fn flashes(pattern: &Pattern, duration_ms: u64) -> usize {
+        pattern
+            .segments()
+            .iter()
+            .filter(|s| s.state == LedState::On && s.duration_ms == duration_ms)
+            .count()
+    }
+
+    // ==================== Fault Pattern Tests ====================
+
+    #[test]
+    fn test_fault_pattern_triple_flash() {
+        let pattern = fault_pattern(0);
+        assert_eq!(pattern.len(), 6);
+        assert_eq!(flashes(&pattern, FAULT_FLASH_MS), 3);
+        assert_eq!(pattern.segments()[0], Segment::on(FAULT_FLASH_MS));
+    }
+
+    #[test]
+    fn test_fault_pattern_code_flashes() {
+        let pattern = fault_pattern(4);
+        assert_eq!(flashes(&pattern, FAULT_FLASH_MS), 3);
+        assert_eq!(flashes(&pattern, FAULT_CODE_MS), 4);
+        assert_eq!(pattern.len(), 14);
+    }
+
+    #[test]
+    fn test_fault_pattern_gaps() {
+        let pattern = fault_pattern(1);
+        let segments = pattern.segments();
+        assert_eq!(segments[5], Segment::off(FAULT_FLASH_MS + FAULT_GAP_MS));
+        assert_eq!(
+            segments[7],
+            Segment::off(FAULT_CODE_MS + FAULT_REPEAT_GAP_MS)
+        );
+    }
+
+    #[test]
+    fn test_fault_pattern_loops() {
+        assert_eq!(fault_pattern(2).end_action(), EndAction::Loop);
+    }
+
+    #[test]
+    fn test_fault_pattern_clamps_code() {
+        let pattern = fault_pattern(u8::MAX);
+        assert_eq!(pattern.len(), MAX_SEGMENTS);
+        assert_eq!(flashes(&pattern, FAULT_CODE_MS), MAX_FAULT_CODE as usize);
+    }
+
+    // ==================== PanicRecord Tests ====================
+
+    #[test]
+    fn test_panic_record_round_trip() {
+        let record = PanicRecord::new("src/main.rs", 42, 7, 1);
+        assert!(record.is_valid());
+        assert_eq!(record.file(), "src/main.rs");
+        assert_eq!(record.line(), 42);
+        assert_eq!(record.column(), 7);
+        assert_eq!(record.count(), 1);
+    }
+
+    #[test]
+    fn test_panic_record_keeps_path_tail() {
+        let path = "a/very/long/path/that/does/not/fit/in/the/record/src/main.rs";
+        let record = PanicRecord::new(path, 1, 1, 1);
+        assert_eq!(record.file().len(), PANIC_FILE_LEN);
+        assert!(record.file().ends_with("src/main.rs"));
+    }
+
+    #[test]
+    fn test_panic_record_keeps_utf8_boundary() {
+        let path = "ééééééééééééééééééééééééééééééé/x.rs";
+        let record = PanicRecord::new(path, 1, 1, 1);
+        assert!(record.file().ends_with("/x.rs"));
+        assert!(record.file().len() <= PANIC_FILE_LEN);
+    }
+
+    #[test]
+    fn test_panic_record_detects_garbage() {
+        let mut record = PanicRecord::new("src/main.rs", 42, 7, 1);
+        record.line = 43;
+        assert!(!record.is_valid());
+    }
+
+    #[test]
+    fn test_panic_record_detects_bad_magic() {
+        let mut record = PanicRecord::new("src/main.rs", 42, 7, 1);
+        record.magic = 0;
+        assert!(!record.is_valid());
+    }
+
+    #[test]
+    fn test_panic_record_reported_once() {
+        let mut record = PanicRecord::new("src/main.rs", 42, 7, 1);
+        assert!(!record.is_reported());
+        assert!(record.take_report());
+        assert!(record.is_valid());
+        assert!(record.is_reported());
+        assert!(!record.take_report());
+        assert_eq!(record.count(), 1);
+    }
+
+    #[test]
+    fn test_panic_record_report_flag_checked() {
+        let mut record = PanicRecord::new("src/main.rs", 42, 7, 1);
+        record.reported = 1;
+        assert!(!record.is_valid());
+    }
+}
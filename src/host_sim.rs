@@ -0,0 +1,278 @@
+/*
+ * @file host_sim.rs
+ * @brief Host stand-ins for the board peripherals
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: host_sim.rs
+//!
+//! DESCRIPTION:
+//! Host Peripherals for the RP2350 Blink Application.
+//!
+//! BRIEF:
+//! Replaces the board's pins, USB console and reset when the
+//! `host-sim` feature builds main.rs for the std executor.
+//! Output pins print their changes to stderr with the time
+//! since boot, the console reads stdin and writes stdout, the
+//! button is never pressed and reset exits the process.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use core::convert::Infallible;
+use embassy_futures::block_on;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_time::Instant;
+use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal::pwm::{ErrorType as PwmErrorType, SetDutyCycle};
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+/// Chunks read from stdin, empty at end of input.
+static STDIN: Channel<CriticalSectionRawMutex, Vec<u8>, 4> = Channel::new();
+
+/// Prints a pin change.
+///
+/// # Arguments
+/// * `name` - Pin name
+/// * `state` - New state text
+fn trace(name: &str, state: &str) {
+    eprintln!("{:>8} ms  {name} {state}", Instant::now().as_millis());
+}
+
+/// Output pin that traces its level.
+///
+/// # Fields
+/// * `name` - Pin name
+/// * `high` - Current level
+pub struct TracePin {
+    name: &'static str,
+    high: bool,
+}
+
+impl TracePin {
+    /// Creates a low pin.
+    ///
+    /// # Arguments
+    /// * `name` - Pin name shown in the trace
+    ///
+    /// # Returns
+    /// * `Self` - New TracePin instance
+    pub fn new(name: &'static str) -> Self {
+        Self { name, high: false }
+    }
+
+    /// Sets the level, tracing a change.
+    ///
+    /// # Arguments
+    /// * `high` - New level
+    fn set(&mut self, high: bool) {
+        if self.high != high {
+            self.high = high;
+            trace(self.name, if high { "on" } else { "off" });
+        }
+    }
+}
+
+impl ErrorType for TracePin {
+    type Error = Infallible;
+}
+
+impl OutputPin for TracePin {
+    /// Drives the pin low.
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.set(false);
+        Ok(())
+    }
+
+    /// Drives the pin high.
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.set(true);
+        Ok(())
+    }
+}
+
+/// PWM output that traces its duty cycle.
+///
+/// # Fields
+/// * `name` - Pin name
+/// * `max` - Duty cycle at full brightness
+/// * `duty` - Current duty cycle
+pub struct TracePwm {
+    name: &'static str,
+    max: u16,
+    duty: u16,
+}
+
+impl TracePwm {
+    /// Creates an output at zero duty.
+    ///
+    /// # Arguments
+    /// * `name` - Pin name shown in the trace
+    /// * `max` - Counter top, as configured on the board
+    ///
+    /// # Returns
+    /// * `Self` - New TracePwm instance
+    pub fn new(name: &'static str, max: u16) -> Self {
+        Self { name, max, duty: 0 }
+    }
+}
+
+impl PwmErrorType for TracePwm {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for TracePwm {
+    /// Returns the counter top.
+    fn max_duty_cycle(&self) -> u16 {
+        self.max
+    }
+
+    /// Sets the duty cycle, tracing a change.
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
+        if self.duty != duty {
+            self.duty = duty;
+            let percent = u32::from(duty) * 100 / u32::from(self.max.max(1));
+            match duty {
+                0 => trace(self.name, "off"),
+                _ => trace(self.name, &format!("on ({percent}%)")),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Button input that is never pressed.
+pub struct IdleButton;
+
+impl IdleButton {
+    /// Returns whether the button is pressed.
+    ///
+    /// # Returns
+    /// * `bool` - Always false
+    pub fn is_low(&self) -> bool {
+        false
+    }
+
+    /// Waits for an edge, which never comes.
+    pub async fn wait_for_any_edge(&mut self) {
+        core::future::pending::<()>().await;
+    }
+}
+
+/// Serial console on stdin and stdout.
+///
+/// # Details
+/// Stdin is read on its own thread and handed over through a
+/// channel, so the executor never blocks on it. The session
+/// ends at end of input and does not reconnect.
+///
+/// # Fields
+/// * `pending` - Received bytes not yet read
+/// * `closed` - Stdin has ended
+pub struct StdioConsole {
+    pending: Vec<u8>,
+    closed: bool,
+}
+
+impl StdioConsole {
+    /// Starts reading stdin.
+    ///
+    /// # Returns
+    /// * `Self` - New StdioConsole instance
+    pub fn new() -> Self {
+        std::thread::spawn(|| {
+            let mut buf = [0u8; 64];
+            loop {
+                let n = io::stdin().read(&mut buf).unwrap_or(0);
+                block_on(STDIN.send(buf[..n].to_vec()));
+                if n == 0 {
+                    break;
+                }
+            }
+        });
+        Self {
+            pending: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Waits for the host to open the port.
+    ///
+    /// # Details
+    /// Ready at once for the first session; pends forever once
+    /// stdin has ended.
+    pub async fn wait_connection(&mut self) {
+        if self.closed {
+            core::future::pending::<()>().await;
+        }
+    }
+
+    /// Reads received bytes.
+    ///
+    /// # Arguments
+    /// * `buf` - Destination buffer
+    ///
+    /// # Returns
+    /// * `io::Result<usize>` - Bytes read, or an error at end of input
+    pub async fn read_packet(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            self.pending = STDIN.receive().await;
+        }
+        if self.pending.is_empty() {
+            self.closed = true;
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let n = self.pending.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+
+    /// Writes bytes to stdout.
+    ///
+    /// # Arguments
+    /// * `data` - Bytes to send
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - Ok or write error
+    pub async fn write_packet(&mut self, data: &[u8]) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        out.write_all(data)?;
+        out.flush()
+    }
+}
+
+/// Restarts the board.
+///
+/// # Details
+/// The host has nothing to restart into, so the process exits.
+pub fn reboot() -> ! {
+    trace("board", "reset");
+    std::process::exit(0)
+}
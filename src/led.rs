@@ -33,13 +33,15 @@
 //! LED State Management for RP2350.
 //!
 //! BRIEF:
-//! Provides LED state enumeration and blink controller.
+//! Provides LED state enumeration and pattern-driven blink controller.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 6, 2025
-//! UPDATE DATE: December 6, 2025
+//! UPDATE DATE: October 17, 2026
 
-use crate::config::BLINK_DELAY_MS;
+use crate::blink_code::{self, BlinkCodeError, BlinkCodeTiming};
+use crate::config::{BLINK_DELAY_MS, BlinkConfig, LED_ACTIVE_LOW};
+use crate::pattern::{EndAction, Pattern};
 
 /// LED state enumeration.
 ///
@@ -57,20 +59,83 @@ pub enum LedState {
     Off,
 }
 
+/// LED wiring polarity.
+///
+/// # Details
+/// Active-low LEDs are wired from the supply to the pin and
+/// light when the pin is driven low.
+///
+/// # Variants
+/// * `ActiveHigh` - LED lights when the pin is high
+/// * `ActiveLow` - LED lights when the pin is low
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum Polarity {
+    ActiveHigh,
+    ActiveLow,
+}
+
+impl Default for Polarity {
+    /// Returns the polarity selected in config.rs.
+    ///
+    /// # Returns
+    /// * `Self` - ActiveLow if `LED_ACTIVE_LOW` is set, else ActiveHigh
+    #[allow(dead_code)]
+    fn default() -> Self {
+        if LED_ACTIVE_LOW {
+            Polarity::ActiveLow
+        } else {
+            Polarity::ActiveHigh
+        }
+    }
+}
+
+impl Polarity {
+    /// Maps a logical LED state to the state to drive.
+    ///
+    /// # Details
+    /// The result feeds the usual high-means-on pin writes.
+    ///
+    /// # Arguments
+    /// * `state` - Logical LED state
+    ///
+    /// # Returns
+    /// * `LedState` - Unchanged for ActiveHigh, inverted for ActiveLow
+    #[allow(dead_code)]
+    pub const fn apply(self, state: LedState) -> LedState {
+        match (self, state) {
+            (Polarity::ActiveHigh, s) => s,
+            (Polarity::ActiveLow, LedState::On) => LedState::Off,
+            (Polarity::ActiveLow, LedState::Off) => LedState::On,
+        }
+    }
+}
+
 /// LED controller with state tracking.
 ///
 /// # Details
-/// Maintains LED state and blink timing configuration.
+/// Plays a timed pattern of on/off segments.
 /// Provides methods for state transitions and queries.
+/// The pattern is held inline, as patterns use fixed storage
+/// without an allocator, so the controller is about 1 KB; pass
+/// it by reference rather than copying it.
 ///
 /// # Fields
 /// * `state` - Current LED state
-/// * `delay_ms` - Blink delay in milliseconds
+/// * `pattern` - Pattern being played
+/// * `index` - Index of the current segment
+/// * `plays` - Completed plays of the pattern
+/// * `started` - Whether the first segment has been entered
+/// * `finished` - Whether the end action has been applied
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub struct LedController {
     state: LedState,
-    delay_ms: u64,
+    pattern: Pattern,
+    index: usize,
+    plays: u16,
+    started: bool,
+    finished: bool,
 }
 
 impl Default for LedController {
@@ -91,44 +156,190 @@ impl LedController {
     /// Creates new LED controller with default settings.
     ///
     /// # Details
-    /// Initializes controller with LED off.
+    /// Initializes controller with LED off and the default
+    /// `BlinkConfig`, a symmetric blink using `BLINK_DELAY_MS`.
     ///
     /// # Returns
     /// * `Self` - New LedController instance
     #[allow(dead_code)]
     pub fn new() -> Self {
+        Self::with_config(BlinkConfig::default())
+    }
+
+    /// Creates LED controller playing the given pattern.
+    ///
+    /// # Details
+    /// Initializes controller with LED off.
+    /// The first toggle enters the first segment.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to play
+    ///
+    /// # Returns
+    /// * `Self` - New LedController instance
+    #[allow(dead_code)]
+    pub fn with_pattern(pattern: Pattern) -> Self {
         Self {
             state: LedState::Off,
-            delay_ms: BLINK_DELAY_MS,
+            pattern,
+            index: 0,
+            plays: 0,
+            started: false,
+            finished: false,
         }
     }
 
+    /// Creates LED controller from a validated blink configuration.
+    ///
+    /// # Details
+    /// Plays a looping blink using the configuration's separate
+    /// ON and OFF delays.
+    ///
+    /// # Arguments
+    /// * `config` - Validated blink configuration
+    ///
+    /// # Returns
+    /// * `Self` - New LedController instance
+    #[allow(dead_code)]
+    pub fn with_config(config: BlinkConfig) -> Self {
+        Self::with_pattern(Pattern::on_off(config.on_ms(), config.off_ms()))
+    }
+
+    /// Applies a validated blink configuration.
+    ///
+    /// # Details
+    /// Replaces the current pattern and restarts playback.
+    ///
+    /// # Arguments
+    /// * `config` - Validated blink configuration
+    #[allow(dead_code)]
+    pub fn set_config(&mut self, config: BlinkConfig) {
+        *self = Self::with_config(config);
+    }
+
+    /// Replaces the pattern and restarts playback.
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern to play
+    #[allow(dead_code)]
+    pub fn set_pattern(&mut self, pattern: Pattern) {
+        *self = Self::with_pattern(pattern);
+    }
+
+    /// Shows a number as a looping blink code.
+    ///
+    /// # Details
+    /// Replaces the current pattern and restarts playback. The
+    /// current pattern is kept if the value cannot be encoded.
+    ///
+    /// # Arguments
+    /// * `value` - Number to show, 0 to 99
+    /// * `timing` - Flash and gap durations
+    ///
+    /// # Returns
+    /// * `Result<(), BlinkCodeError>` - Ok or encoding error
+    #[allow(dead_code)]
+    pub fn set_blink_code(
+        &mut self,
+        value: u16,
+        timing: BlinkCodeTiming,
+    ) -> Result<(), BlinkCodeError> {
+        self.set_pattern(blink_code::encode(value, timing)?);
+        Ok(())
+    }
+
     /// Toggles LED state and returns new state.
     ///
     /// # Details
-    /// Transitions LED from On to Off or Off to On.
+    /// Advances to the next pattern segment. After the last
+    /// repeat the end action applies: `Loop` starts over,
+    /// `Hold` keeps the last state and `Stop` turns the LED off.
     ///
     /// # Returns
     /// * `LedState` - New LED state after toggle
     #[allow(dead_code)]
     pub fn toggle(&mut self) -> LedState {
-        self.state = match self.state {
-            LedState::On => LedState::Off,
-            LedState::Off => LedState::On,
-        };
+        if self.finished {
+            return self.state;
+        }
+        let len = self.pattern.len();
+        if len == 0 {
+            self.finish();
+            return self.state;
+        }
+        if !self.started {
+            self.started = true;
+        } else if self.index + 1 < len {
+            self.index += 1;
+        } else if self.pattern.end_action() == EndAction::Loop
+            || self.plays + 1 < self.pattern.repeat()
+        {
+            self.plays = self.plays.saturating_add(1);
+            self.index = 0;
+        } else {
+            self.finish();
+            return self.state;
+        }
+        self.state = self.pattern.segments()[self.index].state;
         self.state
     }
 
+    /// Applies the end action.
+    ///
+    /// # Details
+    /// `Stop` and empty patterns leave the LED off.
+    fn finish(&mut self) {
+        self.finished = true;
+        if self.pattern.is_empty() || self.pattern.end_action() == EndAction::Stop {
+            self.state = LedState::Off;
+        }
+    }
+
     /// Returns current blink delay.
     ///
     /// # Details
-    /// Delay used for blink timing in milliseconds.
+    /// Duration of the current segment in milliseconds.
+    /// Before the first toggle this is the first segment's
+    /// duration; once finished it stays at the last segment's.
     ///
     /// # Returns
     /// * `u64` - Delay in milliseconds
     #[allow(dead_code)]
     pub fn delay_ms(&self) -> u64 {
-        self.delay_ms
+        self.pattern
+            .segments()
+            .get(self.index)
+            .map_or(BLINK_DELAY_MS, |segment| segment.duration_ms)
+    }
+
+    /// Returns current LED state.
+    ///
+    /// # Returns
+    /// * `LedState` - Current LED state
+    #[allow(dead_code)]
+    pub fn state(&self) -> LedState {
+        self.state
+    }
+
+    /// Returns the pattern being played.
+    ///
+    /// # Returns
+    /// * `&Pattern` - Current pattern
+    #[allow(dead_code)]
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    /// Returns true once the end action has been applied.
+    ///
+    /// # Details
+    /// Looping patterns never finish.
+    ///
+    /// # Returns
+    /// * `bool` - true when playback has ended
+    #[allow(dead_code)]
+    pub fn is_finished(&self) -> bool {
+        self.finished
     }
 }
 
@@ -150,6 +361,7 @@ pub fn led_state_to_level(state: LedState) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pattern::{MAX_SEGMENTS, Segment};
 
     // ==================== LedState Enum Tests ====================
 
@@ -243,6 +455,30 @@ mod tests {
         }
     }
 
+    // ==================== Polarity Tests ====================
+
+    #[test]
+    fn test_polarity_active_high_passes_through() {
+        assert_eq!(Polarity::ActiveHigh.apply(LedState::On), LedState::On);
+        assert_eq!(Polarity::ActiveHigh.apply(LedState::Off), LedState::Off);
+    }
+
+    #[test]
+    fn test_polarity_active_low_inverts() {
+        assert_eq!(Polarity::ActiveLow.apply(LedState::On), LedState::Off);
+        assert_eq!(Polarity::ActiveLow.apply(LedState::Off), LedState::On);
+    }
+
+    #[test]
+    fn test_polarity_default_follows_config() {
+        let expected = if LED_ACTIVE_LOW {
+            Polarity::ActiveLow
+        } else {
+            Polarity::ActiveHigh
+        };
+        assert_eq!(Polarity::default(), expected);
+    }
+
     // ==================== LedController::new() Tests ====================
 
     #[test]
@@ -254,11 +490,8 @@ mod tests {
     #[test]
     fn test_new_controller_state_off() {
         let ctrl = LedController::new();
-        let expected = LedController {
-            state: LedState::Off,
-            delay_ms: BLINK_DELAY_MS,
-        };
-        assert_eq!(ctrl, expected);
+        assert_eq!(ctrl.state(), LedState::Off);
+        assert_eq!(ctrl.delay_ms(), BLINK_DELAY_MS);
     }
 
     #[test]
@@ -395,16 +628,17 @@ mod tests {
     #[test]
     fn test_initial_state_off() {
         let ctrl = LedController::new();
-        let expected = LedController {
-            state: LedState::Off,
-            delay_ms: BLINK_DELAY_MS,
-        };
-        assert_eq!(ctrl, expected);
+        assert_eq!(ctrl.state(), LedState::Off);
+        assert_eq!(ctrl.delay_ms(), BLINK_DELAY_MS);
     }
 
     #[test]
     fn test_controller_size() {
-        assert!(core::mem::size_of::<LedController>() <= 16);
+        // The pattern is stored inline; the playback state around it
+        // keeps the original 16-byte budget.
+        let pattern = core::mem::size_of::<Pattern>();
+        assert!(pattern <= MAX_SEGMENTS * core::mem::size_of::<Segment>() + 16);
+        assert!(core::mem::size_of::<LedController>() <= pattern + 16);
     }
 
     #[test]
@@ -535,4 +769,195 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().delay_ms(), BLINK_DELAY_MS);
     }
+
+    // ==================== Pattern Playback Tests ====================
+
+    #[test]
+    fn test_with_pattern_starts_off() {
+        let ctrl = LedController::with_pattern(Pattern::heartbeat());
+        assert_eq!(ctrl.state(), LedState::Off);
+        assert!(!ctrl.is_finished());
+    }
+
+    #[test]
+    fn test_with_pattern_first_delay() {
+        let ctrl = LedController::with_pattern(Pattern::double_blink());
+        assert_eq!(ctrl.delay_ms(), 100);
+    }
+
+    #[test]
+    fn test_new_uses_blink_pattern() {
+        let ctrl = LedController::new();
+        assert_eq!(*ctrl.pattern(), Pattern::blink(BLINK_DELAY_MS));
+    }
+
+    #[test]
+    fn test_heartbeat_sequence() {
+        let mut ctrl = LedController::with_pattern(Pattern::heartbeat());
+        let mut steps = Vec::new();
+        for _ in 0..8 {
+            let state = ctrl.toggle();
+            steps.push((state, ctrl.delay_ms()));
+        }
+        assert_eq!(
+            steps,
+            vec![
+                (LedState::On, 100),
+                (LedState::Off, 100),
+                (LedState::On, 100),
+                (LedState::Off, 700),
+                (LedState::On, 100),
+                (LedState::Off, 100),
+                (LedState::On, 100),
+                (LedState::Off, 700),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loop_never_finishes() {
+        let mut ctrl = LedController::with_pattern(Pattern::sos());
+        for _ in 0..1000 {
+            ctrl.toggle();
+        }
+        assert!(!ctrl.is_finished());
+    }
+
+    #[test]
+    fn test_repeat_then_stop() {
+        let pattern = Pattern::from_segments(&[Segment::on(10), Segment::on(20)])
+            .unwrap()
+            .with_repeat(2)
+            .with_end_action(EndAction::Stop);
+        let mut ctrl = LedController::with_pattern(pattern);
+        for _ in 0..4 {
+            assert_eq!(ctrl.toggle(), LedState::On);
+        }
+        assert!(!ctrl.is_finished());
+        assert_eq!(ctrl.toggle(), LedState::Off);
+        assert!(ctrl.is_finished());
+        assert_eq!(ctrl.toggle(), LedState::Off);
+    }
+
+    #[test]
+    fn test_hold_keeps_last_state() {
+        let pattern = Pattern::from_segments(&[Segment::off(10), Segment::on(30)])
+            .unwrap()
+            .with_end_action(EndAction::Hold);
+        let mut ctrl = LedController::with_pattern(pattern);
+        assert_eq!(ctrl.toggle(), LedState::Off);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert!(ctrl.is_finished());
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 30);
+    }
+
+    #[test]
+    fn test_stop_after_single_play() {
+        let pattern = Pattern::heartbeat().with_end_action(EndAction::Stop);
+        let mut ctrl = LedController::with_pattern(pattern);
+        for _ in 0..4 {
+            ctrl.toggle();
+        }
+        assert!(!ctrl.is_finished());
+        ctrl.toggle();
+        assert!(ctrl.is_finished());
+        assert_eq!(ctrl.state(), LedState::Off);
+    }
+
+    #[test]
+    fn test_empty_pattern_finishes_off() {
+        let mut ctrl = LedController::with_pattern(Pattern::new());
+        assert_eq!(ctrl.toggle(), LedState::Off);
+        assert!(ctrl.is_finished());
+        assert_eq!(ctrl.delay_ms(), BLINK_DELAY_MS);
+    }
+
+    #[test]
+    fn test_set_pattern_restarts() {
+        let mut ctrl = LedController::new();
+        ctrl.toggle();
+        ctrl.set_pattern(Pattern::sos());
+        assert_eq!(ctrl.state(), LedState::Off);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 150);
+    }
+
+    #[test]
+    fn test_toggle_and_delay_total_period() {
+        let mut ctrl = LedController::with_pattern(Pattern::sos());
+        let mut total = 0;
+        for _ in 0..Pattern::sos().len() {
+            ctrl.toggle();
+            total += ctrl.delay_ms();
+        }
+        assert_eq!(total, Pattern::sos().period_ms());
+    }
+
+    // ==================== LedController::with_config() Tests ====================
+
+    #[test]
+    fn test_with_config_default_equals_new() {
+        assert_eq!(
+            LedController::with_config(BlinkConfig::default()),
+            LedController::new()
+        );
+    }
+
+    #[test]
+    fn test_with_config_asymmetric_delays() {
+        let config = BlinkConfig::from_period(2000, 25).unwrap();
+        let mut ctrl = LedController::with_config(config);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 50);
+        assert_eq!(ctrl.toggle(), LedState::Off);
+        assert_eq!(ctrl.delay_ms(), 1950);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 50);
+    }
+
+    #[test]
+    fn test_with_config_from_builder() {
+        let config = BlinkConfig::builder().on_ms(20).off_ms(80).build().unwrap();
+        let mut ctrl = LedController::with_config(config);
+        ctrl.toggle();
+        assert_eq!(ctrl.delay_ms(), 20);
+        ctrl.toggle();
+        assert_eq!(ctrl.delay_ms(), 80);
+    }
+
+    #[test]
+    fn test_set_config_restarts() {
+        let mut ctrl = LedController::new();
+        ctrl.toggle();
+        ctrl.set_config(BlinkConfig::new(100, 300).unwrap());
+        assert_eq!(ctrl.state(), LedState::Off);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 100);
+    }
+
+    // ==================== LedController::set_blink_code() Tests ====================
+
+    #[test]
+    fn test_set_blink_code_plays_code() {
+        let mut ctrl = LedController::new();
+        ctrl.set_blink_code(12, BlinkCodeTiming::default()).unwrap();
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 600);
+        assert_eq!(ctrl.toggle(), LedState::Off);
+        assert_eq!(ctrl.delay_ms(), 1000);
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 200);
+    }
+
+    #[test]
+    fn test_set_blink_code_out_of_range_keeps_pattern() {
+        let mut ctrl = LedController::with_pattern(Pattern::heartbeat());
+        assert_eq!(
+            ctrl.set_blink_code(100, BlinkCodeTiming::default()),
+            Err(BlinkCodeError::OutOfRange(100))
+        );
+        assert_eq!(*ctrl.pattern(), Pattern::heartbeat());
+    }
 }
@@ -34,12 +34,39 @@
 //!
 //! BRIEF:
 //! Exports all public modules for testing and reuse.
-//! Conditionally enables std for host testing.
+//! Conditionally enables std for host testing and the `std` feature.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 6, 2025
-//! UPDATE DATE: December 6, 2025
+//! UPDATE DATE: October 17, 2026
 
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+pub mod arbiter;
+pub mod bank;
+pub mod blink_code;
+pub mod brightness;
+pub mod button;
+pub mod codec;
+pub mod command;
 pub mod config;
+pub mod crc;
+pub mod dsl;
+#[cfg(any(test, feature = "std"))]
+pub mod emulator;
+pub mod fade;
+pub mod fault;
 pub mod led;
+#[cfg(any(test, feature = "std"))]
+pub mod mock;
+pub mod morse;
+pub mod pattern;
+pub mod protocol;
+#[cfg(feature = "std")]
+pub mod pty;
+pub mod runner;
+pub mod scheduler;
+pub mod settings;
+pub mod shell;
+pub mod trace;
+pub mod vcd;
+pub mod waveform;
@@ -34,48 +34,534 @@
 //!
 //! BRIEF:
 //! Main application entry point for RP2350 GPIO blink driver using Embassy.
-//! Implements async LED blinking on GPIO 25 (built-in LED).
+//! Implements async LED blinking or breathing on GPIO 16 with PWM brightness control
+//! and a bank of indicator LEDs on GPIO 17-20.
+//! With the `host-sim` feature the same tasks run on the std
+//! executor against traced host peripherals from host_sim.rs.
 //! Part of the 365 Pico2 RP2350 Project Ideas series.
 //!
 //! AUTHOR: Kevin Thomas
 //! CREATION DATE: December 6, 2025
-//! UPDATE DATE: December 6, 2025
+//! UPDATE DATE: October 17, 2026
 
-#![no_std]
-#![no_main]
+#![cfg_attr(not(feature = "host-sim"), no_std)]
+#![cfg_attr(not(feature = "host-sim"), no_main)]
 
+mod arbiter;
+mod bank;
+mod blink_code;
+mod brightness;
+mod button;
+mod codec;
+mod command;
 mod config;
+mod crc;
+mod dsl;
+mod fade;
+mod fault;
+#[cfg(feature = "host-sim")]
+mod host_sim;
 mod led;
+#[cfg(feature = "host-sim")]
+#[allow(dead_code)]
+mod mock;
+#[cfg(feature = "panic-blink")]
+mod panic_blink;
+mod pattern;
+mod protocol;
+mod runner;
+mod scheduler;
+mod settings;
+mod shell;
+#[cfg(feature = "host-sim")]
+mod waveform;
 
+use bank::LedBank;
+use brightness::{Brightness, DimmedPin};
+use button::{Button, ButtonTiming, ModeCycler};
+use command::{CommandHandler, LedCommand};
+use config::{
+    ARBITER_SLOTS, BREATHING_ENABLED, BREATHING_PERIOD_MS, FADE_STEP_MS, FLASH_SIZE,
+    INDICATOR_COUNT, INDICATOR_PHASE_MS, LED_BRIGHTNESS_PERCENT, LED_COMMAND_DEPTH, LED_PWM_TOP,
+    SETTINGS_OFFSET, SETTINGS_SIZE, SHELL_LINE_LEN,
+};
+#[cfg(not(feature = "host-sim"))]
+use config::{USB_PID, USB_VID};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use embassy_executor::Spawner;
-use embassy_rp::gpio::{Level, Output};
-use embassy_time::Timer;
-use led::{led_state_to_level, LedController};
+use embassy_futures::select::{Either, select};
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::bind_interrupts;
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::flash::{Blocking, Flash};
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::gpio::{Input, Level, Output, Pull};
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::peripherals::{FLASH, USB};
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::pwm::{Config as PwmConfig, Pwm, PwmOutput};
+#[cfg(not(feature = "host-sim"))]
+use embassy_rp::usb::{Driver, InterruptHandler};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_time::{Delay, Duration, Instant, Timer};
+#[cfg(not(feature = "host-sim"))]
+use embassy_usb::class::cdc_acm::{CdcAcmClass, State};
+#[cfg(not(feature = "host-sim"))]
+use embassy_usb::{Builder, UsbDevice};
+#[cfg(feature = "host-sim")]
+use embedded_storage::nor_flash::NorFlash;
+use fade::{Breathing, Easing};
+#[cfg(feature = "host-sim")]
+use host_sim::{IdleButton, StdioConsole, TracePin, TracePwm, reboot};
+use led::LedState;
+#[cfg(not(feature = "host-sim"))]
+use led::Polarity;
+#[cfg(feature = "host-sim")]
+use mock::MemFlash;
+#[cfg(not(any(feature = "panic-blink", feature = "host-sim")))]
 use panic_halt as _;
+use pattern::Pattern;
+use runner::{BankRunner, run_effect, write_state};
+use scheduler::Clock;
+use settings::{Settings, SettingsStore};
+use shell::{Console, ShellAction};
+#[cfg(not(feature = "host-sim"))]
+use static_cell::StaticCell;
+
+#[cfg(not(feature = "host-sim"))]
+bind_interrupts!(struct Irqs {
+    USBCTRL_IRQ => InterruptHandler<USB>;
+});
+
+/// Flash driver holding the settings region.
+#[cfg(not(feature = "host-sim"))]
+type SettingsFlash = Flash<'static, FLASH, Blocking, FLASH_SIZE>;
+
+/// Flash driver holding the settings region.
+#[cfg(feature = "host-sim")]
+type SettingsFlash = MemFlash;
+
+/// PWM output driving the main LED.
+#[cfg(not(feature = "host-sim"))]
+type LedPwm = PwmOutput<'static>;
+
+/// PWM output driving the main LED.
+#[cfg(feature = "host-sim")]
+type LedPwm = TracePwm;
+
+/// Output pin driving an indicator LED.
+#[cfg(not(feature = "host-sim"))]
+type IndicatorPin = Output<'static>;
+
+/// Output pin driving an indicator LED.
+#[cfg(feature = "host-sim")]
+type IndicatorPin = TracePin;
+
+/// Push-button input.
+#[cfg(not(feature = "host-sim"))]
+type ButtonInput = Input<'static>;
+
+/// Push-button input.
+#[cfg(feature = "host-sim")]
+type ButtonInput = IdleButton;
+
+/// Serial port carrying the console.
+#[cfg(not(feature = "host-sim"))]
+type ConsolePort = CdcAcmClass<'static, Driver<'static, USB>>;
+
+/// Serial port carrying the console.
+#[cfg(feature = "host-sim")]
+type ConsolePort = StdioConsole;
+
+/// USB full-speed packet size for the console.
+const USB_PACKET_LEN: usize = 64;
+
+/// Commands for the LED task.
+///
+/// # Details
+/// Any task may send through `LED_COMMANDS.sender()` to change
+/// the main LED without owning its pin.
+static LED_COMMANDS: Channel<CriticalSectionRawMutex, LedCommand, LED_COMMAND_DEPTH> =
+    Channel::new();
+
+/// Current main LED state, published by the LED task.
+///
+/// # Details
+/// Read by the console to answer state queries.
+static LED_ON: AtomicBool = AtomicBool::new(false);
+
+/// Commands the LED task could not apply.
+///
+/// # Details
+/// Counts commands the CommandHandler refused, such as an
+/// indication arriving while every arbiter slot is taken, so a
+/// dropped command shows up in a debugger instead of vanishing.
+static LED_REJECTED: AtomicU32 = AtomicU32::new(0);
+
+/// Converts a millisecond deadline into an instant.
+///
+/// # Details
+/// Deadlines too far out to represent saturate to `Instant::MAX`
+/// instead of overflowing the tick arithmetic.
+///
+/// # Arguments
+/// * `start` - Instant of time zero
+/// * `ms` - Deadline in milliseconds after `start`
+///
+/// # Returns
+/// * `Instant` - Deadline instant, or `Instant::MAX`
+fn deadline(start: Instant, ms: u64) -> Instant {
+    Duration::try_from_millis(ms)
+        .and_then(|after| start.checked_add(after))
+        .unwrap_or(Instant::MAX)
+}
+
+/// Embassy time as a scheduler clock.
+///
+/// # Details
+/// Counts milliseconds from its creation and waits with
+/// `Timer::at`, so deadlines are absolute instants.
+///
+/// # Fields
+/// * `start` - Instant of time zero
+struct EmbassyClock {
+    start: Instant,
+}
+
+impl EmbassyClock {
+    /// Creates a clock starting now.
+    ///
+    /// # Returns
+    /// * `Self` - New EmbassyClock instance
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for EmbassyClock {
+    /// Returns milliseconds since the clock was created.
+    fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis()
+    }
+
+    /// Sleeps until the absolute deadline.
+    async fn wait_until(&mut self, deadline_ms: u64) {
+        Timer::at(deadline(self.start, deadline_ms)).await;
+    }
+}
+
+/// Main LED task.
+///
+/// # Details
+/// Owns the dimmed LED pin. Sleeps until either the next pattern
+/// deadline or an incoming command, lets the CommandHandler
+/// decide the LED state and writes it. Refused commands are
+/// counted in `LED_REJECTED`. Starts with the saved
+/// startup pattern as the background pattern.
+///
+/// # Arguments
+/// * `led` - Dimmed PWM output for the main LED
+/// * `background` - Pattern to play until a command replaces it
+#[embassy_executor::task]
+async fn led_task(mut led: DimmedPin<LedPwm>, background: Pattern) {
+    let start = Instant::now();
+    let mut handler: CommandHandler<ARBITER_SLOTS> = CommandHandler::new(background);
+    loop {
+        let state = handler.advance(start.elapsed().as_millis());
+        LED_ON.store(state == LedState::On, Ordering::Relaxed);
+        let _ = write_state(&mut led, state);
+        let wake = handler
+            .next_deadline()
+            .map_or(Instant::MAX, |ms| deadline(start, ms));
+        if let Either::First(command) = select(LED_COMMANDS.receive(), Timer::at(wake)).await
+            && handler
+                .handle(command, start.elapsed().as_millis())
+                .is_err()
+        {
+            LED_REJECTED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Indicator bank task.
+///
+/// # Details
+/// Plays a staggered heartbeat across the indicator LEDs,
+/// all advanced from one shared timebase on absolute deadlines.
+///
+/// # Arguments
+/// * `pins` - Indicator output pins in channel order
+#[embassy_executor::task]
+async fn indicator_task(pins: [IndicatorPin; INDICATOR_COUNT]) {
+    let mut bank = LedBank::staggered(Pattern::heartbeat(), INDICATOR_PHASE_MS);
+    let mut runner = BankRunner::new(pins, EmbassyClock::new());
+    let _ = runner.run(&mut bank).await;
+}
+
+/// Push-button task.
+///
+/// # Details
+/// Wakes on every edge of the button input and on the button's
+/// next timeout, classifies clicks and sends the resulting mode
+/// changes to the LED task.
+///
+/// # Arguments
+/// * `input` - Button input, pulled up and pressed when low
+#[embassy_executor::task]
+async fn button_task(mut input: ButtonInput) {
+    let start = Instant::now();
+    let mut button = Button::new(ButtonTiming::default());
+    let mut modes = ModeCycler::new();
+    loop {
+        let now = start.elapsed().as_millis();
+        if let Some(command) = button
+            .update(input.is_low(), now)
+            .and_then(|e| modes.apply(e))
+        {
+            LED_COMMANDS.send(command).await;
+        }
+        let wake = button
+            .next_deadline()
+            .map_or(Instant::MAX, |ms| deadline(start, ms));
+        select(input.wait_for_any_edge(), Timer::at(wake)).await;
+    }
+}
+
+/// USB device task.
+///
+/// # Details
+/// Runs enumeration and control requests for the console.
+///
+/// # Arguments
+/// * `usb` - Built USB device
+#[cfg(not(feature = "host-sim"))]
+#[embassy_executor::task]
+async fn usb_task(mut usb: UsbDevice<'static, Driver<'static, USB>>) -> ! {
+    usb.run().await
+}
+
+/// Serial console task.
+///
+/// # Details
+/// Feeds every received byte to the Console, which serves the
+/// text shell and the framed control protocol on the one port,
+/// and writes its output back. LED changes go to the LED task,
+/// `save` writes the settings store and `reset` reboots. Starts
+/// a new session whenever the host reopens the port.
+///
+/// # Arguments
+/// * `class` - Console port, the CDC-ACM class on the board
+/// * `store` - Settings store, None if the region is unusable
+/// * `settings` - Settings loaded at boot
+#[embassy_executor::task]
+async fn shell_task(
+    mut class: ConsolePort,
+    mut store: Option<SettingsStore<SettingsFlash>>,
+    settings: Settings,
+) {
+    let mut console: Console<SHELL_LINE_LEN> = Console::new(settings);
+    let mut packet = [0u8; USB_PACKET_LEN];
+    loop {
+        class.wait_connection().await;
+        console.reset();
+        while let Ok(n) = class.read_packet(&mut packet).await {
+            for &byte in &packet[..n] {
+                let led = if LED_ON.load(Ordering::Relaxed) {
+                    LedState::On
+                } else {
+                    LedState::Off
+                };
+                let (output, action) = console.push(byte, led, |settings| {
+                    store.as_mut().is_some_and(|s| s.save(settings).is_ok())
+                });
+                for chunk in output.chunks(USB_PACKET_LEN) {
+                    let _ = class.write_packet(chunk).await;
+                }
+                match action {
+                    ShellAction::Led(command) => LED_COMMANDS.send(command).await,
+                    ShellAction::Reset => {
+                        Timer::after_millis(10).await;
+                        reboot();
+                    }
+                    ShellAction::None | ShellAction::Save(_) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Restarts the board.
+#[cfg(not(feature = "host-sim"))]
+fn reboot() -> ! {
+    cortex_m::peripheral::SCB::sys_reset()
+}
+
+/// Peripherals handed to the application tasks.
+///
+/// # Fields
+/// * `indicators` - Indicator LED pins in channel order
+/// * `button` - Push-button input
+/// * `console` - Serial port for the console
+/// * `led` - PWM output for the main LED
+struct Board {
+    indicators: [IndicatorPin; INDICATOR_COUNT],
+    button: ButtonInput,
+    console: ConsolePort,
+    led: LedPwm,
+}
+
+/// Starts the application tasks.
+///
+/// # Details
+/// Spawns the indicator bank, push-button and console tasks and
+/// hands the main LED at the configured brightness to the LED
+/// task, which other tasks control through `LED_COMMANDS`. When
+/// `BREATHING_ENABLED` is set the LED breathes with a sine-eased
+/// fade instead and this never returns; LED commands are then
+/// drained and dropped so their senders never block.
+///
+/// # Arguments
+/// * `spawner` - Embassy task spawner
+/// * `board` - Peripherals for the tasks
+/// * `store` - Settings store, None if the region is unusable
+/// * `settings` - Settings loaded at boot
+async fn run(
+    spawner: Spawner,
+    mut board: Board,
+    store: Option<SettingsStore<SettingsFlash>>,
+    settings: Settings,
+) {
+    spawner.spawn(indicator_task(board.indicators).unwrap());
+    spawner.spawn(button_task(board.button).unwrap());
+    spawner.spawn(shell_task(board.console, store, settings).unwrap());
+    let brightness = Brightness::from_percent(LED_BRIGHTNESS_PERCENT);
+    if BREATHING_ENABLED {
+        let breathing = Breathing::new(
+            Brightness::OFF,
+            brightness,
+            BREATHING_PERIOD_MS,
+            Easing::Sine,
+        );
+        let mut delay = Delay;
+        let effect = run_effect(&mut board.led, &mut delay, &breathing, FADE_STEP_MS);
+        let drain = async {
+            loop {
+                LED_COMMANDS.receive().await;
+            }
+        };
+        let _ = select(effect, drain).await;
+        return;
+    }
+    spawner.spawn(
+        led_task(
+            DimmedPin::new(board.led, brightness),
+            settings.startup_pattern(),
+        )
+        .unwrap(),
+    );
+}
 
 /// Main application entry point.
 ///
 /// # Details
-/// Initializes Embassy runtime and runs the main blink loop.
-/// Uses LedController for state management.
+/// Initializes Embassy runtime, loads the saved settings from
+/// the reserved flash region, brings up the USB console and the
+/// main LED on PWM slice 0 channel A and starts the application
+/// tasks.
 ///
 /// # Arguments
-/// * `_spawner` - Embassy task spawner (reserved for future async tasks).
+/// * `spawner` - Embassy task spawner for the LED, indicator, button and console tasks.
 ///
 /// # Returns
-/// * `()` - Never returns (infinite loop).
+/// * `()` - Returns once the tasks are spawned.
+#[cfg(not(feature = "host-sim"))]
 #[embassy_executor::main]
-async fn main(_spawner: Spawner) {
+async fn main(spawner: Spawner) {
     let p = embassy_rp::init(Default::default());
-    let mut led = Output::new(p.PIN_16, Level::Low);
-    let mut controller = LedController::new();
-    loop {
-        let state = controller.toggle();
-        if led_state_to_level(state) {
-            led.set_high();
-        } else {
-            led.set_low();
-        }
-        Timer::after_millis(controller.delay_ms()).await;
+    let flash = Flash::<_, Blocking, FLASH_SIZE>::new_blocking(p.FLASH);
+    let store = SettingsStore::new(flash, SETTINGS_OFFSET, SETTINGS_SIZE).ok();
+    let settings = store
+        .as_ref()
+        .map_or_else(Settings::default, |s| *s.settings());
+    #[cfg(feature = "panic-blink")]
+    panic_blink::set_polarity(settings.polarity);
+    let mut usb_config = embassy_usb::Config::new(USB_VID, USB_PID);
+    usb_config.manufacturer = Some("Kevin Thomas");
+    usb_config.product = Some("DAY001 Blink LED");
+    usb_config.max_power = 100;
+    usb_config.max_packet_size_0 = USB_PACKET_LEN as u8;
+    static CONFIG_DESCRIPTOR: StaticCell<[u8; 256]> = StaticCell::new();
+    static BOS_DESCRIPTOR: StaticCell<[u8; 256]> = StaticCell::new();
+    static CONTROL_BUF: StaticCell<[u8; 64]> = StaticCell::new();
+    static CDC_STATE: StaticCell<State> = StaticCell::new();
+    let mut builder = Builder::new(
+        Driver::new(p.USB, Irqs),
+        usb_config,
+        CONFIG_DESCRIPTOR.init([0; 256]),
+        BOS_DESCRIPTOR.init([0; 256]),
+        &mut [],
+        CONTROL_BUF.init([0; 64]),
+    );
+    let console = CdcAcmClass::new(
+        &mut builder,
+        CDC_STATE.init(State::new()),
+        USB_PACKET_LEN as u16,
+    );
+    spawner.spawn(usb_task(builder.build()).unwrap());
+    let mut pwm_config = PwmConfig::default();
+    pwm_config.top = LED_PWM_TOP;
+    pwm_config.compare_a = 0;
+    pwm_config.invert_a = settings.polarity == Polarity::ActiveLow;
+    let pwm = Pwm::new_output_a(p.PWM_SLICE0, p.PIN_16, pwm_config);
+    let (Some(led), _) = pwm.split() else {
+        return;
+    };
+    let board = Board {
+        indicators: [
+            Output::new(p.PIN_17, Level::Low),
+            Output::new(p.PIN_18, Level::Low),
+            Output::new(p.PIN_19, Level::Low),
+            Output::new(p.PIN_20, Level::Low),
+        ],
+        button: Input::new(p.PIN_14, Pull::Up),
+        console,
+        led,
+    };
+    #[cfg(feature = "panic-blink")]
+    if let Some(report) = panic_blink::boot_report() {
+        let _ = LED_COMMANDS.try_send(report);
     }
+    run(spawner, board, store, settings).await;
+}
+
+/// Host simulation entry point.
+///
+/// # Details
+/// Runs the same tasks on the std executor and time driver.
+/// Settings live in erased in-memory flash, pin changes are
+/// printed to stderr and the console uses stdin and stdout.
+///
+/// # Arguments
+/// * `spawner` - Embassy task spawner for the LED, indicator, button and console tasks.
+///
+/// # Returns
+/// * `()` - Returns once the tasks are spawned.
+#[cfg(feature = "host-sim")]
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let flash = MemFlash::new(FLASH_SIZE / MemFlash::ERASE_SIZE);
+    let store = SettingsStore::new(flash, SETTINGS_OFFSET, SETTINGS_SIZE).ok();
+    let settings = store
+        .as_ref()
+        .map_or_else(Settings::default, |s| *s.settings());
+    let board = Board {
+        indicators: ["GP17", "GP18", "GP19", "GP20"].map(TracePin::new),
+        button: IdleButton,
+        console: StdioConsole::new(),
+        led: TracePwm::new("GP16", LED_PWM_TOP),
+    };
+    run(spawner, board, store, settings).await;
 }
@@ -0,0 +1,803 @@
+/*
+ * @file mock.rs
+ * @brief Host mock GPIO pin and virtual clock
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: mock.rs
+//!
+//! DESCRIPTION:
+//! Host Test Support for RP2350 Blink Logic.
+//!
+//! BRIEF:
+//! Provides a recording mock output pin, a manually advanced
+//! virtual clock and a matching async delay so LedController
+//! timelines can be asserted without hardware, plus an in-memory
+//! NOR flash for storage tests.
+//! Only built for host tests or with the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::scheduler::Clock;
+use crate::waveform;
+use core::convert::Infallible;
+use core::future::Future;
+use core::task::{Context, Poll, Waker};
+use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal_async::delay::DelayNs;
+use embedded_storage::nor_flash::{
+    ErrorType as FlashErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Nanoseconds per millisecond.
+const NS_PER_MS: u64 = 1_000_000;
+
+/// Manually advanced virtual clock.
+///
+/// # Details
+/// Clones share the same time, so a pin and a delay created
+/// from one clock observe each other's progress.
+/// Time is kept in nanoseconds and reported in milliseconds.
+///
+/// # Fields
+/// * `now_ns` - Shared current time in nanoseconds
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock {
+    now_ns: Rc<Cell<u64>>,
+}
+
+impl VirtualClock {
+    /// Creates a clock at time zero.
+    ///
+    /// # Returns
+    /// * `Self` - New VirtualClock instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current time.
+    ///
+    /// # Returns
+    /// * `u64` - Elapsed virtual milliseconds
+    pub fn now_ms(&self) -> u64 {
+        self.now_ns.get() / NS_PER_MS
+    }
+
+    /// Returns the current time at full resolution.
+    ///
+    /// # Returns
+    /// * `u64` - Elapsed virtual nanoseconds
+    pub fn now_ns(&self) -> u64 {
+        self.now_ns.get()
+    }
+
+    /// Advances the clock.
+    ///
+    /// # Arguments
+    /// * `ms` - Milliseconds to advance
+    pub fn advance_ms(&self, ms: u64) {
+        self.advance_ns(ms.saturating_mul(NS_PER_MS));
+    }
+
+    /// Advances the clock.
+    ///
+    /// # Arguments
+    /// * `ns` - Nanoseconds to advance
+    pub fn advance_ns(&self, ns: u64) {
+        self.now_ns.set(self.now_ns.get().saturating_add(ns));
+    }
+
+    /// Creates an async delay that advances this clock.
+    ///
+    /// # Returns
+    /// * `VirtualDelay` - Delay sharing this clock
+    pub fn delay(&self) -> VirtualDelay {
+        VirtualDelay {
+            clock: self.clone(),
+        }
+    }
+
+    /// Creates an absolute-deadline timer on this clock.
+    ///
+    /// # Returns
+    /// * `VirtualTimer` - Timer sharing this clock, without latency
+    pub fn timer(&self) -> VirtualTimer {
+        VirtualTimer {
+            clock: self.clone(),
+            latency_ns: 0,
+        }
+    }
+
+    /// Creates a recording pin stamped by this clock.
+    ///
+    /// # Returns
+    /// * `MockPin` - Pin sharing this clock
+    pub fn pin(&self) -> MockPin {
+        MockPin::new(self.clone())
+    }
+}
+
+/// Async delay backed by a virtual clock.
+///
+/// # Details
+/// Completes immediately after advancing the clock by the
+/// requested time, so runners progress without real waiting.
+///
+/// # Fields
+/// * `clock` - Clock advanced by each delay
+#[derive(Clone, Debug)]
+pub struct VirtualDelay {
+    clock: VirtualClock,
+}
+
+impl DelayNs for VirtualDelay {
+    /// Advances the virtual clock.
+    ///
+    /// # Arguments
+    /// * `ns` - Nanoseconds to wait
+    async fn delay_ns(&mut self, ns: u32) {
+        self.clock.advance_ns(u64::from(ns));
+    }
+
+    /// Advances the virtual clock.
+    ///
+    /// # Arguments
+    /// * `ms` - Milliseconds to wait
+    async fn delay_ms(&mut self, ms: u32) {
+        self.clock.advance_ms(u64::from(ms));
+    }
+}
+
+/// Absolute-deadline timer backed by a virtual clock.
+///
+/// # Details
+/// Waits jump the clock to the deadline, then add a fixed
+/// latency to model wake-up delay in a real executor.
+///
+/// # Fields
+/// * `clock` - Clock advanced by each wait
+/// * `latency_ns` - Extra time added after every wait
+#[derive(Clone, Debug)]
+pub struct VirtualTimer {
+    clock: VirtualClock,
+    latency_ns: u64,
+}
+
+impl VirtualTimer {
+    /// Sets the latency added after every wait.
+    ///
+    /// # Arguments
+    /// * `ns` - Latency in nanoseconds
+    ///
+    /// # Returns
+    /// * `Self` - Updated timer
+    pub fn with_latency_ns(mut self, ns: u64) -> Self {
+        self.latency_ns = ns;
+        self
+    }
+}
+
+impl Clock for VirtualTimer {
+    /// Returns the virtual time.
+    fn now_ms(&self) -> u64 {
+        self.clock.now_ms()
+    }
+
+    /// Jumps to the deadline if it is ahead, then adds latency.
+    async fn wait_until(&mut self, deadline_ms: u64) {
+        let deadline_ns = deadline_ms.saturating_mul(NS_PER_MS);
+        let now_ns = self.clock.now_ns();
+        self.clock
+            .advance_ns(deadline_ns.saturating_sub(now_ns) + self.latency_ns);
+    }
+}
+
+/// Recording mock output pin.
+///
+/// # Details
+/// Logs every level change as `(timestamp_ms, level)` using
+/// the shared virtual clock. Writes that repeat the current
+/// level are counted but not logged.
+///
+/// # Fields
+/// * `clock` - Clock used for timestamps
+/// * `level` - Current level, None before the first write
+/// * `transitions` - Logged level changes
+/// * `writes` - Total number of writes
+#[derive(Clone, Debug)]
+pub struct MockPin {
+    clock: VirtualClock,
+    level: Option<bool>,
+    transitions: Vec<(u64, bool)>,
+    writes: usize,
+}
+
+impl MockPin {
+    /// Creates a pin with no recorded history.
+    ///
+    /// # Arguments
+    /// * `clock` - Clock used for timestamps
+    ///
+    /// # Returns
+    /// * `Self` - New MockPin instance
+    pub fn new(clock: VirtualClock) -> Self {
+        Self {
+            clock,
+            level: None,
+            transitions: Vec::new(),
+            writes: 0,
+        }
+    }
+
+    /// Returns the recorded level changes.
+    ///
+    /// # Returns
+    /// * `&[(u64, bool)]` - `(timestamp_ms, level)` pairs in order
+    pub fn transitions(&self) -> &[(u64, bool)] {
+        &self.transitions
+    }
+
+    /// Returns the current level.
+    ///
+    /// # Returns
+    /// * `Option<bool>` - Level, or None if never written
+    pub fn level(&self) -> Option<bool> {
+        self.level
+    }
+
+    /// Returns the level at a point in the recorded timeline.
+    ///
+    /// # Arguments
+    /// * `at_ms` - Virtual timestamp in milliseconds
+    ///
+    /// # Returns
+    /// * `Option<bool>` - Level at that time, or None before the first write
+    pub fn level_at(&self, at_ms: u64) -> Option<bool> {
+        waveform::level_at(&self.transitions, at_ms)
+    }
+
+    /// Returns the total number of writes.
+    ///
+    /// # Returns
+    /// * `usize` - Writes including repeats of the current level
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Records a write.
+    ///
+    /// # Arguments
+    /// * `level` - Level written
+    fn record(&mut self, level: bool) {
+        self.writes += 1;
+        if self.level != Some(level) {
+            self.level = Some(level);
+            self.transitions.push((self.clock.now_ms(), level));
+        }
+    }
+}
+
+impl ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockPin {
+    /// Records a low level.
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.record(false);
+        Ok(())
+    }
+
+    /// Records a high level.
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.record(true);
+        Ok(())
+    }
+}
+
+/// In-memory flash errors.
+///
+/// # Variants
+/// * `OutOfBounds` - Access beyond the flash size
+/// * `NotAligned` - Offset or length not a multiple of the block size
+/// * `PowerLoss` - Injected failure partway through a write
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemFlashError {
+    OutOfBounds,
+    NotAligned,
+    PowerLoss,
+}
+
+impl NorFlashError for MemFlashError {
+    /// Maps to the embedded-storage error kind.
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            MemFlashError::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            MemFlashError::NotAligned => NorFlashErrorKind::NotAligned,
+            MemFlashError::PowerLoss => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+/// In-memory NOR flash.
+///
+/// # Details
+/// Behaves like real NOR: erase sets a sector to 0xFF and writes
+/// can only clear bits. Counts erases per sector and can simulate
+/// power loss by failing after a number of bytes.
+///
+/// # Fields
+/// * `data` - Flash contents
+/// * `erases` - Erase count per sector
+/// * `write_budget` - Bytes that may still be written before failing
+#[derive(Clone, Debug)]
+pub struct MemFlash {
+    data: Vec<u8>,
+    erases: Vec<u32>,
+    write_budget: Option<usize>,
+}
+
+impl MemFlash {
+    /// Creates an erased flash.
+    ///
+    /// # Arguments
+    /// * `sectors` - Size in `ERASE_SIZE` sectors
+    ///
+    /// # Returns
+    /// * `Self` - New MemFlash instance
+    pub fn new(sectors: usize) -> Self {
+        Self {
+            data: vec![0xFF; sectors * Self::ERASE_SIZE],
+            erases: vec![0; sectors],
+            write_budget: None,
+        }
+    }
+
+    /// Creates a flash holding an image.
+    ///
+    /// # Details
+    /// The image is padded with 0xFF to whole sectors.
+    ///
+    /// # Arguments
+    /// * `data` - Flash contents
+    ///
+    /// # Returns
+    /// * `Self` - New MemFlash instance
+    pub fn from_data(mut data: Vec<u8>) -> Self {
+        let sectors = data.len().div_ceil(Self::ERASE_SIZE);
+        data.resize(sectors * Self::ERASE_SIZE, 0xFF);
+        Self {
+            data,
+            erases: vec![0; sectors],
+            write_budget: None,
+        }
+    }
+
+    /// Returns the flash contents.
+    ///
+    /// # Returns
+    /// * `&[u8]` - Raw bytes
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the flash contents for corruption tests.
+    ///
+    /// # Returns
+    /// * `&mut [u8]` - Raw bytes
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Returns the erase count of every sector.
+    ///
+    /// # Returns
+    /// * `&[u32]` - Erases per sector
+    pub fn erase_counts(&self) -> &[u32] {
+        &self.erases
+    }
+
+    /// Fails writes once a number of further bytes are written.
+    ///
+    /// # Arguments
+    /// * `bytes` - Bytes to accept before losing power, None to disable
+    pub fn fail_after(&mut self, bytes: Option<usize>) {
+        self.write_budget = bytes;
+    }
+
+    /// Checks a request against alignment and bounds.
+    ///
+    /// # Arguments
+    /// * `offset` - Start offset
+    /// * `len` - Length in bytes
+    /// * `align` - Required alignment
+    ///
+    /// # Returns
+    /// * `Result<usize, MemFlashError>` - Start index or error
+    fn check(&self, offset: u32, len: usize, align: usize) -> Result<usize, MemFlashError> {
+        let start = offset as usize;
+        if !start.is_multiple_of(align) || !len.is_multiple_of(align) {
+            return Err(MemFlashError::NotAligned);
+        }
+        if start + len > self.data.len() {
+            return Err(MemFlashError::OutOfBounds);
+        }
+        Ok(start)
+    }
+}
+
+impl FlashErrorType for MemFlash {
+    type Error = MemFlashError;
+}
+
+impl ReadNorFlash for MemFlash {
+    const READ_SIZE: usize = 1;
+
+    /// Copies flash contents into `bytes`.
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), MemFlashError> {
+        let start = self.check(offset, bytes.len(), Self::READ_SIZE)?;
+        bytes.copy_from_slice(&self.data[start..start + bytes.len()]);
+        Ok(())
+    }
+
+    /// Returns the flash size in bytes.
+    fn capacity(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl NorFlash for MemFlash {
+    const WRITE_SIZE: usize = 4;
+    const ERASE_SIZE: usize = 4096;
+
+    /// Sets every byte in whole sectors to 0xFF.
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), MemFlashError> {
+        if to < from {
+            return Err(MemFlashError::OutOfBounds);
+        }
+        let start = self.check(from, (to - from) as usize, Self::ERASE_SIZE)?;
+        self.data[start..to as usize].fill(0xFF);
+        for sector in start / Self::ERASE_SIZE..to as usize / Self::ERASE_SIZE {
+            self.erases[sector] += 1;
+        }
+        Ok(())
+    }
+
+    /// Clears bits, like programming real NOR flash.
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), MemFlashError> {
+        let start = self.check(offset, bytes.len(), Self::WRITE_SIZE)?;
+        for (i, byte) in bytes.iter().enumerate() {
+            if let Some(budget) = self.write_budget.as_mut() {
+                if *budget == 0 {
+                    return Err(MemFlashError::PowerLoss);
+                }
+                *budget -= 1;
+            }
+            self.data[start + i] &= byte;
+        }
+        Ok(())
+    }
+}
+
+/// Polls a future to completion on the current thread.
+///
+/// # Details
+/// Intended for futures driven by virtual time, which never
+/// actually pend.
+///
+/// # Arguments
+/// * `fut` - Future to run
+///
+/// # Returns
+/// * `F::Output` - Future output
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = core::pin::pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BlinkConfig;
+    use crate::led::LedController;
+    use crate::pattern::{EndAction, Pattern};
+    use crate::runner::BlinkRunner;
+
+    fn run_for(ctrl: &mut LedController, clock: &VirtualClock, until_ms: u64) -> MockPin {
+        let mut pin = clock.pin();
+        let mut runner = BlinkRunner::new(&mut pin, clock.delay());
+        while clock.now_ms() < until_ms && !ctrl.is_finished() {
+            block_on(runner.step(ctrl)).unwrap();
+        }
+        pin
+    }
+
+    // ==================== VirtualClock Tests ====================
+
+    #[test]
+    fn test_clock_starts_at_zero() {
+        let clock = VirtualClock::new();
+        assert_eq!(clock.now_ms(), 0);
+        assert_eq!(clock.now_ns(), 0);
+    }
+
+    #[test]
+    fn test_clock_advance_ms() {
+        let clock = VirtualClock::new();
+        clock.advance_ms(250);
+        clock.advance_ms(250);
+        assert_eq!(clock.now_ms(), 500);
+    }
+
+    #[test]
+    fn test_clock_advance_ns_accumulates() {
+        let clock = VirtualClock::new();
+        clock.advance_ns(600_000);
+        assert_eq!(clock.now_ms(), 0);
+        clock.advance_ns(600_000);
+        assert_eq!(clock.now_ms(), 1);
+    }
+
+    #[test]
+    fn test_clock_clones_share_time() {
+        let clock = VirtualClock::new();
+        let other = clock.clone();
+        other.advance_ms(42);
+        assert_eq!(clock.now_ms(), 42);
+    }
+
+    #[test]
+    fn test_clock_saturates() {
+        let clock = VirtualClock::new();
+        clock.advance_ms(u64::MAX);
+        clock.advance_ms(1);
+        assert_eq!(clock.now_ns(), u64::MAX);
+    }
+
+    // ==================== VirtualDelay Tests ====================
+
+    #[test]
+    fn test_delay_ms_advances_clock() {
+        let clock = VirtualClock::new();
+        let mut delay = clock.delay();
+        block_on(delay.delay_ms(500));
+        assert_eq!(clock.now_ms(), 500);
+    }
+
+    #[test]
+    fn test_delay_us_advances_clock() {
+        let clock = VirtualClock::new();
+        let mut delay = clock.delay();
+        block_on(delay.delay_us(1500));
+        assert_eq!(clock.now_ns(), 1_500_000);
+    }
+
+    // ==================== VirtualTimer Tests ====================
+
+    #[test]
+    fn test_timer_waits_until_deadline() {
+        let clock = VirtualClock::new();
+        let mut timer = clock.timer();
+        block_on(timer.wait_until(750));
+        assert_eq!(clock.now_ms(), 750);
+        assert_eq!(timer.now_ms(), 750);
+    }
+
+    #[test]
+    fn test_timer_past_deadline_returns_at_once() {
+        let clock = VirtualClock::new();
+        clock.advance_ms(900);
+        block_on(clock.timer().wait_until(750));
+        assert_eq!(clock.now_ms(), 900);
+    }
+
+    #[test]
+    fn test_timer_adds_latency() {
+        let clock = VirtualClock::new();
+        let mut timer = clock.timer().with_latency_ns(3 * NS_PER_MS);
+        block_on(timer.wait_until(100));
+        assert_eq!(clock.now_ms(), 103);
+    }
+
+    // ==================== MockPin Tests ====================
+
+    #[test]
+    fn test_pin_starts_unwritten() {
+        let pin = VirtualClock::new().pin();
+        assert_eq!(pin.level(), None);
+        assert!(pin.transitions().is_empty());
+        assert_eq!(pin.writes(), 0);
+    }
+
+    #[test]
+    fn test_pin_records_timestamps() {
+        let clock = VirtualClock::new();
+        let mut pin = clock.pin();
+        pin.set_high().unwrap();
+        clock.advance_ms(10);
+        pin.set_low().unwrap();
+        assert_eq!(pin.transitions(), &[(0, true), (10, false)]);
+    }
+
+    #[test]
+    fn test_pin_ignores_repeated_level() {
+        let clock = VirtualClock::new();
+        let mut pin = clock.pin();
+        pin.set_low().unwrap();
+        clock.advance_ms(5);
+        pin.set_low().unwrap();
+        assert_eq!(pin.transitions(), &[(0, false)]);
+        assert_eq!(pin.writes(), 2);
+    }
+
+    #[test]
+    fn test_pin_level_at() {
+        let clock = VirtualClock::new();
+        let mut pin = clock.pin();
+        clock.advance_ms(5);
+        pin.set_high().unwrap();
+        clock.advance_ms(10);
+        pin.set_low().unwrap();
+        assert_eq!(pin.level_at(0), None);
+        assert_eq!(pin.level_at(5), Some(true));
+        assert_eq!(pin.level_at(14), Some(true));
+        assert_eq!(pin.level_at(15), Some(false));
+        assert_eq!(pin.level_at(1000), Some(false));
+    }
+
+    // ==================== MemFlash Tests ====================
+
+    #[test]
+    fn test_mem_flash_starts_erased() {
+        let flash = MemFlash::new(2);
+        assert_eq!(flash.capacity(), 8192);
+        assert!(flash.data().iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn test_mem_flash_write_and_read() {
+        let mut flash = MemFlash::new(1);
+        flash.write(8, &[1, 2, 3, 4]).unwrap();
+        let mut buf = [0u8; 6];
+        flash.read(7, &mut buf).unwrap();
+        assert_eq!(buf, [0xFF, 1, 2, 3, 4, 0xFF]);
+    }
+
+    #[test]
+    fn test_mem_flash_from_data_pads_sectors() {
+        let flash = MemFlash::from_data(vec![0x12; 5000]);
+        assert_eq!(flash.data().len(), 8192);
+        assert_eq!(flash.erase_counts(), &[0, 0]);
+        assert_eq!(flash.data()[4999], 0x12);
+        assert_eq!(flash.data()[5000], 0xFF);
+    }
+
+    #[test]
+    fn test_mem_flash_write_only_clears_bits() {
+        let mut flash = MemFlash::new(1);
+        flash.write(0, &[0x0F, 0xF0, 0xFF, 0x00]).unwrap();
+        flash.write(0, &[0xF0, 0xFF, 0x0F, 0xFF]).unwrap();
+        assert_eq!(&flash.data()[..4], &[0x00, 0xF0, 0x0F, 0x00]);
+    }
+
+    #[test]
+    fn test_mem_flash_erase_counts_sectors() {
+        let mut flash = MemFlash::new(3);
+        flash.write(4096, &[0; 4]).unwrap();
+        flash.erase(4096, 12288).unwrap();
+        assert_eq!(flash.erase_counts(), &[0, 1, 1]);
+        assert_eq!(flash.data()[4096], 0xFF);
+    }
+
+    #[test]
+    fn test_mem_flash_alignment_and_bounds() {
+        let mut flash = MemFlash::new(1);
+        assert_eq!(flash.write(2, &[0; 4]), Err(MemFlashError::NotAligned));
+        assert_eq!(flash.write(0, &[0; 3]), Err(MemFlashError::NotAligned));
+        assert_eq!(flash.erase(0, 100), Err(MemFlashError::NotAligned));
+        assert_eq!(flash.write(4096, &[0; 4]), Err(MemFlashError::OutOfBounds));
+        assert_eq!(flash.erase(0, 8192), Err(MemFlashError::OutOfBounds));
+    }
+
+    #[test]
+    fn test_mem_flash_power_loss_tears_write() {
+        let mut flash = MemFlash::new(1);
+        flash.fail_after(Some(2));
+        assert_eq!(flash.write(0, &[0; 4]), Err(MemFlashError::PowerLoss));
+        assert_eq!(&flash.data()[..4], &[0, 0, 0xFF, 0xFF]);
+        flash.fail_after(None);
+        assert!(flash.write(4, &[0; 4]).is_ok());
+    }
+
+    // ==================== LedController Timeline Tests ====================
+
+    #[test]
+    fn test_default_blink_timeline() {
+        let clock = VirtualClock::new();
+        let mut ctrl = LedController::new();
+        let pin = run_for(&mut ctrl, &clock, 1500);
+        assert_eq!(pin.transitions(), &[(0, true), (500, false), (1000, true)]);
+        assert_eq!(clock.now_ms(), 1500);
+    }
+
+    #[test]
+    fn test_asymmetric_config_timeline() {
+        let clock = VirtualClock::new();
+        let config = BlinkConfig::from_period(2000, 25).unwrap();
+        let mut ctrl = LedController::with_config(config);
+        let pin = run_for(&mut ctrl, &clock, 4000);
+        assert_eq!(
+            pin.transitions(),
+            &[(0, true), (50, false), (2000, true), (2050, false)]
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_timeline() {
+        let clock = VirtualClock::new();
+        let mut ctrl = LedController::with_pattern(Pattern::heartbeat());
+        let pin = run_for(&mut ctrl, &clock, 1000);
+        assert_eq!(
+            pin.transitions(),
+            &[(0, true), (100, false), (200, true), (300, false)]
+        );
+        assert_eq!(pin.level_at(999), Some(false));
+    }
+
+    #[test]
+    fn test_stop_pattern_timeline() {
+        let clock = VirtualClock::new();
+        let pattern = Pattern::double_blink().with_end_action(EndAction::Stop);
+        let mut ctrl = LedController::with_pattern(pattern);
+        let pin = run_for(&mut ctrl, &clock, u64::MAX);
+        assert_eq!(
+            pin.transitions(),
+            &[(0, true), (100, false), (300, true), (400, false)]
+        );
+        assert!(ctrl.is_finished());
+        assert_eq!(clock.now_ms(), 2000);
+    }
+
+    #[test]
+    fn test_sos_timeline_period() {
+        let clock = VirtualClock::new();
+        let mut ctrl = LedController::with_pattern(Pattern::sos());
+        let pin = run_for(&mut ctrl, &clock, Pattern::sos().period_ms());
+        let highs = pin.transitions().iter().filter(|(_, l)| *l).count();
+        assert_eq!(highs, 9);
+        assert_eq!(clock.now_ms(), Pattern::sos().period_ms());
+    }
+}
@@ -0,0 +1,541 @@
+/*
+ * @file morse.rs
+ * @brief Morse code encoder for LED patterns
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: morse.rs
+//!
+//! DESCRIPTION:
+//! Morse Code Encoder for RP2350 LED Patterns.
+//!
+//! BRIEF:
+//! Converts ASCII text into a timed on/off Pattern using standard
+//! dot/dash and spacing ratios, with configurable words-per-minute
+//! and optional Farnsworth spacing.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::pattern::{Pattern, PatternError, Segment};
+
+/// Default Morse speed in words per minute.
+///
+/// # Value
+/// 12 WPM
+#[allow(dead_code)]
+pub const DEFAULT_WPM: u16 = 12;
+
+/// Milliseconds per dot at one word per minute.
+///
+/// # Details
+/// The standard word "PARIS " is 50 dot units long.
+///
+/// # Value
+/// 1200 milliseconds
+const DOT_MS_AT_1_WPM: u64 = 1200;
+
+/// Morse encoding errors.
+///
+/// # Variants
+/// * `UnsupportedChar` - Character has no Morse symbol
+/// * `Empty` - Text contains no characters to send
+/// * `TooLong` - Encoded message exceeds pattern capacity
+/// * `InvalidSpeed` - Speed is zero, too high, or Farnsworth exceeds it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum MorseError {
+    UnsupportedChar(char),
+    Empty,
+    TooLong,
+    InvalidSpeed,
+}
+
+/// Morse speed configuration.
+///
+/// # Details
+/// Characters are sent at `wpm`. With Farnsworth spacing the gaps
+/// between characters and words are stretched so the overall rate
+/// drops to `farnsworth_wpm`.
+///
+/// # Fields
+/// * `wpm` - Character speed in words per minute
+/// * `farnsworth_wpm` - Optional slower overall speed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorseConfig {
+    pub wpm: u16,
+    pub farnsworth_wpm: Option<u16>,
+}
+
+impl Default for MorseConfig {
+    /// Returns `DEFAULT_WPM` without Farnsworth spacing.
+    ///
+    /// # Returns
+    /// * `Self` - Default Morse configuration
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self {
+            wpm: DEFAULT_WPM,
+            farnsworth_wpm: None,
+        }
+    }
+}
+
+/// Element and gap durations derived from a MorseConfig.
+///
+/// # Fields
+/// * `dot_ms` - Dot length
+/// * `dash_ms` - Dash length, three dots
+/// * `element_gap_ms` - Gap inside a character, one dot
+/// * `char_gap_ms` - Gap between characters, three units
+/// * `word_gap_ms` - Gap between words, seven units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct MorseTiming {
+    pub dot_ms: u64,
+    pub dash_ms: u64,
+    pub element_gap_ms: u64,
+    pub char_gap_ms: u64,
+    pub word_gap_ms: u64,
+}
+
+impl MorseConfig {
+    /// Computes element and gap durations.
+    ///
+    /// # Details
+    /// Dot length is 1200 ms / WPM. Farnsworth spacing follows
+    /// the ARRL formula: the time left over for the 19 spacing
+    /// units of "PARIS " at the overall speed is shared 3:7
+    /// between character and word gaps.
+    ///
+    /// # Returns
+    /// * `Result<MorseTiming, MorseError>` - Timing or speed error
+    #[allow(dead_code)]
+    pub fn timing(&self) -> Result<MorseTiming, MorseError> {
+        let c = u64::from(self.wpm);
+        if c == 0 || c > DOT_MS_AT_1_WPM {
+            return Err(MorseError::InvalidSpeed);
+        }
+        let dot_ms = DOT_MS_AT_1_WPM / c;
+        let (char_gap_ms, word_gap_ms) = match self.farnsworth_wpm {
+            None => (3 * dot_ms, 7 * dot_ms),
+            Some(s) if s == 0 || u64::from(s) > c => return Err(MorseError::InvalidSpeed),
+            Some(s) => {
+                let s = u64::from(s);
+                let spacing_ms = (60_000 * c - 37_200 * s) / (s * c);
+                (3 * spacing_ms / 19, 7 * spacing_ms / 19)
+            }
+        };
+        Ok(MorseTiming {
+            dot_ms,
+            dash_ms: 3 * dot_ms,
+            element_gap_ms: dot_ms,
+            char_gap_ms,
+            word_gap_ms,
+        })
+    }
+}
+
+/// Returns the Morse symbol for a character.
+///
+/// # Details
+/// Letters are case-insensitive. Covers A-Z, 0-9 and common
+/// ITU punctuation.
+///
+/// # Arguments
+/// * `c` - Character to look up
+///
+/// # Returns
+/// * `Option<&'static str>` - Dots and dashes, or None if unsupported
+#[allow(dead_code)]
+pub fn symbol(c: char) -> Option<&'static str> {
+    Some(match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '.' => ".-.-.-",
+        ',' => "--..--",
+        '?' => "..--..",
+        '\'' => ".----.",
+        '!' => "-.-.--",
+        '/' => "-..-.",
+        '(' => "-.--.",
+        ')' => "-.--.-",
+        '&' => ".-...",
+        ':' => "---...",
+        '=' => "-...-",
+        '+' => ".-.-.",
+        '-' => "-....-",
+        '"' => ".-..-.",
+        '@' => ".--.-.",
+        _ => return None,
+    })
+}
+
+/// Encodes text as a Morse pattern.
+///
+/// # Details
+/// Each element is an on segment followed by an off gap; the gap
+/// after a character's last element is the character gap, after a
+/// word's last character the word gap. The message ends with a word
+/// gap so a looping pattern repeats cleanly. Runs of whitespace
+/// count as one word break.
+///
+/// # Arguments
+/// * `text` - ASCII text to send
+/// * `config` - Speed configuration
+///
+/// # Returns
+/// * `Result<Pattern, MorseError>` - Looping pattern or encoding error
+#[allow(dead_code)]
+pub fn encode(text: &str, config: MorseConfig) -> Result<Pattern, MorseError> {
+    let timing = config.timing()?;
+    let mut pattern = Pattern::new();
+    for word in text.split_whitespace() {
+        let mut chars = word.chars().peekable();
+        while let Some(c) = chars.next() {
+            let code = symbol(c).ok_or(MorseError::UnsupportedChar(c))?;
+            let mut elements = code.bytes().peekable();
+            while let Some(element) = elements.next() {
+                let on_ms = if element == b'-' {
+                    timing.dash_ms
+                } else {
+                    timing.dot_ms
+                };
+                let off_ms = if elements.peek().is_some() {
+                    timing.element_gap_ms
+                } else if chars.peek().is_some() {
+                    timing.char_gap_ms
+                } else {
+                    timing.word_gap_ms
+                };
+                push(&mut pattern, Segment::on(on_ms))?;
+                push(&mut pattern, Segment::off(off_ms))?;
+            }
+        }
+    }
+    if pattern.is_empty() {
+        return Err(MorseError::Empty);
+    }
+    Ok(pattern)
+}
+
+/// Appends a segment, mapping capacity errors.
+///
+/// # Arguments
+/// * `pattern` - Pattern to extend
+/// * `segment` - Segment to append
+///
+/// # Returns
+/// * `Result<(), MorseError>` - Ok or `TooLong`
+fn push(pattern: &mut Pattern, segment: Segment) -> Result<(), MorseError> {
+    pattern.push(segment).map_err(|e| match e {
+        PatternError::TooManySegments => MorseError::TooLong,
+        _ => MorseError::InvalidSpeed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::led::LedState;
+    use crate::pattern::MAX_SEGMENTS;
+
+    fn cfg(wpm: u16) -> MorseConfig {
+        MorseConfig {
+            wpm,
+            farnsworth_wpm: None,
+        }
+    }
+
+    fn durations(p: &Pattern) -> Vec<(LedState, u64)> {
+        p.segments()
+            .iter()
+            .map(|s| (s.state, s.duration_ms))
+            .collect()
+    }
+
+    // ==================== symbol() Tests ====================
+
+    #[test]
+    fn test_symbol_letters() {
+        assert_eq!(symbol('S'), Some("..."));
+        assert_eq!(symbol('O'), Some("---"));
+    }
+
+    #[test]
+    fn test_symbol_case_insensitive() {
+        assert_eq!(symbol('k'), symbol('K'));
+    }
+
+    #[test]
+    fn test_symbol_digits() {
+        assert_eq!(symbol('0'), Some("-----"));
+        assert_eq!(symbol('7'), Some("--..."));
+    }
+
+    #[test]
+    fn test_symbol_all_alphanumerics_supported() {
+        for c in ('A'..='Z').chain('0'..='9') {
+            assert!(symbol(c).is_some(), "{c}");
+        }
+    }
+
+    #[test]
+    fn test_symbol_unsupported() {
+        assert_eq!(symbol('#'), None);
+        assert_eq!(symbol('é'), None);
+    }
+
+    // ==================== MorseConfig::timing() Tests ====================
+
+    #[test]
+    fn test_timing_default() {
+        let t = MorseConfig::default().timing().unwrap();
+        assert_eq!(t.dot_ms, 100);
+        assert_eq!(t.dash_ms, 300);
+        assert_eq!(t.element_gap_ms, 100);
+        assert_eq!(t.char_gap_ms, 300);
+        assert_eq!(t.word_gap_ms, 700);
+    }
+
+    #[test]
+    fn test_timing_20_wpm() {
+        let t = cfg(20).timing().unwrap();
+        assert_eq!(t.dot_ms, 60);
+        assert_eq!(t.dash_ms, 180);
+    }
+
+    #[test]
+    fn test_timing_paris_is_50_units() {
+        let p = encode("PARIS", cfg(12)).unwrap();
+        assert_eq!(p.period_ms(), 50 * 100);
+    }
+
+    #[test]
+    fn test_timing_zero_wpm() {
+        assert_eq!(cfg(0).timing(), Err(MorseError::InvalidSpeed));
+    }
+
+    #[test]
+    fn test_timing_too_fast() {
+        assert_eq!(cfg(1201).timing(), Err(MorseError::InvalidSpeed));
+    }
+
+    #[test]
+    fn test_farnsworth_stretches_gaps() {
+        let config = MorseConfig {
+            wpm: 20,
+            farnsworth_wpm: Some(10),
+        };
+        let t = config.timing().unwrap();
+        assert_eq!(t.dot_ms, 60);
+        assert_eq!(t.element_gap_ms, 60);
+        assert!(t.char_gap_ms > 3 * t.dot_ms);
+        assert!(t.word_gap_ms > 7 * t.dot_ms);
+    }
+
+    #[test]
+    fn test_farnsworth_overall_speed() {
+        let config = MorseConfig {
+            wpm: 20,
+            farnsworth_wpm: Some(10),
+        };
+        let p = encode("PARIS", config).unwrap();
+        let minute_per_word_ms = 60_000 / 10;
+        assert!(p.period_ms().abs_diff(minute_per_word_ms) < 20);
+    }
+
+    #[test]
+    fn test_farnsworth_equal_speed_is_standard() {
+        let config = MorseConfig {
+            wpm: 12,
+            farnsworth_wpm: Some(12),
+        };
+        assert_eq!(config.timing(), cfg(12).timing());
+    }
+
+    #[test]
+    fn test_farnsworth_faster_than_wpm() {
+        let config = MorseConfig {
+            wpm: 10,
+            farnsworth_wpm: Some(15),
+        };
+        assert_eq!(config.timing(), Err(MorseError::InvalidSpeed));
+    }
+
+    #[test]
+    fn test_farnsworth_zero() {
+        let config = MorseConfig {
+            wpm: 10,
+            farnsworth_wpm: Some(0),
+        };
+        assert_eq!(config.timing(), Err(MorseError::InvalidSpeed));
+    }
+
+    // ==================== encode() Tests ====================
+
+    #[test]
+    fn test_encode_single_dot() {
+        let p = encode("E", cfg(12)).unwrap();
+        assert_eq!(
+            durations(&p),
+            vec![(LedState::On, 100), (LedState::Off, 700)]
+        );
+    }
+
+    #[test]
+    fn test_encode_character_gaps() {
+        let p = encode("ET", cfg(12)).unwrap();
+        assert_eq!(
+            durations(&p),
+            vec![
+                (LedState::On, 100),
+                (LedState::Off, 300),
+                (LedState::On, 300),
+                (LedState::Off, 700),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_element_gaps() {
+        let p = encode("A", cfg(12)).unwrap();
+        assert_eq!(
+            durations(&p),
+            vec![
+                (LedState::On, 100),
+                (LedState::Off, 100),
+                (LedState::On, 300),
+                (LedState::Off, 700),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_word_gap() {
+        let p = encode("E E", cfg(12)).unwrap();
+        assert_eq!(p.segments()[1], Segment::off(700));
+        assert_eq!(p.segments()[3], Segment::off(700));
+    }
+
+    #[test]
+    fn test_encode_collapses_whitespace() {
+        let a = encode("  E \t  E  ", cfg(12)).unwrap();
+        let b = encode("E E", cfg(12)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_encode_lowercase() {
+        assert_eq!(
+            encode("sos", cfg(12)).unwrap(),
+            encode("SOS", cfg(12)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_node_id() {
+        let p = encode("NODE 42", cfg(15)).unwrap();
+        assert_eq!(p.len(), 2 * (2 + 3 + 3 + 1 + 5 + 5));
+        assert_eq!(p.segments()[0].state, LedState::On);
+    }
+
+    #[test]
+    fn test_encode_alternates_states() {
+        let p = encode("HELLO WORLD", cfg(12)).unwrap();
+        for pair in p.segments().windows(2) {
+            assert_ne!(pair[0].state, pair[1].state);
+        }
+    }
+
+    #[test]
+    fn test_encode_unsupported_char() {
+        assert_eq!(
+            encode("AB#C", cfg(12)),
+            Err(MorseError::UnsupportedChar('#'))
+        );
+    }
+
+    #[test]
+    fn test_encode_empty() {
+        assert_eq!(encode("", cfg(12)), Err(MorseError::Empty));
+        assert_eq!(encode("   ", cfg(12)), Err(MorseError::Empty));
+    }
+
+    #[test]
+    fn test_encode_too_long() {
+        let text = "0".repeat(MAX_SEGMENTS / 10 + 1);
+        assert_eq!(encode(&text, cfg(12)), Err(MorseError::TooLong));
+    }
+
+    #[test]
+    fn test_encode_invalid_speed() {
+        assert_eq!(encode("E", cfg(0)), Err(MorseError::InvalidSpeed));
+    }
+
+    #[test]
+    fn test_encode_plays_on_controller() {
+        let mut ctrl = crate::led::LedController::with_pattern(encode("SOS", cfg(12)).unwrap());
+        assert_eq!(ctrl.toggle(), LedState::On);
+        assert_eq!(ctrl.delay_ms(), 100);
+    }
+}
@@ -0,0 +1,175 @@
+/*
+ * @file panic_blink.rs
+ * @brief Blink-code panic handler
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: panic_blink.rs
+//!
+//! DESCRIPTION:
+//! Blink-Code Panic Handler for RP2350.
+//!
+//! BRIEF:
+//! Replaces panic-halt when the `panic-blink` feature is on.
+//! Saves the panic location to RAM that survives a soft reset,
+//! then takes over the main LED and flashes the fault pattern
+//! forever, so a crash is visible without a debug probe.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::arbiter::{Priority, Request};
+use crate::command::LedCommand;
+use crate::config::PANIC_BLINK_CODE;
+use crate::fault::{PanicRecord, fault_pattern};
+use crate::led::{LedState, Polarity};
+use crate::pattern::EndAction;
+use core::mem::MaybeUninit;
+use core::panic::PanicInfo;
+use core::ptr::{addr_of, addr_of_mut};
+use core::sync::atomic::{AtomicBool, Ordering};
+use embassy_rp::gpio::{Level, Output};
+
+/// Core clock while the handler busy-waits.
+///
+/// # Value
+/// 150 MHz (RP2350 default)
+const CORE_CLOCK_HZ: u32 = 150_000_000;
+
+/// Whether the main LED is wired active-low.
+///
+/// # Details
+/// Copied from the saved settings at boot, so the handler
+/// drives the LED the same way the PWM output does.
+static ACTIVE_LOW: AtomicBool = AtomicBool::new(false);
+
+/// Last panic, in RAM the runtime does not initialize.
+///
+/// # Details
+/// Kept across soft resets and debugger resets; garbage after
+/// power-on, which `PanicRecord::is_valid` rejects.
+#[unsafe(link_section = ".uninit.PANIC_RECORD")]
+static mut PANIC_RECORD: MaybeUninit<PanicRecord> = MaybeUninit::uninit();
+
+/// Returns the record left by the last panic.
+///
+/// # Returns
+/// * `Option<PanicRecord>` - Record, None after power-on
+pub fn last_panic() -> Option<PanicRecord> {
+    // SAFETY: every bit pattern is a valid PanicRecord (integers
+    // and bytes only), and the volatile read keeps the compiler
+    // from assuming the uninitialized contents.
+    let record = unsafe { addr_of!(PANIC_RECORD).cast::<PanicRecord>().read_volatile() };
+    record.is_valid().then_some(record)
+}
+
+/// Records the LED polarity the handler should use.
+///
+/// # Arguments
+/// * `polarity` - Polarity loaded from the settings store
+pub fn set_polarity(polarity: Polarity) {
+    ACTIVE_LOW.store(polarity == Polarity::ActiveLow, Ordering::Relaxed);
+}
+
+/// Builds the boot-time report of an earlier panic.
+///
+/// # Details
+/// After a soft reset that followed a panic, the LED task shows
+/// the fault pattern three times at critical priority before
+/// normal playback resumes. The record is marked reported and
+/// written back, so later soft resets, including the shell
+/// `reset`, do not replay it.
+///
+/// # Returns
+/// * `Option<LedCommand>` - Report command, None if no unreported panic
+pub fn boot_report() -> Option<LedCommand> {
+    let mut record = last_panic()?;
+    if !record.take_report() {
+        return None;
+    }
+    // SAFETY: called once at boot before any task is spawned, and
+    // the panic handler is the only other writer.
+    unsafe {
+        addr_of_mut!(PANIC_RECORD)
+            .cast::<PanicRecord>()
+            .write_volatile(record)
+    };
+    let pattern = fault_pattern(PANIC_BLINK_CODE)
+        .with_repeat(3)
+        .with_end_action(EndAction::Stop);
+    Some(LedCommand::Indicate(Request::new(
+        Priority::Critical,
+        pattern,
+    )))
+}
+
+/// Panic handler.
+///
+/// # Details
+/// Masks interrupts so no task runs again, records the
+/// location, reclaims GPIO 16 as a plain output and plays the
+/// fault pattern with the saved polarity, busy-waiting one
+/// millisecond at a time.
+///
+/// # Arguments
+/// * `info` - Panic information
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    cortex_m::interrupt::disable();
+    let count = last_panic().map_or(1, |record| record.count().saturating_add(1));
+    let record = match info.location() {
+        Some(location) => {
+            PanicRecord::new(location.file(), location.line(), location.column(), count)
+        }
+        None => PanicRecord::new("", 0, 0, count),
+    };
+    // SAFETY: interrupts are masked and nothing else touches the record.
+    unsafe {
+        addr_of_mut!(PANIC_RECORD)
+            .cast::<PanicRecord>()
+            .write_volatile(record)
+    };
+    // SAFETY: the application never runs again, so taking the
+    // LED pin from whatever owned it cannot race.
+    let p = unsafe { embassy_rp::Peripherals::steal() };
+    let mut led = Output::new(p.PIN_16, Level::Low);
+    let pattern = fault_pattern(PANIC_BLINK_CODE);
+    let polarity = if ACTIVE_LOW.load(Ordering::Relaxed) {
+        Polarity::ActiveLow
+    } else {
+        Polarity::ActiveHigh
+    };
+    loop {
+        for segment in pattern.segments() {
+            let on = polarity.apply(segment.state) == LedState::On;
+            led.set_level(Level::from(on));
+            for _ in 0..segment.duration_ms {
+                cortex_m::asm::delay(CORE_CLOCK_HZ / 1000);
+            }
+        }
+    }
+}
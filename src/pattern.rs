@@ -0,0 +1,698 @@
+/*
+ * @file pattern.rs
+ * @brief Timed LED pattern sequences
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: pattern.rs
+//!
+//! DESCRIPTION:
+//! LED Pattern Sequences for RP2350.
+//!
+//! BRIEF:
+//! Provides timed on/off segments, repeat counts and end actions.
+//! Includes heartbeat, double-blink and SOS presets.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::LedState;
+
+/// Maximum number of segments a pattern can hold.
+///
+/// # Details
+/// Patterns use fixed storage so they work without an allocator.
+///
+/// # Value
+/// 64 segments
+#[allow(dead_code)]
+pub const MAX_SEGMENTS: usize = 64;
+
+/// Longest duration a single segment may have.
+///
+/// # Details
+/// Keeps deadlines computed from segment lengths far from
+/// overflowing the timer, whatever produced the pattern.
+///
+/// # Value
+/// 4294967295 milliseconds (about 49.7 days)
+#[allow(dead_code)]
+pub const MAX_SEGMENT_MS: u64 = u32::MAX as u64;
+
+/// Single timed step of a pattern.
+///
+/// # Details
+/// Holds the LED at `state` for `duration_ms` milliseconds.
+///
+/// # Fields
+/// * `state` - LED state during the segment
+/// * `duration_ms` - Segment length in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Segment {
+    pub state: LedState,
+    pub duration_ms: u64,
+}
+
+impl Segment {
+    /// Creates a segment with the LED on.
+    ///
+    /// # Arguments
+    /// * `duration_ms` - Segment length in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - On segment
+    #[allow(dead_code)]
+    pub const fn on(duration_ms: u64) -> Self {
+        Self {
+            state: LedState::On,
+            duration_ms,
+        }
+    }
+
+    /// Creates a segment with the LED off.
+    ///
+    /// # Arguments
+    /// * `duration_ms` - Segment length in milliseconds
+    ///
+    /// # Returns
+    /// * `Self` - Off segment
+    #[allow(dead_code)]
+    pub const fn off(duration_ms: u64) -> Self {
+        Self {
+            state: LedState::Off,
+            duration_ms,
+        }
+    }
+}
+
+/// Action taken once a pattern has played all repeats.
+///
+/// # Variants
+/// * `Loop` - Start over from the first segment forever
+/// * `Hold` - Keep the LED in the state of the last segment
+/// * `Stop` - Turn the LED off and stop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum EndAction {
+    Loop,
+    Hold,
+    Stop,
+}
+
+/// Pattern construction errors.
+///
+/// # Variants
+/// * `Empty` - Pattern has no segments
+/// * `TooManySegments` - More than `MAX_SEGMENTS` segments
+/// * `ZeroDuration` - A segment has a zero duration
+/// * `DurationTooLong` - A segment lasts longer than `MAX_SEGMENT_MS`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum PatternError {
+    Empty,
+    TooManySegments,
+    ZeroDuration,
+    DurationTooLong,
+}
+
+/// Fixed-capacity LED pattern.
+///
+/// # Details
+/// Sequence of timed segments played `repeat` times before
+/// the end action applies.
+///
+/// # Fields
+/// * `segments` - Segment storage
+/// * `len` - Number of used segments
+/// * `repeat` - Number of times the sequence is played
+/// * `end` - Action once all repeats have played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Pattern {
+    segments: [Segment; MAX_SEGMENTS],
+    len: usize,
+    repeat: u16,
+    end: EndAction,
+}
+
+impl Default for Pattern {
+    /// Returns an empty looping pattern.
+    ///
+    /// # Returns
+    /// * `Self` - Empty pattern
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pattern {
+    /// Creates an empty pattern.
+    ///
+    /// # Details
+    /// Plays once and loops by default.
+    ///
+    /// # Returns
+    /// * `Self` - Empty pattern
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            segments: [Segment::off(0); MAX_SEGMENTS],
+            len: 0,
+            repeat: 1,
+            end: EndAction::Loop,
+        }
+    }
+
+    /// Creates a pattern from a slice of segments.
+    ///
+    /// # Arguments
+    /// * `segments` - Segments in play order
+    ///
+    /// # Returns
+    /// * `Result<Self, PatternError>` - Pattern or validation error
+    #[allow(dead_code)]
+    pub fn from_segments(segments: &[Segment]) -> Result<Self, PatternError> {
+        if segments.is_empty() {
+            return Err(PatternError::Empty);
+        }
+        let mut pattern = Self::new();
+        for segment in segments {
+            pattern.push(*segment)?;
+        }
+        Ok(pattern)
+    }
+
+    /// Creates a symmetric on/off blink pattern.
+    ///
+    /// # Details
+    /// Panics if `delay_ms` is zero or above `MAX_SEGMENT_MS`; in a
+    /// `const` context that is a compile error.
+    ///
+    /// # Arguments
+    /// * `delay_ms` - Duration of both the on and off segment
+    ///
+    /// # Returns
+    /// * `Self` - Looping blink pattern
+    #[allow(dead_code)]
+    pub const fn blink(delay_ms: u64) -> Self {
+        Self::on_off(delay_ms, delay_ms)
+    }
+
+    /// Creates an asymmetric on/off blink pattern.
+    ///
+    /// # Details
+    /// Panics if either duration is zero or above `MAX_SEGMENT_MS`; in a
+    /// `const` context that is a compile error.
+    ///
+    /// # Arguments
+    /// * `on_ms` - Duration of the on segment
+    /// * `off_ms` - Duration of the off segment
+    ///
+    /// # Returns
+    /// * `Self` - Looping blink pattern
+    #[allow(dead_code)]
+    pub const fn on_off(on_ms: u64, off_ms: u64) -> Self {
+        Self::preset(&[Segment::on(on_ms), Segment::off(off_ms)])
+    }
+
+    /// Creates a heartbeat pattern.
+    ///
+    /// # Details
+    /// Two quick pulses followed by a long pause, one second total.
+    ///
+    /// # Returns
+    /// * `Self` - Looping heartbeat pattern
+    #[allow(dead_code)]
+    pub const fn heartbeat() -> Self {
+        Self::preset(&[
+            Segment::on(100),
+            Segment::off(100),
+            Segment::on(100),
+            Segment::off(700),
+        ])
+    }
+
+    /// Creates a double-blink pattern.
+    ///
+    /// # Details
+    /// Two short flashes every two seconds.
+    ///
+    /// # Returns
+    /// * `Self` - Looping double-blink pattern
+    #[allow(dead_code)]
+    pub const fn double_blink() -> Self {
+        Self::preset(&[
+            Segment::on(100),
+            Segment::off(200),
+            Segment::on(100),
+            Segment::off(1600),
+        ])
+    }
+
+    /// Creates an SOS pattern.
+    ///
+    /// # Details
+    /// Morse "SOS" with a 150 ms unit and a word gap before repeating.
+    ///
+    /// # Returns
+    /// * `Self` - Looping SOS pattern
+    #[allow(dead_code)]
+    pub const fn sos() -> Self {
+        Self::preset(&[
+            Segment::on(150),
+            Segment::off(150),
+            Segment::on(150),
+            Segment::off(150),
+            Segment::on(150),
+            Segment::off(450),
+            Segment::on(450),
+            Segment::off(150),
+            Segment::on(450),
+            Segment::off(150),
+            Segment::on(450),
+            Segment::off(450),
+            Segment::on(150),
+            Segment::off(150),
+            Segment::on(150),
+            Segment::off(150),
+            Segment::on(150),
+            Segment::off(1050),
+        ])
+    }
+
+    /// Builds a pattern from a trusted segment list.
+    ///
+    /// # Details
+    /// Applies the same duration bounds as `push`, but by assertion so the
+    /// constructors can stay `const`. A zero duration would otherwise stall
+    /// every scheduler that adds `delay_ms` to its next deadline.
+    ///
+    /// # Arguments
+    /// * `segments` - Segments, at most `MAX_SEGMENTS` long
+    ///
+    /// # Returns
+    /// * `Self` - Looping pattern
+    const fn preset(segments: &[Segment]) -> Self {
+        let mut pattern = Self::new();
+        let mut i = 0;
+        while i < segments.len() {
+            let duration_ms = segments[i].duration_ms;
+            assert!(
+                duration_ms > 0 && duration_ms <= MAX_SEGMENT_MS,
+                "segment duration out of range"
+            );
+            pattern.segments[i] = segments[i];
+            i += 1;
+        }
+        pattern.len = segments.len();
+        pattern
+    }
+
+    /// Appends a segment.
+    ///
+    /// # Arguments
+    /// * `segment` - Segment to append
+    ///
+    /// # Returns
+    /// * `Result<(), PatternError>` - Ok or validation error
+    #[allow(dead_code)]
+    pub fn push(&mut self, segment: Segment) -> Result<(), PatternError> {
+        if segment.duration_ms == 0 {
+            return Err(PatternError::ZeroDuration);
+        }
+        if segment.duration_ms > MAX_SEGMENT_MS {
+            return Err(PatternError::DurationTooLong);
+        }
+        if self.len == MAX_SEGMENTS {
+            return Err(PatternError::TooManySegments);
+        }
+        self.segments[self.len] = segment;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Sets how many times the sequence is played.
+    ///
+    /// # Details
+    /// A count of zero is treated as one.
+    ///
+    /// # Arguments
+    /// * `count` - Number of plays before the end action
+    ///
+    /// # Returns
+    /// * `Self` - Updated pattern
+    #[allow(dead_code)]
+    pub const fn with_repeat(mut self, count: u16) -> Self {
+        self.repeat = if count == 0 { 1 } else { count };
+        self
+    }
+
+    /// Sets the action taken after the last repeat.
+    ///
+    /// # Arguments
+    /// * `end` - End action
+    ///
+    /// # Returns
+    /// * `Self` - Updated pattern
+    #[allow(dead_code)]
+    pub const fn with_end_action(mut self, end: EndAction) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Returns the used segments.
+    ///
+    /// # Returns
+    /// * `&[Segment]` - Segments in play order
+    #[allow(dead_code)]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments[..self.len]
+    }
+
+    /// Returns the number of segments.
+    ///
+    /// # Returns
+    /// * `usize` - Segment count
+    #[allow(dead_code)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the pattern has no segments.
+    ///
+    /// # Returns
+    /// * `bool` - true when empty
+    #[allow(dead_code)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the repeat count.
+    ///
+    /// # Returns
+    /// * `u16` - Number of plays before the end action
+    #[allow(dead_code)]
+    pub const fn repeat(&self) -> u16 {
+        self.repeat
+    }
+
+    /// Returns the end action.
+    ///
+    /// # Returns
+    /// * `EndAction` - Action after the last repeat
+    #[allow(dead_code)]
+    pub const fn end_action(&self) -> EndAction {
+        self.end
+    }
+
+    /// Returns the duration of one play of the sequence.
+    ///
+    /// # Returns
+    /// * `u64` - Sum of segment durations in milliseconds
+    #[allow(dead_code)]
+    pub fn period_ms(&self) -> u64 {
+        self.segments()
+            .iter()
+            .fold(0u64, |acc, s| acc.saturating_add(s.duration_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Segment Tests ====================
+
+    #[test]
+    fn test_segment_on() {
+        let seg = Segment::on(100);
+        assert_eq!(seg.state, LedState::On);
+        assert_eq!(seg.duration_ms, 100);
+    }
+
+    #[test]
+    fn test_segment_off() {
+        let seg = Segment::off(250);
+        assert_eq!(seg.state, LedState::Off);
+        assert_eq!(seg.duration_ms, 250);
+    }
+
+    #[test]
+    fn test_segment_equality() {
+        assert_eq!(Segment::on(10), Segment::on(10));
+        assert_ne!(Segment::on(10), Segment::off(10));
+        assert_ne!(Segment::on(10), Segment::on(20));
+    }
+
+    // ==================== Pattern::new() Tests ====================
+
+    #[test]
+    fn test_new_is_empty() {
+        let p = Pattern::new();
+        assert!(p.is_empty());
+        assert_eq!(p.len(), 0);
+        assert!(p.segments().is_empty());
+    }
+
+    #[test]
+    fn test_new_defaults() {
+        let p = Pattern::new();
+        assert_eq!(p.repeat(), 1);
+        assert_eq!(p.end_action(), EndAction::Loop);
+    }
+
+    #[test]
+    fn test_default_equals_new() {
+        assert_eq!(Pattern::default(), Pattern::new());
+    }
+
+    // ==================== Pattern::from_segments() Tests ====================
+
+    #[test]
+    fn test_from_segments() {
+        let p = Pattern::from_segments(&[Segment::on(10), Segment::off(20)]).unwrap();
+        assert_eq!(p.segments(), &[Segment::on(10), Segment::off(20)]);
+    }
+
+    #[test]
+    fn test_from_segments_empty() {
+        assert_eq!(Pattern::from_segments(&[]), Err(PatternError::Empty));
+    }
+
+    #[test]
+    fn test_from_segments_zero_duration() {
+        let result = Pattern::from_segments(&[Segment::on(10), Segment::off(0)]);
+        assert_eq!(result, Err(PatternError::ZeroDuration));
+    }
+
+    #[test]
+    fn test_from_segments_at_capacity() {
+        let segs = [Segment::on(1); MAX_SEGMENTS];
+        let p = Pattern::from_segments(&segs).unwrap();
+        assert_eq!(p.len(), MAX_SEGMENTS);
+    }
+
+    #[test]
+    fn test_from_segments_over_capacity() {
+        let segs = [Segment::on(1); MAX_SEGMENTS + 1];
+        let result = Pattern::from_segments(&segs);
+        assert_eq!(result, Err(PatternError::TooManySegments));
+    }
+
+    // ==================== Pattern::push() Tests ====================
+
+    #[test]
+    fn test_push_appends() {
+        let mut p = Pattern::new();
+        p.push(Segment::on(5)).unwrap();
+        p.push(Segment::off(6)).unwrap();
+        assert_eq!(p.len(), 2);
+        assert_eq!(p.segments()[1], Segment::off(6));
+    }
+
+    #[test]
+    fn test_push_zero_duration_rejected() {
+        let mut p = Pattern::new();
+        assert_eq!(p.push(Segment::on(0)), Err(PatternError::ZeroDuration));
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn test_push_duration_at_limit_accepted() {
+        let mut p = Pattern::new();
+        p.push(Segment::off(MAX_SEGMENT_MS)).unwrap();
+        assert_eq!(p.segments(), &[Segment::off(MAX_SEGMENT_MS)]);
+    }
+
+    #[test]
+    fn test_push_duration_too_long_rejected() {
+        let mut p = Pattern::new();
+        assert_eq!(
+            p.push(Segment::on(MAX_SEGMENT_MS + 1)),
+            Err(PatternError::DurationTooLong)
+        );
+        assert_eq!(
+            Pattern::from_segments(&[Segment::on(1), Segment::off(u64::MAX)]),
+            Err(PatternError::DurationTooLong)
+        );
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn test_push_full_rejected() {
+        let mut p = Pattern::from_segments(&[Segment::on(1); MAX_SEGMENTS]).unwrap();
+        assert_eq!(p.push(Segment::on(1)), Err(PatternError::TooManySegments));
+        assert_eq!(p.len(), MAX_SEGMENTS);
+    }
+
+    // ==================== Builder Method Tests ====================
+
+    #[test]
+    fn test_with_repeat() {
+        let p = Pattern::heartbeat().with_repeat(3);
+        assert_eq!(p.repeat(), 3);
+    }
+
+    #[test]
+    fn test_with_repeat_zero_is_one() {
+        let p = Pattern::heartbeat().with_repeat(0);
+        assert_eq!(p.repeat(), 1);
+    }
+
+    #[test]
+    fn test_with_end_action() {
+        let p = Pattern::sos().with_end_action(EndAction::Stop);
+        assert_eq!(p.end_action(), EndAction::Stop);
+    }
+
+    #[test]
+    fn test_builders_preserve_segments() {
+        let p = Pattern::heartbeat()
+            .with_repeat(2)
+            .with_end_action(EndAction::Hold);
+        assert_eq!(p.segments(), Pattern::heartbeat().segments());
+    }
+
+    // ==================== Preset Tests ====================
+
+    #[test]
+    fn test_blink_preset() {
+        let p = Pattern::blink(500);
+        assert_eq!(p.segments(), &[Segment::on(500), Segment::off(500)]);
+        assert_eq!(p.end_action(), EndAction::Loop);
+    }
+
+    #[test]
+    fn test_on_off_preset() {
+        let p = Pattern::on_off(50, 1950);
+        assert_eq!(p.segments(), &[Segment::on(50), Segment::off(1950)]);
+        assert_eq!(p.period_ms(), 2000);
+    }
+
+    #[test]
+    fn test_heartbeat_preset() {
+        let p = Pattern::heartbeat();
+        assert_eq!(p.len(), 4);
+        assert_eq!(p.period_ms(), 1000);
+    }
+
+    #[test]
+    fn test_double_blink_preset() {
+        let p = Pattern::double_blink();
+        let on_count = p
+            .segments()
+            .iter()
+            .filter(|s| s.state == LedState::On)
+            .count();
+        assert_eq!(on_count, 2);
+        assert_eq!(p.period_ms(), 2000);
+    }
+
+    #[test]
+    fn test_sos_preset() {
+        let p = Pattern::sos();
+        assert_eq!(p.len(), 18);
+        let ons: Vec<u64> = p
+            .segments()
+            .iter()
+            .filter(|s| s.state == LedState::On)
+            .map(|s| s.duration_ms)
+            .collect();
+        assert_eq!(ons, vec![150, 150, 150, 450, 450, 450, 150, 150, 150]);
+    }
+
+    #[test]
+    fn test_presets_alternate_states() {
+        for p in [
+            Pattern::heartbeat(),
+            Pattern::double_blink(),
+            Pattern::sos(),
+        ] {
+            for pair in p.segments().windows(2) {
+                assert_ne!(pair[0].state, pair[1].state);
+            }
+        }
+    }
+
+    #[test]
+    fn test_presets_start_on() {
+        for p in [
+            Pattern::heartbeat(),
+            Pattern::double_blink(),
+            Pattern::sos(),
+        ] {
+            assert_eq!(p.segments()[0].state, LedState::On);
+        }
+    }
+
+    // ==================== Pattern::period_ms() Tests ====================
+
+    #[test]
+    fn test_period_empty() {
+        assert_eq!(Pattern::new().period_ms(), 0);
+    }
+
+    #[test]
+    fn test_period_at_segment_limit() {
+        let p = Pattern::on_off(MAX_SEGMENT_MS, MAX_SEGMENT_MS);
+        assert_eq!(p.period_ms(), 2 * MAX_SEGMENT_MS);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment duration out of range")]
+    fn test_on_off_rejects_zero() {
+        let _ = Pattern::on_off(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment duration out of range")]
+    fn test_blink_rejects_oversized() {
+        let _ = Pattern::blink(MAX_SEGMENT_MS + 1);
+    }
+}
@@ -0,0 +1,1495 @@
+/*
+ * @file protocol.rs
+ * @brief Framed binary control protocol
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: protocol.rs
+//!
+//! DESCRIPTION:
+//! RP2350 LED Control Protocol.
+//!
+//! BRIEF:
+//! Request/response messages for driving the LED firmware over
+//! any byte link, shared by the firmware and the host tools.
+//! Each message travels in one frame carrying a sequence number,
+//! so a host can resend a request after a timeout and match
+//! replies to requests. Frames are COBS-encoded and wrapped in
+//! zero bytes, so a receiver fed a byte at a time resynchronizes
+//! at the next frame after noise, a dropped byte or a reset. The
+//! device side replays its last reply to a resent request instead
+//! of running it twice.
+//!
+//! FRAME (before COBS, multi-byte fields little-endian):
+//!   0  sequence number     1 byte, echoed in the response
+//!   1  message type        1 byte
+//!   2  payload             0..MAX_PAYLOAD_LEN bytes
+//!   n  CRC-16              2 bytes over bytes 0..n
+//!
+//! MESSAGES (request -> response):
+//!   0x01 ping              -> 0x81 pong: protocol version
+//!   0x02 get config        -> 0x82 config: ON ms, OFF ms as u32
+//!   0x03 set config: ON ms, OFF ms as u32 -> 0x83 ack
+//!   0x04 upload pattern: codec.rs pattern -> 0x83 ack
+//!   0x05 get state         -> 0x84 state: 0 off, 1 on
+//!   0x06 get settings      -> 0x85 settings: settings.rs payload
+//!   any                    -> 0xFF error: error code
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::codec::{self, CodecError};
+use crate::config::BlinkConfig;
+use crate::crc::crc16;
+use crate::led::LedState;
+use crate::pattern::Pattern;
+use crate::settings::{self, Settings};
+
+/// Protocol version reported by `pong`.
+///
+/// # Value
+/// 1
+#[allow(dead_code)]
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Byte that ends every frame on the wire.
+///
+/// # Value
+/// 0x00
+#[allow(dead_code)]
+pub const FRAME_DELIMITER: u8 = 0x00;
+
+/// Size of the sequence number and message type.
+const FRAME_HEADER_LEN: usize = 2;
+
+/// Size of the CRC trailer.
+const FRAME_CRC_LEN: usize = 2;
+
+/// Largest message payload, a settings payload with a worst-case pattern.
+///
+/// # Value
+/// 725 bytes
+#[allow(dead_code)]
+pub const MAX_PAYLOAD_LEN: usize = settings::MAX_PAYLOAD_LEN;
+
+/// Largest frame before COBS encoding.
+///
+/// # Value
+/// 729 bytes
+#[allow(dead_code)]
+pub const MAX_RAW_LEN: usize = FRAME_HEADER_LEN + MAX_PAYLOAD_LEN + FRAME_CRC_LEN;
+
+/// Largest frame on the wire, including COBS overhead and delimiters.
+///
+/// # Details
+/// COBS adds one code byte per 254 data bytes, plus one.
+///
+/// # Value
+/// 734 bytes
+#[allow(dead_code)]
+pub const MAX_FRAME_LEN: usize = MAX_RAW_LEN + MAX_RAW_LEN / 254 + 3;
+
+/// Largest COBS body between delimiters.
+///
+/// # Value
+/// 732 bytes
+#[allow(dead_code)]
+pub const MAX_BODY_LEN: usize = MAX_FRAME_LEN - 2;
+
+const PING: u8 = 0x01;
+const GET_CONFIG: u8 = 0x02;
+const SET_CONFIG: u8 = 0x03;
+const UPLOAD_PATTERN: u8 = 0x04;
+const GET_STATE: u8 = 0x05;
+const GET_SETTINGS: u8 = 0x06;
+const PONG: u8 = 0x81;
+const CONFIG: u8 = 0x82;
+const ACK: u8 = 0x83;
+const STATE: u8 = 0x84;
+const SETTINGS: u8 = 0x85;
+const ERROR: u8 = 0xFF;
+
+/// Size of an encoded blink configuration.
+const CONFIG_LEN: usize = 8;
+
+/// Protocol errors.
+///
+/// # Variants
+/// * `BufferTooSmall` - Output buffer cannot hold the frame
+/// * `Cobs` - Frame is not valid COBS
+/// * `Truncated` - Frame shorter than header and CRC
+/// * `BadCrc` - Checksum mismatch
+/// * `UnknownType` - Message type not in the message set
+/// * `InvalidPayload` - Payload does not fit the message type
+/// * `FrameTooLong` - No delimiter within the decoder's buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ProtocolError {
+    BufferTooSmall,
+    Cobs,
+    Truncated,
+    BadCrc,
+    UnknownType(u8),
+    InvalidPayload,
+    FrameTooLong,
+}
+
+/// Error codes a device returns instead of a normal response.
+///
+/// # Variants
+/// * `BadRequest` - Request was malformed or not a request
+/// * `InvalidConfig` - Blink delays out of range
+/// * `InvalidPattern` - Uploaded pattern could not be decoded
+/// * `Busy` - Device cannot take the request now
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ErrorCode {
+    BadRequest = 1,
+    InvalidConfig = 2,
+    InvalidPattern = 3,
+    Busy = 4,
+}
+
+impl ErrorCode {
+    /// Parses a wire error code.
+    ///
+    /// # Arguments
+    /// * `code` - Code byte
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Error code, None if unknown
+    #[allow(dead_code)]
+    pub const fn from_u8(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::BadRequest),
+            2 => Some(Self::InvalidConfig),
+            3 => Some(Self::InvalidPattern),
+            4 => Some(Self::Busy),
+            _ => None,
+        }
+    }
+}
+
+/// Host-to-device messages.
+///
+/// # Variants
+/// * `Ping` - Check the link
+/// * `GetConfig` - Read the blink rate
+/// * `SetConfig` - Blink with these delays
+/// * `UploadPattern` - Play this pattern
+/// * `GetState` - Read the current LED state
+/// * `GetSettings` - Read the full settings
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code, clippy::large_enum_variant)]
+pub enum Request {
+    Ping,
+    GetConfig,
+    SetConfig(BlinkConfig),
+    UploadPattern(Pattern),
+    GetState,
+    GetSettings,
+}
+
+/// Device-to-host messages.
+///
+/// # Variants
+/// * `Pong` - Reply to ping with the protocol version
+/// * `Config` - Current blink rate
+/// * `Ack` - Request carried out
+/// * `State` - Current LED state
+/// * `Settings` - Current settings
+/// * `Error` - Request refused
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code, clippy::large_enum_variant)]
+pub enum Response {
+    Pong(u8),
+    Config(BlinkConfig),
+    Ack,
+    State(LedState),
+    Settings(Settings),
+    Error(ErrorCode),
+}
+
+/// Message that can travel in a frame.
+///
+/// # Details
+/// Implemented by `Request` and `Response`, so the framing code
+/// is shared by both directions.
+#[allow(dead_code)]
+pub trait Message: Sized {
+    /// Returns the message type byte.
+    ///
+    /// # Returns
+    /// * `u8` - Type code
+    fn kind(&self) -> u8;
+
+    /// Serializes the payload.
+    ///
+    /// # Arguments
+    /// * `out` - Payload buffer of `MAX_PAYLOAD_LEN` bytes
+    ///
+    /// # Returns
+    /// * `Result<usize, ProtocolError>` - Payload length or error
+    fn encode_payload(&self, out: &mut [u8]) -> Result<usize, ProtocolError>;
+
+    /// Parses a message.
+    ///
+    /// # Arguments
+    /// * `kind` - Type code
+    /// * `payload` - Payload bytes
+    ///
+    /// # Returns
+    /// * `Result<Self, ProtocolError>` - Message or error
+    fn decode(kind: u8, payload: &[u8]) -> Result<Self, ProtocolError>;
+}
+
+impl Message for Request {
+    fn kind(&self) -> u8 {
+        match self {
+            Self::Ping => PING,
+            Self::GetConfig => GET_CONFIG,
+            Self::SetConfig(_) => SET_CONFIG,
+            Self::UploadPattern(_) => UPLOAD_PATTERN,
+            Self::GetState => GET_STATE,
+            Self::GetSettings => GET_SETTINGS,
+        }
+    }
+
+    fn encode_payload(&self, out: &mut [u8]) -> Result<usize, ProtocolError> {
+        match self {
+            Self::SetConfig(config) => encode_config(config, out),
+            Self::UploadPattern(pattern) => codec::encode(pattern, out).map_err(|e| match e {
+                CodecError::BufferTooSmall => ProtocolError::BufferTooSmall,
+                _ => ProtocolError::InvalidPayload,
+            }),
+            _ => Ok(0),
+        }
+    }
+
+    fn decode(kind: u8, payload: &[u8]) -> Result<Self, ProtocolError> {
+        match kind {
+            PING => expect_empty(payload, Self::Ping),
+            GET_CONFIG => expect_empty(payload, Self::GetConfig),
+            SET_CONFIG => decode_config(payload).map(Self::SetConfig),
+            UPLOAD_PATTERN => codec::decode(payload)
+                .map(Self::UploadPattern)
+                .map_err(|_| ProtocolError::InvalidPayload),
+            GET_STATE => expect_empty(payload, Self::GetState),
+            GET_SETTINGS => expect_empty(payload, Self::GetSettings),
+            kind => Err(ProtocolError::UnknownType(kind)),
+        }
+    }
+}
+
+impl Message for Response {
+    fn kind(&self) -> u8 {
+        match self {
+            Self::Pong(_) => PONG,
+            Self::Config(_) => CONFIG,
+            Self::Ack => ACK,
+            Self::State(_) => STATE,
+            Self::Settings(_) => SETTINGS,
+            Self::Error(_) => ERROR,
+        }
+    }
+
+    fn encode_payload(&self, out: &mut [u8]) -> Result<usize, ProtocolError> {
+        match self {
+            Self::Pong(version) => encode_byte(*version, out),
+            Self::Config(config) => encode_config(config, out),
+            Self::Ack => Ok(0),
+            Self::State(state) => encode_byte(u8::from(*state == LedState::On), out),
+            Self::Settings(settings) => {
+                let out = out
+                    .get_mut(..MAX_PAYLOAD_LEN)
+                    .ok_or(ProtocolError::BufferTooSmall)?;
+                Ok(settings.encode(out))
+            }
+            Self::Error(code) => encode_byte(*code as u8, out),
+        }
+    }
+
+    fn decode(kind: u8, payload: &[u8]) -> Result<Self, ProtocolError> {
+        match (kind, payload) {
+            (PONG, &[version]) => Ok(Self::Pong(version)),
+            (CONFIG, _) => decode_config(payload).map(Self::Config),
+            (ACK, &[]) => Ok(Self::Ack),
+            (STATE, &[0]) => Ok(Self::State(LedState::Off)),
+            (STATE, &[1]) => Ok(Self::State(LedState::On)),
+            (SETTINGS, _) => Settings::decode(payload)
+                .map(Self::Settings)
+                .ok_or(ProtocolError::InvalidPayload),
+            (ERROR, &[code]) => ErrorCode::from_u8(code)
+                .map(Self::Error)
+                .ok_or(ProtocolError::InvalidPayload),
+            (PONG | ACK | STATE | ERROR, _) => Err(ProtocolError::InvalidPayload),
+            (kind, _) => Err(ProtocolError::UnknownType(kind)),
+        }
+    }
+}
+
+/// Sequence-numbered message.
+///
+/// # Fields
+/// * `seq` - Sequence number, echoed in the response
+/// * `message` - Request or response
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct Packet<M> {
+    pub seq: u8,
+    pub message: M,
+}
+
+/// Returns a message if the payload is empty.
+///
+/// # Arguments
+/// * `payload` - Payload bytes
+/// * `message` - Message to return
+///
+/// # Returns
+/// * `Result<M, ProtocolError>` - Message, or error for a non-empty payload
+fn expect_empty<M>(payload: &[u8], message: M) -> Result<M, ProtocolError> {
+    if payload.is_empty() {
+        Ok(message)
+    } else {
+        Err(ProtocolError::InvalidPayload)
+    }
+}
+
+/// Writes a one-byte payload.
+///
+/// # Arguments
+/// * `value` - Payload byte
+/// * `out` - Payload buffer
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Payload length or error
+fn encode_byte(value: u8, out: &mut [u8]) -> Result<usize, ProtocolError> {
+    *out.first_mut().ok_or(ProtocolError::BufferTooSmall)? = value;
+    Ok(1)
+}
+
+/// Writes a blink configuration payload.
+///
+/// # Arguments
+/// * `config` - Blink configuration
+/// * `out` - Payload buffer
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Payload length or error
+fn encode_config(config: &BlinkConfig, out: &mut [u8]) -> Result<usize, ProtocolError> {
+    let out = out
+        .get_mut(..CONFIG_LEN)
+        .ok_or(ProtocolError::BufferTooSmall)?;
+    out[..4].copy_from_slice(&(config.on_ms() as u32).to_le_bytes());
+    out[4..].copy_from_slice(&(config.off_ms() as u32).to_le_bytes());
+    Ok(CONFIG_LEN)
+}
+
+/// Parses a blink configuration payload.
+///
+/// # Arguments
+/// * `payload` - Payload bytes
+///
+/// # Returns
+/// * `Result<BlinkConfig, ProtocolError>` - Validated configuration or error
+fn decode_config(payload: &[u8]) -> Result<BlinkConfig, ProtocolError> {
+    let [a, b, c, d, e, f, g, h] = *payload else {
+        return Err(ProtocolError::InvalidPayload);
+    };
+    let on_ms = u32::from_le_bytes([a, b, c, d]);
+    let off_ms = u32::from_le_bytes([e, f, g, h]);
+    BlinkConfig::new(u64::from(on_ms), u64::from(off_ms)).map_err(|_| ProtocolError::InvalidPayload)
+}
+
+/// COBS-encodes a byte slice.
+///
+/// # Details
+/// The output contains no zero bytes and no delimiter.
+///
+/// # Arguments
+/// * `input` - Raw bytes
+/// * `out` - Buffer of at least `input.len() + input.len() / 254 + 1` bytes
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Encoded length or error
+#[allow(dead_code)]
+pub fn cobs_encode(input: &[u8], out: &mut [u8]) -> Result<usize, ProtocolError> {
+    if out.len() < input.len() + input.len() / 254 + 1 {
+        return Err(ProtocolError::BufferTooSmall);
+    }
+    let mut code_index = 0;
+    let mut write = 1;
+    let mut code = 1u8;
+    for &byte in input {
+        if byte != 0 {
+            out[write] = byte;
+            write += 1;
+            code += 1;
+        }
+        if byte == 0 || code == 0xFF {
+            out[code_index] = code;
+            code_index = write;
+            write += 1;
+            code = 1;
+        }
+    }
+    out[code_index] = code;
+    Ok(write)
+}
+
+/// Decodes a COBS-encoded byte slice.
+///
+/// # Arguments
+/// * `input` - Encoded bytes without the delimiter
+/// * `out` - Output buffer
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Decoded length or error
+#[allow(dead_code)]
+pub fn cobs_decode(input: &[u8], out: &mut [u8]) -> Result<usize, ProtocolError> {
+    let mut read = 0;
+    let mut write = 0;
+    while read < input.len() {
+        let code = usize::from(input[read]);
+        read += 1;
+        let end = read + code.checked_sub(1).ok_or(ProtocolError::Cobs)?;
+        let block = input.get(read..end).ok_or(ProtocolError::Cobs)?;
+        if block.contains(&0) {
+            return Err(ProtocolError::Cobs);
+        }
+        out.get_mut(write..write + block.len())
+            .ok_or(ProtocolError::BufferTooSmall)?
+            .copy_from_slice(block);
+        write += block.len();
+        read = end;
+        if code != 0xFF && read < input.len() {
+            *out.get_mut(write).ok_or(ProtocolError::BufferTooSmall)? = 0;
+            write += 1;
+        }
+    }
+    Ok(write)
+}
+
+/// Encodes a message as a complete wire frame.
+///
+/// # Details
+/// The frame starts with a delimiter as well as ending with one,
+/// which terminates any partial frame the receiver still holds.
+///
+/// # Arguments
+/// * `seq` - Sequence number
+/// * `message` - Message to send
+/// * `out` - Buffer of at least `MAX_FRAME_LEN` bytes
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Frame length including the delimiters, or error
+#[allow(dead_code)]
+pub fn encode_frame<M: Message>(
+    seq: u8,
+    message: &M,
+    out: &mut [u8],
+) -> Result<usize, ProtocolError> {
+    let mut raw = [0u8; MAX_RAW_LEN];
+    raw[0] = seq;
+    raw[1] = message.kind();
+    let payload_len =
+        message.encode_payload(&mut raw[FRAME_HEADER_LEN..MAX_RAW_LEN - FRAME_CRC_LEN])?;
+    let crc_at = FRAME_HEADER_LEN + payload_len;
+    let crc = crc16(&raw[..crc_at]);
+    raw[crc_at..crc_at + FRAME_CRC_LEN].copy_from_slice(&crc.to_le_bytes());
+    let (first, body) = out.split_first_mut().ok_or(ProtocolError::BufferTooSmall)?;
+    *first = FRAME_DELIMITER;
+    let len = cobs_encode(&raw[..crc_at + FRAME_CRC_LEN], body)?;
+    *body.get_mut(len).ok_or(ProtocolError::BufferTooSmall)? = FRAME_DELIMITER;
+    Ok(len + 2)
+}
+
+/// Removes COBS and checks the CRC of one frame.
+///
+/// # Arguments
+/// * `frame` - COBS bytes between delimiters
+/// * `raw` - Buffer of `MAX_RAW_LEN` bytes for the decoded frame
+///
+/// # Returns
+/// * `Result<usize, ProtocolError>` - Length of header and payload, or error
+fn unframe(frame: &[u8], raw: &mut [u8]) -> Result<usize, ProtocolError> {
+    let len = cobs_decode(frame, raw).map_err(|_| ProtocolError::Cobs)?;
+    if len < FRAME_HEADER_LEN + FRAME_CRC_LEN {
+        return Err(ProtocolError::Truncated);
+    }
+    let (body, crc) = raw[..len].split_at(len - FRAME_CRC_LEN);
+    if crc16(body).to_le_bytes() != crc {
+        return Err(ProtocolError::BadCrc);
+    }
+    Ok(body.len())
+}
+
+/// Decodes one frame.
+///
+/// # Arguments
+/// * `frame` - COBS bytes between delimiters
+///
+/// # Returns
+/// * `Result<Packet<M>, ProtocolError>` - Sequence-numbered message or error
+#[allow(dead_code)]
+pub fn decode_frame<M: Message>(frame: &[u8]) -> Result<Packet<M>, ProtocolError> {
+    let mut raw = [0u8; MAX_RAW_LEN];
+    let len = unframe(frame, &mut raw)?;
+    let message = M::decode(raw[1], &raw[FRAME_HEADER_LEN..len])?;
+    Ok(Packet {
+        seq: raw[0],
+        message,
+    })
+}
+
+/// Incremental frame splitter.
+///
+/// # Details
+/// Takes received bytes one at a time and hands out each frame
+/// body when its delimiter arrives, so partial frames survive
+/// across reads. Empty frames between back-to-back delimiters
+/// are skipped. A frame longer than `N` is reported once and
+/// dropped up to the next delimiter.
+///
+/// # Fields
+/// * `buf` - Bytes of the frame in progress
+/// * `len` - Number of bytes held
+/// * `overflow` - Frame in progress outgrew the buffer
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub struct FrameDecoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    overflow: bool,
+}
+
+impl<const N: usize> Default for FrameDecoder<N> {
+    /// Returns default FrameDecoder instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New empty FrameDecoder
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FrameDecoder<N> {
+    /// Creates an empty decoder.
+    ///
+    /// # Returns
+    /// * `Self` - New FrameDecoder instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Adds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<Result<&[u8], ProtocolError>>` - Frame body once complete, or None
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<Result<&[u8], ProtocolError>> {
+        if byte != FRAME_DELIMITER {
+            match self.buf.get_mut(self.len) {
+                Some(slot) => {
+                    *slot = byte;
+                    self.len += 1;
+                }
+                None => self.overflow = true,
+            }
+            return None;
+        }
+        let len = core::mem::take(&mut self.len);
+        if core::mem::take(&mut self.overflow) {
+            return Some(Err(ProtocolError::FrameTooLong));
+        }
+        if len == 0 {
+            return None;
+        }
+        Some(Ok(&self.buf[..len]))
+    }
+
+    /// Adds one received byte and decodes completed frames.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<Result<Packet<M>, ProtocolError>>` - Message once complete, or None
+    #[allow(dead_code)]
+    pub fn decode<M: Message>(&mut self, byte: u8) -> Option<Result<Packet<M>, ProtocolError>> {
+        self.push(byte).map(|frame| frame.and_then(decode_frame))
+    }
+
+    /// Discards the frame in progress.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.overflow = false;
+    }
+
+    /// Returns the number of bytes of the frame in progress.
+    ///
+    /// # Returns
+    /// * `usize` - Bytes held
+    #[allow(dead_code)]
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+}
+
+/// Result of a byte fed to a Server.
+///
+/// # Variants
+/// * `Request` - New request; answer it with `Server::respond`
+/// * `Reply` - Send `Server::reply` as is, for a resent or refused request
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code, clippy::large_enum_variant)]
+pub enum ServerEvent {
+    Request(Packet<Request>),
+    Reply,
+}
+
+/// Device end of the protocol.
+///
+/// # Details
+/// Splits incoming bytes into requests and remembers the last
+/// reply. A request whose sequence number and checksum match the
+/// previous one is a host retry after a lost reply; it gets the
+/// remembered reply again without being run twice. Requests with
+/// a valid checksum but a bad type or payload are refused with an
+/// error reply. Frames with a bad checksum are dropped, since
+/// their sequence number cannot be trusted; the host resends.
+///
+/// # Fields
+/// * `decoder` - Incoming frame splitter
+/// * `last` - Sequence number and CRC of the last answered request
+/// * `reply` - Encoded last reply
+/// * `reply_len` - Length of the encoded last reply
+/// * `dropped` - Number of frames dropped as corrupt
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub struct Server {
+    decoder: FrameDecoder<MAX_BODY_LEN>,
+    last: Option<(u8, [u8; FRAME_CRC_LEN])>,
+    reply: [u8; MAX_FRAME_LEN],
+    reply_len: usize,
+    dropped: u32,
+}
+
+impl Default for Server {
+    /// Returns default Server instance.
+    ///
+    /// # Details
+    /// Delegates to new() for initialization.
+    ///
+    /// # Returns
+    /// * `Self` - New Server with no reply remembered
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    /// Creates a server with no reply remembered.
+    ///
+    /// # Returns
+    /// * `Self` - New Server instance
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            decoder: FrameDecoder::new(),
+            last: None,
+            reply: [0; MAX_FRAME_LEN],
+            reply_len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Adds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<ServerEvent>` - Work for the caller, or None
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<ServerEvent> {
+        let mut raw = [0u8; MAX_RAW_LEN];
+        let frame = self.decoder.push(byte)?;
+        let Ok(len) = frame.and_then(|frame| unframe(frame, &mut raw)) else {
+            self.dropped = self.dropped.wrapping_add(1);
+            return None;
+        };
+        let seq = raw[0];
+        let crc = [raw[len], raw[len + 1]];
+        if self.last == Some((seq, crc)) && self.reply_len > 0 {
+            return Some(ServerEvent::Reply);
+        }
+        self.last = Some((seq, crc));
+        let message = match Request::decode(raw[1], &raw[FRAME_HEADER_LEN..len]) {
+            Ok(message) => message,
+            Err(_) => {
+                let code = match raw[1] {
+                    SET_CONFIG => ErrorCode::InvalidConfig,
+                    UPLOAD_PATTERN => ErrorCode::InvalidPattern,
+                    _ => ErrorCode::BadRequest,
+                };
+                self.respond(seq, &Response::Error(code));
+                return Some(ServerEvent::Reply);
+            }
+        };
+        self.reply_len = 0;
+        Some(ServerEvent::Request(Packet { seq, message }))
+    }
+
+    /// Encodes and remembers the reply to a request.
+    ///
+    /// # Arguments
+    /// * `seq` - Sequence number of the request
+    /// * `response` - Reply
+    ///
+    /// # Returns
+    /// * `&[u8]` - Frame to send
+    #[allow(dead_code)]
+    pub fn respond(&mut self, seq: u8, response: &Response) -> &[u8] {
+        self.reply_len = encode_frame(seq, response, &mut self.reply).unwrap_or(0);
+        self.reply()
+    }
+
+    /// Returns the last reply frame.
+    ///
+    /// # Returns
+    /// * `&[u8]` - Frame to send, empty before the first reply
+    #[allow(dead_code)]
+    pub fn reply(&self) -> &[u8] {
+        &self.reply[..self.reply_len]
+    }
+
+    /// Forgets the frame in progress and the last reply.
+    ///
+    /// # Details
+    /// Call when the host reconnects, so a new session starting
+    /// at the same sequence number is not taken for a retry.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.decoder.clear();
+        self.last = None;
+        self.reply_len = 0;
+    }
+
+    /// Returns the number of frames dropped as corrupt.
+    ///
+    /// # Returns
+    /// * `u32` - Dropped frame count
+    #[allow(dead_code)]
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::led::Polarity;
+    use crate::pattern::{MAX_SEGMENT_MS, MAX_SEGMENTS, Segment};
+
+    fn roundtrip<M: Message + core::fmt::Debug + PartialEq>(seq: u8, message: M) {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, &message, &mut frame).unwrap();
+        assert_eq!(frame[0], FRAME_DELIMITER);
+        assert_eq!(frame[len - 1], FRAME_DELIMITER);
+        assert!(!frame[1..len - 1].contains(&0));
+        let packet: Packet<M> = decode_frame(&frame[1..len - 1]).unwrap();
+        assert_eq!(packet, Packet { seq, message });
+    }
+
+    fn frame_of<M: Message>(seq: u8, message: &M) -> Vec<u8> {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, message, &mut frame).unwrap();
+        frame[1..len - 1].to_vec()
+    }
+
+    fn raw_frame(bytes: &[u8]) -> Vec<u8> {
+        let mut raw = bytes.to_vec();
+        raw.extend_from_slice(&crc16(bytes).to_le_bytes());
+        let mut out = [0u8; MAX_FRAME_LEN];
+        let len = cobs_encode(&raw, &mut out).unwrap();
+        out[..len].to_vec()
+    }
+
+    fn worst_case_pattern() -> Pattern {
+        let mut pattern = Pattern::new();
+        for i in 0..MAX_SEGMENTS {
+            let state = if i % 2 == 0 {
+                LedState::On
+            } else {
+                LedState::Off
+            };
+            let duration_ms = MAX_SEGMENT_MS - i as u64;
+            pattern.push(Segment { state, duration_ms }).unwrap();
+        }
+        pattern
+    }
+
+    // ==================== COBS Tests ====================
+
+    #[test]
+    fn test_cobs_known_vectors() {
+        let cases: [(&[u8], &[u8]); 5] = [
+            (&[], &[0x01]),
+            (&[0x00], &[0x01, 0x01]),
+            (&[0x00, 0x00], &[0x01, 0x01, 0x01]),
+            (&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x02, 0x33]),
+            (&[0x11, 0x22, 0x33, 0x44], &[0x05, 0x11, 0x22, 0x33, 0x44]),
+        ];
+        for (raw, encoded) in cases {
+            let mut out = [0u8; 16];
+            let len = cobs_encode(raw, &mut out).unwrap();
+            assert_eq!(&out[..len], encoded, "encode {raw:?}");
+            let len = cobs_decode(encoded, &mut out).unwrap();
+            assert_eq!(&out[..len], raw, "decode {encoded:?}");
+        }
+    }
+
+    #[test]
+    fn test_cobs_long_block() {
+        let raw: Vec<u8> = (1..=254).collect();
+        let mut out = [0u8; 300];
+        let len = cobs_encode(&raw, &mut out).unwrap();
+        assert_eq!(len, 256);
+        assert_eq!(out[0], 0xFF);
+        assert_eq!(out[255], 0x01);
+        let mut back = [0u8; 300];
+        let len = cobs_decode(&out[..len], &mut back).unwrap();
+        assert_eq!(&back[..len], &raw[..]);
+    }
+
+    #[test]
+    fn test_cobs_roundtrip_mixed() {
+        let raw: Vec<u8> = (0..1000).map(|i| (i * 7 % 5) as u8).collect();
+        let mut out = [0u8; 1100];
+        let len = cobs_encode(&raw, &mut out).unwrap();
+        assert!(!out[..len].contains(&0));
+        let mut back = [0u8; 1100];
+        let len = cobs_decode(&out[..len], &mut back).unwrap();
+        assert_eq!(&back[..len], &raw[..]);
+    }
+
+    #[test]
+    fn test_cobs_encode_buffer_too_small() {
+        let mut out = [0u8; 4];
+        assert_eq!(
+            cobs_encode(&[1, 2, 3, 4], &mut out),
+            Err(ProtocolError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_cobs_decode_rejects_invalid() {
+        let mut out = [0u8; 16];
+        assert_eq!(cobs_decode(&[0x00], &mut out), Err(ProtocolError::Cobs));
+        assert_eq!(
+            cobs_decode(&[0x05, 1, 2], &mut out),
+            Err(ProtocolError::Cobs)
+        );
+        assert_eq!(
+            cobs_decode(&[0x03, 1, 0], &mut out),
+            Err(ProtocolError::Cobs)
+        );
+    }
+
+    // ==================== Request Tests ====================
+
+    #[test]
+    fn test_request_roundtrip() {
+        roundtrip(0, Request::Ping);
+        roundtrip(1, Request::GetConfig);
+        roundtrip(2, Request::SetConfig(BlinkConfig::new(100, 900).unwrap()));
+        roundtrip(3, Request::UploadPattern(Pattern::sos()));
+        roundtrip(4, Request::GetState);
+        roundtrip(255, Request::GetSettings);
+    }
+
+    #[test]
+    fn test_request_worst_case_pattern_fits() {
+        roundtrip(7, Request::UploadPattern(worst_case_pattern()));
+    }
+
+    #[test]
+    fn test_request_rejects_response_type() {
+        let frame = frame_of(1, &Response::Ack);
+        assert_eq!(
+            decode_frame::<Request>(&frame),
+            Err(ProtocolError::UnknownType(ACK))
+        );
+    }
+
+    #[test]
+    fn test_request_rejects_payload_on_empty_message() {
+        let frame = raw_frame(&[1, PING, 0x42]);
+        assert_eq!(
+            decode_frame::<Request>(&frame),
+            Err(ProtocolError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_request_rejects_invalid_config() {
+        let mut bytes = vec![1, SET_CONFIG];
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            decode_frame::<Request>(&raw_frame(&bytes)),
+            Err(ProtocolError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_request_rejects_bad_pattern() {
+        let frame = raw_frame(&[1, UPLOAD_PATTERN, b'L', b'P']);
+        assert_eq!(
+            decode_frame::<Request>(&frame),
+            Err(ProtocolError::InvalidPayload)
+        );
+    }
+
+    // ==================== Response Tests ====================
+
+    #[test]
+    fn test_response_roundtrip() {
+        roundtrip(0, Response::Pong(PROTOCOL_VERSION));
+        roundtrip(1, Response::Config(BlinkConfig::default()));
+        roundtrip(2, Response::Ack);
+        roundtrip(3, Response::State(LedState::On));
+        roundtrip(4, Response::State(LedState::Off));
+        roundtrip(5, Response::Error(ErrorCode::Busy));
+        roundtrip(6, Response::Settings(Settings::default()));
+    }
+
+    #[test]
+    fn test_response_worst_case_settings_fit() {
+        let settings = Settings {
+            blink: BlinkConfig::new(10, 10_000).unwrap(),
+            pattern: Some(worst_case_pattern()),
+            polarity: Polarity::ActiveLow,
+        };
+        roundtrip(9, Response::Settings(settings));
+    }
+
+    #[test]
+    fn test_response_rejects_bad_payloads() {
+        for bytes in [
+            &[1, PONG][..],
+            &[1, ACK, 0],
+            &[1, STATE, 2],
+            &[1, ERROR, 99],
+            &[1, SETTINGS, 0xEE],
+        ] {
+            assert_eq!(
+                decode_frame::<Response>(&raw_frame(bytes)),
+                Err(ProtocolError::InvalidPayload),
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_code_from_u8() {
+        for code in [
+            ErrorCode::BadRequest,
+            ErrorCode::InvalidConfig,
+            ErrorCode::InvalidPattern,
+            ErrorCode::Busy,
+        ] {
+            assert_eq!(ErrorCode::from_u8(code as u8), Some(code));
+        }
+        assert_eq!(ErrorCode::from_u8(0), None);
+    }
+
+    // ==================== Frame Tests ====================
+
+    #[test]
+    fn test_frame_layout() {
+        let frame = frame_of(0x2A, &Request::GetState);
+        let mut raw = [0u8; 8];
+        let len = cobs_decode(&frame, &mut raw).unwrap();
+        let crc = crc16(&[0x2A, GET_STATE]).to_le_bytes();
+        assert_eq!(&raw[..len], &[0x2A, GET_STATE, crc[0], crc[1]]);
+    }
+
+    #[test]
+    fn test_frame_detects_corruption() {
+        let frame = frame_of(3, &Request::SetConfig(BlinkConfig::default()));
+        for i in 0..frame.len() {
+            let mut bad = frame.clone();
+            bad[i] ^= 0x10;
+            if bad[i] == 0 {
+                continue;
+            }
+            assert!(decode_frame::<Request>(&bad).is_err(), "byte {i}");
+        }
+    }
+
+    #[test]
+    fn test_frame_truncated() {
+        assert_eq!(decode_frame::<Request>(&[]), Err(ProtocolError::Truncated));
+        let frame = raw_frame(&[1]);
+        assert_eq!(
+            decode_frame::<Request>(&frame),
+            Err(ProtocolError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_frame_too_long() {
+        let frame = vec![0xFF; MAX_FRAME_LEN + 10];
+        assert_eq!(decode_frame::<Request>(&frame), Err(ProtocolError::Cobs));
+    }
+
+    #[test]
+    fn test_frame_encode_buffer_too_small() {
+        let mut out = [0u8; 4];
+        assert_eq!(
+            encode_frame(1, &Request::Ping, &mut out),
+            Err(ProtocolError::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_frame_encode_rejects_empty_pattern() {
+        let mut out = [0u8; MAX_FRAME_LEN];
+        assert_eq!(
+            encode_frame(1, &Request::UploadPattern(Pattern::new()), &mut out),
+            Err(ProtocolError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_max_frame_len() {
+        assert_eq!(MAX_RAW_LEN, 729);
+        assert_eq!(MAX_FRAME_LEN, 734);
+        assert_eq!(MAX_BODY_LEN, 732);
+    }
+
+    // ==================== FrameDecoder Tests ====================
+
+    fn wire<M: Message>(seq: u8, message: &M) -> Vec<u8> {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, message, &mut frame).unwrap();
+        frame[..len].to_vec()
+    }
+
+    fn decode_all<M: Message>(
+        decoder: &mut FrameDecoder<MAX_BODY_LEN>,
+        bytes: &[u8],
+    ) -> Vec<Result<Packet<M>, ProtocolError>> {
+        bytes.iter().filter_map(|&b| decoder.decode(b)).collect()
+    }
+
+    #[test]
+    fn test_decoder_whole_frame() {
+        let mut decoder = FrameDecoder::new();
+        let packets = decode_all(&mut decoder, &wire(5, &Request::Ping));
+        assert_eq!(
+            packets,
+            vec![Ok(Packet {
+                seq: 5,
+                message: Request::Ping
+            })]
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decoder_partial_frame_across_reads() {
+        let bytes = wire(9, &Request::UploadPattern(Pattern::heartbeat()));
+        let (head, tail) = bytes.split_at(bytes.len() / 2);
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        assert!(decode_all::<Request>(&mut decoder, head).is_empty());
+        assert!(decoder.pending() > 0);
+        let packets = decode_all::<Request>(&mut decoder, tail);
+        assert_eq!(
+            packets[0].unwrap().message,
+            Request::UploadPattern(Pattern::heartbeat())
+        );
+    }
+
+    #[test]
+    fn test_decoder_skips_empty_frames() {
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        for _ in 0..5 {
+            assert!(decoder.push(FRAME_DELIMITER).is_none());
+        }
+    }
+
+    #[test]
+    fn test_decoder_resyncs_after_truncated_frame() {
+        let first = wire(1, &Request::SetConfig(BlinkConfig::default()));
+        let mut bytes = first[..first.len() / 2].to_vec();
+        bytes.extend(wire(2, &Request::GetState));
+        let mut decoder = FrameDecoder::new();
+        let packets = decode_all::<Request>(&mut decoder, &bytes);
+        assert_eq!(packets.len(), 2);
+        assert!(packets[0].is_err());
+        assert_eq!(
+            packets[1],
+            Ok(Packet {
+                seq: 2,
+                message: Request::GetState
+            })
+        );
+    }
+
+    #[test]
+    fn test_decoder_overflow() {
+        let mut decoder = FrameDecoder::<8>::new();
+        let mut events: Vec<Result<Vec<u8>, ProtocolError>> = Vec::new();
+        for &b in [1u8; 20].iter().chain(&[0, 3, 7, 7, 0]) {
+            if let Some(r) = decoder.push(b) {
+                events.push(r.map(<[u8]>::to_vec));
+            }
+        }
+        assert_eq!(
+            events,
+            vec![Err(ProtocolError::FrameTooLong), Ok(vec![3, 7, 7])]
+        );
+    }
+
+    #[test]
+    fn test_decoder_clear() {
+        let mut decoder = FrameDecoder::<8>::new();
+        decoder.push(1);
+        decoder.push(2);
+        decoder.clear();
+        assert_eq!(decoder.pending(), 0);
+        assert!(decoder.push(FRAME_DELIMITER).is_none());
+    }
+
+    // ==================== Server Tests ====================
+
+    fn feed(server: &mut Server, bytes: &[u8]) -> Vec<ServerEvent> {
+        bytes.iter().filter_map(|&b| server.push(b)).collect()
+    }
+
+    fn reply_of(server: &Server) -> Packet<Response> {
+        let bytes = server.reply();
+        decode_frame(&bytes[1..bytes.len() - 1]).unwrap()
+    }
+
+    #[test]
+    fn test_server_request_and_respond() {
+        let mut server = Server::new();
+        let events = feed(&mut server, &wire(4, &Request::GetState));
+        assert_eq!(
+            events,
+            vec![ServerEvent::Request(Packet {
+                seq: 4,
+                message: Request::GetState
+            })]
+        );
+        let frame = server.respond(4, &Response::State(LedState::On)).to_vec();
+        assert_eq!(frame, wire(4, &Response::State(LedState::On)));
+    }
+
+    #[test]
+    fn test_server_replays_retry() {
+        let mut server = Server::new();
+        let request = wire(7, &Request::SetConfig(BlinkConfig::default()));
+        assert_eq!(feed(&mut server, &request).len(), 1);
+        server.respond(7, &Response::Ack);
+        assert_eq!(feed(&mut server, &request), vec![ServerEvent::Reply]);
+        assert_eq!(
+            reply_of(&server),
+            Packet {
+                seq: 7,
+                message: Response::Ack
+            }
+        );
+    }
+
+    #[test]
+    fn test_server_same_seq_new_request_runs() {
+        let mut server = Server::new();
+        feed(&mut server, &wire(1, &Request::GetConfig));
+        server.respond(1, &Response::Config(BlinkConfig::default()));
+        let events = feed(&mut server, &wire(1, &Request::GetState));
+        assert!(matches!(events[..], [ServerEvent::Request(_)]));
+    }
+
+    #[test]
+    fn test_server_unanswered_request_runs_again() {
+        let mut server = Server::new();
+        let request = wire(3, &Request::Ping);
+        feed(&mut server, &request);
+        let events = feed(&mut server, &request);
+        assert!(matches!(events[..], [ServerEvent::Request(_)]));
+    }
+
+    #[test]
+    fn test_server_reset_forgets_reply() {
+        let mut server = Server::new();
+        let request = wire(1, &Request::GetState);
+        feed(&mut server, &request);
+        server.respond(1, &Response::State(LedState::Off));
+        server.reset();
+        assert!(server.reply().is_empty());
+        let events = feed(&mut server, &request);
+        assert!(matches!(events[..], [ServerEvent::Request(_)]));
+    }
+
+    #[test]
+    fn test_server_refuses_bad_requests() {
+        let mut bad_config = vec![6, SET_CONFIG];
+        bad_config.extend_from_slice(&[1, 0, 0, 0, 1, 0, 0, 0]);
+        let cases = [
+            (raw_frame(&bad_config), 6, ErrorCode::InvalidConfig),
+            (
+                raw_frame(&[7, UPLOAD_PATTERN, 1]),
+                7,
+                ErrorCode::InvalidPattern,
+            ),
+            (raw_frame(&[8, 0x42]), 8, ErrorCode::BadRequest),
+            (raw_frame(&[9, ACK]), 9, ErrorCode::BadRequest),
+        ];
+        for (body, seq, code) in cases {
+            let mut server = Server::new();
+            let mut bytes = vec![FRAME_DELIMITER];
+            bytes.extend(body);
+            bytes.push(FRAME_DELIMITER);
+            assert_eq!(feed(&mut server, &bytes), vec![ServerEvent::Reply]);
+            assert_eq!(
+                reply_of(&server),
+                Packet {
+                    seq,
+                    message: Response::Error(code)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_server_drops_corrupt_frames() {
+        let mut server = Server::new();
+        let mut bytes = wire(1, &Request::Ping);
+        bytes[2] ^= 0x80;
+        assert!(feed(&mut server, &bytes).is_empty());
+        assert_eq!(server.dropped(), 1);
+        assert!(server.reply().is_empty());
+    }
+
+    // ==================== Fuzz Tests ====================
+
+    /// Deterministic xorshift generator for reproducible fuzzing.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn byte(&mut self) -> u8 {
+            self.next() as u8
+        }
+    }
+
+    fn random_request(rng: &mut Rng) -> Request {
+        match rng.below(6) {
+            0 => Request::Ping,
+            1 => Request::GetConfig,
+            2 => {
+                let on_ms = 10 + rng.below(9_991);
+                let off_ms = 10 + rng.below(9_991);
+                Request::SetConfig(BlinkConfig::new(on_ms, off_ms).unwrap())
+            }
+            3 => {
+                let mut pattern = Pattern::new();
+                for i in 0..1 + rng.below(MAX_SEGMENTS as u64) {
+                    let state = if i % 2 == 0 {
+                        LedState::On
+                    } else {
+                        LedState::Off
+                    };
+                    let bits = rng.below(32) + 1;
+                    let duration_ms = 1 + rng.below((1 << bits) - 1);
+                    pattern.push(Segment { state, duration_ms }).unwrap();
+                }
+                Request::UploadPattern(pattern)
+            }
+            4 => Request::GetState,
+            _ => Request::GetSettings,
+        }
+    }
+
+    #[test]
+    fn test_fuzz_random_chunking() {
+        let mut rng = Rng(0x1234_5678_9ABC_DEF0);
+        let requests: Vec<Request> = (0..200).map(|_| random_request(&mut rng)).collect();
+        let mut stream = Vec::new();
+        for (seq, request) in requests.iter().enumerate() {
+            stream.extend(wire(seq as u8, request));
+        }
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        let mut decoded = Vec::new();
+        let mut rest = &stream[..];
+        while !rest.is_empty() {
+            let take = (1 + rng.below(64) as usize).min(rest.len());
+            let (chunk, tail) = rest.split_at(take);
+            decoded.extend(decode_all::<Request>(&mut decoder, chunk));
+            rest = tail;
+        }
+        let expected: Vec<_> = requests
+            .iter()
+            .enumerate()
+            .map(|(seq, &message)| {
+                Ok(Packet {
+                    seq: seq as u8,
+                    message,
+                })
+            })
+            .collect();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_fuzz_noise_between_frames() {
+        let mut rng = Rng(0xDEAD_BEEF_0BAD_F00D);
+        let mut stream = Vec::new();
+        let mut expected = Vec::new();
+        for seq in 0..200u8 {
+            for _ in 0..rng.below(40) {
+                stream.push(rng.byte());
+            }
+            let request = random_request(&mut rng);
+            stream.extend(wire(seq, &request));
+            expected.push(Packet {
+                seq,
+                message: request,
+            });
+        }
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        let good: Vec<_> = decode_all::<Request>(&mut decoder, &stream)
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(good, expected);
+    }
+
+    #[test]
+    fn test_fuzz_bit_flips_never_decode_wrong() {
+        let mut rng = Rng(0x0F0F_1234_AAAA_5555);
+        for _ in 0..2000 {
+            let request = random_request(&mut rng);
+            let mut bytes = wire(rng.byte(), &request);
+            let at = 1 + rng.below(bytes.len() as u64 - 2) as usize;
+            bytes[at] ^= 1 << rng.below(8);
+            let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+            for packet in decode_all::<Request>(&mut decoder, &bytes) {
+                assert!(packet.is_err(), "flip at {at} decoded as {packet:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzz_dropped_bytes_resync() {
+        let mut rng = Rng(0x5555_AAAA_1234_0F0F);
+        let mut stream = Vec::new();
+        let mut expected = Vec::new();
+        for seq in 0..200u8 {
+            let request = random_request(&mut rng);
+            let mut bytes = wire(seq, &request);
+            if rng.below(4) == 0 {
+                let at = 1 + rng.below(bytes.len() as u64 - 2) as usize;
+                bytes.remove(at);
+            } else {
+                expected.push(Packet {
+                    seq,
+                    message: request,
+                });
+            }
+            stream.extend(bytes);
+        }
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        let good: Vec<_> = decode_all::<Request>(&mut decoder, &stream)
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(good, expected);
+    }
+
+    #[test]
+    fn test_fuzz_random_bytes_do_not_panic() {
+        let mut rng = Rng(0xC0DE_CAFE_F00D_0001);
+        let mut decoder = FrameDecoder::<MAX_BODY_LEN>::new();
+        let mut server = Server::new();
+        for _ in 0..200_000 {
+            let byte = if rng.below(16) == 0 { 0 } else { rng.byte() };
+            let _ = decoder.decode::<Response>(byte);
+            if let Some(ServerEvent::Request(packet)) = server.push(byte) {
+                server.respond(packet.seq, &Response::Ack);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzz_cobs_roundtrip() {
+        let mut rng = Rng(0x1111_2222_3333_4444);
+        for _ in 0..500 {
+            let len = rng.below(800) as usize;
+            let zeros = rng.below(4);
+            let raw: Vec<u8> = (0..len)
+                .map(|_| if rng.below(4) < zeros { 0 } else { rng.byte() })
+                .collect();
+            let mut encoded = vec![0u8; len + len / 254 + 1];
+            let n = cobs_encode(&raw, &mut encoded).unwrap();
+            assert!(!encoded[..n].contains(&0));
+            let mut decoded = vec![0u8; len];
+            let m = cobs_decode(&encoded[..n], &mut decoded).unwrap();
+            assert_eq!(&decoded[..m], &raw[..]);
+        }
+    }
+}
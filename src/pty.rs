@@ -0,0 +1,84 @@
+/*
+ * @file pty.rs
+ * @brief Pseudo-terminal helper for the host tools
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: pty.rs
+//!
+//! DESCRIPTION:
+//! Pseudo-Terminal Helper for the Host Tools.
+//!
+//! BRIEF:
+//! Opens a raw-mode pseudo-terminal, the stand-in for the board's
+//! USB serial port used by led-emulator and the ledctl tests.
+//! Requires the `std` feature.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use std::ffi::CStr;
+use std::fs::File;
+use std::io;
+use std::os::fd::FromRawFd;
+use std::string::String;
+
+/// Opens a pseudo-terminal in raw mode.
+///
+/// # Details
+/// Raw mode is set on the master side, so bytes pass unchanged
+/// even before the host configures its end.
+///
+/// # Returns
+/// * `io::Result<(File, String)>` - Master side and the terminal's path
+pub fn open_pty() -> io::Result<(File, String)> {
+    // SAFETY: the fd is checked before use and then owned by the
+    // returned File; termios is plain data; ptsname_r writes a
+    // NUL-terminated name into `name`.
+    unsafe {
+        let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = File::from_raw_fd(fd);
+        let mut tty: libc::termios = std::mem::zeroed();
+        let mut name = [0 as libc::c_char; 128];
+        if libc::grantpt(fd) != 0 || libc::unlockpt(fd) != 0 || libc::tcgetattr(fd, &mut tty) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        libc::cfmakeraw(&mut tty);
+        if libc::tcsetattr(fd, libc::TCSANOW, &tty) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let err = libc::ptsname_r(fd, name.as_mut_ptr(), name.len());
+        if err != 0 {
+            return Err(io::Error::from_raw_os_error(err));
+        }
+        let path = CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned();
+        Ok((master, path))
+    }
+}
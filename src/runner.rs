@@ -0,0 +1,977 @@
+/*
+ * @file runner.rs
+ * @brief Hardware-agnostic async blink runner
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: runner.rs
+//!
+//! DESCRIPTION:
+//! Hardware-Agnostic Blink Runner for RP2350.
+//!
+//! BRIEF:
+//! Drives an LedController against any embedded-hal output pin
+//! and async delay, so the same loop runs on the RP2350 and on
+//! host mocks.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::arbiter::Arbiter;
+use crate::bank::LedBank;
+use crate::brightness::brightness_to_duty;
+use crate::fade::Effect;
+use crate::led::{LedController, LedState, led_state_to_level};
+use crate::scheduler::{Clock, LatePolicy, Scheduler, SchedulerStats};
+use embedded_hal::digital::{OutputPin, PinState};
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_async::delay::DelayNs;
+
+/// Async blink runner.
+///
+/// # Details
+/// Owns an output pin and an async delay provider.
+/// Each step toggles the controller, drives the pin and waits
+/// for the controller's current delay.
+///
+/// # Fields
+/// * `pin` - LED output pin
+/// * `delay` - Async delay provider
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct BlinkRunner<P, D> {
+    pin: P,
+    delay: D,
+}
+
+impl<P, D> BlinkRunner<P, D>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    /// Creates a new runner.
+    ///
+    /// # Arguments
+    /// * `pin` - LED output pin
+    /// * `delay` - Async delay provider
+    ///
+    /// # Returns
+    /// * `Self` - New BlinkRunner instance
+    #[allow(dead_code)]
+    pub fn new(pin: P, delay: D) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Runs a single blink step.
+    ///
+    /// # Details
+    /// Toggles the controller, writes the new state to the pin
+    /// and waits for the controller's delay. No wait happens once
+    /// the controller has finished.
+    ///
+    /// # Arguments
+    /// * `controller` - Controller to advance
+    ///
+    /// # Returns
+    /// * `Result<LedState, P::Error>` - New LED state or pin error
+    #[allow(dead_code)]
+    pub async fn step(&mut self, controller: &mut LedController) -> Result<LedState, P::Error> {
+        let state = controller.toggle();
+        write_state(&mut self.pin, state)?;
+        if !controller.is_finished() {
+            delay_ms(&mut self.delay, controller.delay_ms()).await;
+        }
+        Ok(state)
+    }
+
+    /// Runs the controller until its pattern finishes.
+    ///
+    /// # Details
+    /// Looping patterns never finish, so this runs forever
+    /// unless the pin reports an error.
+    ///
+    /// # Arguments
+    /// * `controller` - Controller to play
+    ///
+    /// # Returns
+    /// * `Result<(), P::Error>` - Ok once finished, or pin error
+    #[allow(dead_code)]
+    pub async fn run(&mut self, controller: &mut LedController) -> Result<(), P::Error> {
+        while !controller.is_finished() {
+            self.step(controller).await?;
+        }
+        Ok(())
+    }
+
+    /// Drives the pin from an arbiter until it goes idle.
+    ///
+    /// # Details
+    /// Sleeps until the arbiter's next deadline, advances it to
+    /// that instant and writes the winning state. Time starts at
+    /// the arbiter's current time and moves forward by exactly the
+    /// delays waited.
+    ///
+    /// # Arguments
+    /// * `arbiter` - Arbiter holding the live requests
+    ///
+    /// # Returns
+    /// * `Result<(), P::Error>` - Ok once no requests remain, or pin error
+    #[allow(dead_code)]
+    pub async fn run_arbiter<const N: usize>(
+        &mut self,
+        arbiter: &mut Arbiter<N>,
+    ) -> Result<(), P::Error> {
+        let mut now_ms = arbiter.now_ms();
+        loop {
+            write_state(&mut self.pin, arbiter.advance(now_ms))?;
+            let Some(next_ms) = arbiter.next_deadline() else {
+                return Ok(());
+            };
+            delay_ms(&mut self.delay, next_ms.saturating_sub(now_ms)).await;
+            now_ms = next_ms;
+        }
+    }
+
+    /// Consumes the runner and returns its parts.
+    ///
+    /// # Returns
+    /// * `(P, D)` - Output pin and delay provider
+    #[allow(dead_code)]
+    pub fn release(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+/// Drift-free async blink runner.
+///
+/// # Details
+/// Like `BlinkRunner`, but waits for absolute deadlines on a
+/// `Clock`. Each deadline is the previous one plus the step's
+/// delay, so time spent writing the pin or waking up late never
+/// accumulates. Steps that are already over when the runner
+/// wakes are replayed or skipped according to the `LatePolicy`.
+///
+/// # Fields
+/// * `pin` - LED output pin
+/// * `clock` - Clock providing time and absolute waits
+/// * `scheduler` - Deadline and lateness tracking
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct ScheduledRunner<P, C> {
+    pin: P,
+    clock: C,
+    scheduler: Scheduler,
+}
+
+impl<P, C> ScheduledRunner<P, C>
+where
+    P: OutputPin,
+    C: Clock,
+{
+    /// Creates a new runner.
+    ///
+    /// # Arguments
+    /// * `pin` - LED output pin
+    /// * `clock` - Clock providing time and absolute waits
+    /// * `policy` - Handling of steps that are already over
+    ///
+    /// # Returns
+    /// * `Self` - New ScheduledRunner instance
+    #[allow(dead_code)]
+    pub fn new(pin: P, clock: C, policy: LatePolicy) -> Self {
+        let scheduler = Scheduler::new(clock.now_ms(), policy);
+        Self {
+            pin,
+            clock,
+            scheduler,
+        }
+    }
+
+    /// Runs the controller until its pattern finishes.
+    ///
+    /// # Details
+    /// The first step starts at the clock's current time.
+    /// Looping patterns never finish, so this runs forever
+    /// unless the pin reports an error.
+    ///
+    /// # Arguments
+    /// * `controller` - Controller to play
+    ///
+    /// # Returns
+    /// * `Result<(), P::Error>` - Ok once finished, or pin error
+    #[allow(dead_code)]
+    pub async fn run(&mut self, controller: &mut LedController) -> Result<(), P::Error> {
+        self.scheduler = Scheduler::new(self.clock.now_ms(), self.scheduler.policy());
+        loop {
+            let now_ms = self.clock.now_ms();
+            let mut state = controller.toggle();
+            while !controller.is_finished()
+                && !self.scheduler.advance(controller.delay_ms(), now_ms)
+            {
+                state = controller.toggle();
+            }
+            write_state(&mut self.pin, state)?;
+            if controller.is_finished() {
+                return Ok(());
+            }
+            self.clock.wait_until(self.scheduler.deadline_ms()).await;
+            self.scheduler.wake(self.clock.now_ms());
+        }
+    }
+
+    /// Returns lateness statistics of the current or last run.
+    ///
+    /// # Returns
+    /// * `SchedulerStats` - Statistics
+    #[allow(dead_code)]
+    pub fn stats(&self) -> SchedulerStats {
+        self.scheduler.stats()
+    }
+
+    /// Consumes the runner and returns its parts.
+    ///
+    /// # Returns
+    /// * `(P, C)` - Output pin and clock
+    #[allow(dead_code)]
+    pub fn release(self) -> (P, C) {
+        (self.pin, self.clock)
+    }
+}
+
+/// Async runner for a multi-channel LED bank.
+///
+/// # Details
+/// Owns one output pin per channel and a shared clock. Sleeps
+/// until the bank's next absolute deadline, advances every
+/// channel and rewrites the pins. The bank's time is the
+/// clock's time. When a wake-up is late, `Skip` advances the
+/// bank straight to the current time while `CatchUp` steps it
+/// through each missed deadline.
+///
+/// # Fields
+/// * `pins` - LED output pins in channel order
+/// * `clock` - Clock providing time and absolute waits
+/// * `scheduler` - Deadline and lateness tracking
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct BankRunner<P, C, const N: usize> {
+    pins: [P; N],
+    clock: C,
+    scheduler: Scheduler,
+}
+
+impl<P, C, const N: usize> BankRunner<P, C, N>
+where
+    P: OutputPin,
+    C: Clock,
+{
+    /// Creates a new bank runner that skips missed steps.
+    ///
+    /// # Arguments
+    /// * `pins` - LED output pins in channel order
+    /// * `clock` - Clock providing time and absolute waits
+    ///
+    /// # Returns
+    /// * `Self` - New BankRunner instance
+    #[allow(dead_code)]
+    pub fn new(pins: [P; N], clock: C) -> Self {
+        Self {
+            pins,
+            clock,
+            scheduler: Scheduler::new(0, LatePolicy::Skip),
+        }
+    }
+
+    /// Sets the late-step policy.
+    ///
+    /// # Arguments
+    /// * `policy` - Handling of steps that are already over
+    ///
+    /// # Returns
+    /// * `Self` - Updated runner
+    #[allow(dead_code)]
+    pub fn with_policy(mut self, policy: LatePolicy) -> Self {
+        self.scheduler = Scheduler::new(0, policy);
+        self
+    }
+
+    /// Runs the bank until every channel finishes.
+    ///
+    /// # Details
+    /// Starts from the bank's current time. Looping channels
+    /// keep this running forever unless a pin reports an error.
+    ///
+    /// # Arguments
+    /// * `bank` - Bank to play
+    ///
+    /// # Returns
+    /// * `Result<(), P::Error>` - Ok once finished, or pin error
+    #[allow(dead_code)]
+    pub async fn run(&mut self, bank: &mut LedBank<N>) -> Result<(), P::Error> {
+        let policy = self.scheduler.policy();
+        self.scheduler = Scheduler::new(bank.now_ms(), policy);
+        let mut now_ms = bank.now_ms();
+        loop {
+            bank.advance(now_ms);
+            for (pin, state) in self.pins.iter_mut().zip(bank.states()) {
+                write_state(pin, state)?;
+            }
+            let Some(next_ms) = bank.next_deadline() else {
+                return Ok(());
+            };
+            self.scheduler.advance_to(next_ms, now_ms);
+            self.clock.wait_until(next_ms).await;
+            let woke_ms = self.clock.now_ms();
+            self.scheduler.wake(woke_ms);
+            now_ms = match policy {
+                LatePolicy::Skip => woke_ms.max(next_ms),
+                LatePolicy::CatchUp => next_ms,
+            };
+        }
+    }
+
+    /// Returns lateness statistics of the current or last run.
+    ///
+    /// # Returns
+    /// * `SchedulerStats` - Statistics
+    #[allow(dead_code)]
+    pub fn stats(&self) -> SchedulerStats {
+        self.scheduler.stats()
+    }
+
+    /// Consumes the runner and returns its parts.
+    ///
+    /// # Returns
+    /// * `([P; N], C)` - Output pins and clock
+    #[allow(dead_code)]
+    pub fn release(self) -> ([P; N], C) {
+        (self.pins, self.clock)
+    }
+}
+
+/// Drives an output pin to match an LED state.
+///
+/// # Details
+/// Uses `led_state_to_level` for the state to level mapping.
+///
+/// # Arguments
+/// * `pin` - Output pin to drive
+/// * `state` - LED state to apply
+///
+/// # Returns
+/// * `Result<(), P::Error>` - Ok or pin error
+#[allow(dead_code)]
+pub fn write_state<P: OutputPin>(pin: &mut P, state: LedState) -> Result<(), P::Error> {
+    pin.set_state(PinState::from(led_state_to_level(state)))
+}
+
+/// Waits for a 64-bit millisecond delay.
+///
+/// # Details
+/// `DelayNs` takes 32-bit milliseconds, so long delays are
+/// split into chunks.
+///
+/// # Arguments
+/// * `delay` - Async delay provider
+/// * `ms` - Delay in milliseconds
+#[allow(dead_code)]
+pub async fn delay_ms<D: DelayNs>(delay: &mut D, mut ms: u64) {
+    while ms > 0 {
+        let chunk = ms.min(u64::from(u32::MAX));
+        delay.delay_ms(chunk as u32).await;
+        ms -= chunk;
+    }
+}
+
+/// Plays a brightness effect on a PWM channel.
+///
+/// # Details
+/// Samples the effect every `step_ms` and writes the matching
+/// duty cycle. Returns after writing the final sample of a
+/// finite effect; endless effects run until the PWM errors.
+///
+/// # Arguments
+/// * `pwm` - PWM channel driving the LED
+/// * `delay` - Async delay provider
+/// * `effect` - Effect to play
+/// * `step_ms` - Update interval, at least 1 ms
+///
+/// # Returns
+/// * `Result<(), P::Error>` - Ok once finished, or PWM error
+#[allow(dead_code)]
+pub async fn run_effect<P, D, E>(
+    pwm: &mut P,
+    delay: &mut D,
+    effect: &E,
+    step_ms: u64,
+) -> Result<(), P::Error>
+where
+    P: SetDutyCycle,
+    D: DelayNs,
+    E: Effect,
+{
+    let step_ms = step_ms.max(1);
+    let mut elapsed_ms = 0u64;
+    loop {
+        let duty = brightness_to_duty(effect.sample(elapsed_ms), pwm.max_duty_cycle());
+        pwm.set_duty_cycle(duty)?;
+        if effect.duration_ms().is_some_and(|d| elapsed_ms >= d) {
+            return Ok(());
+        }
+        delay_ms(delay, step_ms).await;
+        elapsed_ms = elapsed_ms.saturating_add(step_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbiter::{Priority, Request};
+    use crate::brightness::Brightness;
+    use crate::fade::{Breathing, Easing, Fade};
+    use crate::mock::{MockPin, VirtualClock, VirtualDelay, block_on};
+    use crate::pattern::{EndAction, Pattern, Segment};
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    #[derive(Default)]
+    struct TestPin {
+        levels: Vec<bool>,
+    }
+
+    impl ErrorType for TestPin {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for TestPin {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.levels.push(false);
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.levels.push(true);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct TestDelay {
+        waits_ns: Vec<u64>,
+    }
+
+    impl DelayNs for TestDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.waits_ns.push(u64::from(ns));
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.waits_ns.push(u64::from(ms) * 1_000_000);
+        }
+    }
+
+    /// Delay that oversleeps by 3 ms, like a busy executor.
+    struct LaggyDelay(VirtualDelay);
+
+    impl DelayNs for LaggyDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.0.delay_ns(ns).await;
+            self.0.delay_ms(3).await;
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.0.delay_ms(ms + 3).await;
+        }
+    }
+
+    struct FailingPin;
+
+    #[derive(Debug, PartialEq)]
+    struct PinFault;
+
+    impl embedded_hal::digital::Error for PinFault {
+        fn kind(&self) -> embedded_hal::digital::ErrorKind {
+            embedded_hal::digital::ErrorKind::Other
+        }
+    }
+
+    impl ErrorType for FailingPin {
+        type Error = PinFault;
+    }
+
+    impl OutputPin for FailingPin {
+        fn set_low(&mut self) -> Result<(), PinFault> {
+            Err(PinFault)
+        }
+
+        fn set_high(&mut self) -> Result<(), PinFault> {
+            Err(PinFault)
+        }
+    }
+
+    struct TestPwm {
+        duties: Vec<u16>,
+        limit: usize,
+    }
+
+    impl TestPwm {
+        fn with_limit(limit: usize) -> Self {
+            Self {
+                duties: Vec::new(),
+                limit,
+            }
+        }
+    }
+
+    impl embedded_hal::pwm::ErrorType for TestPwm {
+        type Error = embedded_hal::pwm::ErrorKind;
+    }
+
+    impl SetDutyCycle for TestPwm {
+        fn max_duty_cycle(&self) -> u16 {
+            1000
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            if self.duties.len() == self.limit {
+                return Err(embedded_hal::pwm::ErrorKind::Other);
+            }
+            self.duties.push(duty);
+            Ok(())
+        }
+    }
+
+    // ==================== write_state() Tests ====================
+
+    #[test]
+    fn test_write_state_on_sets_high() {
+        let mut pin = TestPin::default();
+        write_state(&mut pin, LedState::On).unwrap();
+        assert_eq!(pin.levels, vec![true]);
+    }
+
+    #[test]
+    fn test_write_state_off_sets_low() {
+        let mut pin = TestPin::default();
+        write_state(&mut pin, LedState::Off).unwrap();
+        assert_eq!(pin.levels, vec![false]);
+    }
+
+    #[test]
+    fn test_write_state_propagates_error() {
+        assert_eq!(write_state(&mut FailingPin, LedState::On), Err(PinFault));
+    }
+
+    // ==================== delay_ms() Tests ====================
+
+    #[test]
+    fn test_delay_ms_short() {
+        let mut delay = TestDelay::default();
+        block_on(delay_ms(&mut delay, 500));
+        assert_eq!(delay.waits_ns, vec![500_000_000]);
+    }
+
+    #[test]
+    fn test_delay_ms_zero_does_not_wait() {
+        let mut delay = TestDelay::default();
+        block_on(delay_ms(&mut delay, 0));
+        assert!(delay.waits_ns.is_empty());
+    }
+
+    #[test]
+    fn test_delay_ms_splits_long_delays() {
+        let mut delay = TestDelay::default();
+        block_on(delay_ms(&mut delay, u64::from(u32::MAX) + 5));
+        let total_ms: u64 = delay.waits_ns.iter().map(|ns| ns / 1_000_000).sum();
+        assert_eq!(delay.waits_ns.len(), 2);
+        assert_eq!(total_ms, u64::from(u32::MAX) + 5);
+    }
+
+    // ==================== BlinkRunner::step() Tests ====================
+
+    #[test]
+    fn test_step_drives_pin_and_waits() {
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::new();
+        let state = block_on(runner.step(&mut ctrl)).unwrap();
+        assert_eq!(state, LedState::On);
+        let (pin, delay) = runner.release();
+        assert_eq!(pin.levels, vec![true]);
+        assert_eq!(delay.waits_ns, vec![500_000_000]);
+    }
+
+    #[test]
+    fn test_steps_alternate_levels() {
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::new();
+        for _ in 0..4 {
+            block_on(runner.step(&mut ctrl)).unwrap();
+        }
+        let (pin, _) = runner.release();
+        assert_eq!(pin.levels, vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn test_step_uses_segment_delays() {
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::with_pattern(Pattern::heartbeat());
+        for _ in 0..4 {
+            block_on(runner.step(&mut ctrl)).unwrap();
+        }
+        let (_, delay) = runner.release();
+        let waits_ms: Vec<u64> = delay.waits_ns.iter().map(|ns| ns / 1_000_000).collect();
+        assert_eq!(waits_ms, vec![100, 100, 100, 700]);
+    }
+
+    #[test]
+    fn test_step_propagates_pin_error() {
+        let mut runner = BlinkRunner::new(FailingPin, TestDelay::default());
+        let mut ctrl = LedController::new();
+        assert_eq!(block_on(runner.step(&mut ctrl)), Err(PinFault));
+    }
+
+    // ==================== BlinkRunner::run() Tests ====================
+
+    #[test]
+    fn test_run_plays_stop_pattern_to_end() {
+        let pattern = Pattern::from_segments(&[Segment::on(10), Segment::off(20)])
+            .unwrap()
+            .with_repeat(2)
+            .with_end_action(EndAction::Stop);
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::with_pattern(pattern);
+        block_on(runner.run(&mut ctrl)).unwrap();
+        let (pin, delay) = runner.release();
+        assert_eq!(pin.levels, vec![true, false, true, false, false]);
+        assert_eq!(delay.waits_ns.len(), 4);
+        assert!(ctrl.is_finished());
+    }
+
+    #[test]
+    fn test_run_hold_leaves_last_level() {
+        let pattern = Pattern::from_segments(&[Segment::off(10), Segment::on(10)])
+            .unwrap()
+            .with_end_action(EndAction::Hold);
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::with_pattern(pattern);
+        block_on(runner.run(&mut ctrl)).unwrap();
+        let (pin, _) = runner.release();
+        assert_eq!(pin.levels.last(), Some(&true));
+    }
+
+    #[test]
+    fn test_run_finished_controller_returns_immediately() {
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        let mut ctrl = LedController::with_pattern(Pattern::new());
+        ctrl.toggle();
+        block_on(runner.run(&mut ctrl)).unwrap();
+        let (pin, delay) = runner.release();
+        assert!(pin.levels.is_empty());
+        assert!(delay.waits_ns.is_empty());
+    }
+
+    #[test]
+    fn test_run_propagates_pin_error() {
+        let mut runner = BlinkRunner::new(FailingPin, TestDelay::default());
+        let mut ctrl = LedController::new();
+        assert_eq!(block_on(runner.run(&mut ctrl)), Err(PinFault));
+    }
+
+    // ==================== BlinkRunner::run_arbiter() Tests ====================
+
+    #[test]
+    fn test_run_arbiter_preempts_and_resumes() {
+        let clock = VirtualClock::new();
+        let mut arbiter: Arbiter<4> = Arbiter::new();
+        let background = Pattern::on_off(100, 100)
+            .with_repeat(3)
+            .with_end_action(EndAction::Stop);
+        let alert = Pattern::on_off(20, 20)
+            .with_repeat(2)
+            .with_end_action(EndAction::Stop);
+        arbiter
+            .submit(Request::new(Priority::Background, background))
+            .unwrap();
+        arbiter.advance(150);
+        arbiter
+            .submit(Request::new(Priority::Critical, alert))
+            .unwrap();
+        clock.advance_ms(150);
+        let mut runner = BlinkRunner::new(clock.pin(), clock.delay());
+        block_on(runner.run_arbiter(&mut arbiter)).unwrap();
+        let (pin, _) = runner.release();
+        assert_eq!(
+            pin.transitions(),
+            &[
+                (150, true),
+                (170, false),
+                (190, true),
+                (210, false),
+                (230, true),
+                (300, false),
+                (400, true),
+                (500, false),
+            ]
+        );
+        assert!(arbiter.is_empty());
+    }
+
+    #[test]
+    fn test_run_arbiter_idle_returns_immediately() {
+        let mut arbiter: Arbiter<2> = Arbiter::new();
+        let mut runner = BlinkRunner::new(TestPin::default(), TestDelay::default());
+        block_on(runner.run_arbiter(&mut arbiter)).unwrap();
+        let (pin, delay) = runner.release();
+        assert_eq!(pin.levels, vec![false]);
+        assert!(delay.waits_ns.is_empty());
+    }
+
+    #[test]
+    fn test_run_arbiter_propagates_pin_error() {
+        let mut arbiter: Arbiter<2> = Arbiter::new();
+        arbiter
+            .submit(Request::new(Priority::Normal, Pattern::heartbeat()))
+            .unwrap();
+        let mut runner = BlinkRunner::new(FailingPin, TestDelay::default());
+        assert_eq!(block_on(runner.run_arbiter(&mut arbiter)), Err(PinFault));
+    }
+
+    // ==================== run_effect() Tests ====================
+
+    #[test]
+    fn test_run_effect_fade_up() {
+        let fade = Fade::new(Brightness::OFF, Brightness::FULL, 100, Easing::Linear);
+        let mut pwm = TestPwm::with_limit(usize::MAX);
+        let mut delay = TestDelay::default();
+        block_on(run_effect(&mut pwm, &mut delay, &fade, 10)).unwrap();
+        assert_eq!(pwm.duties.len(), 11);
+        assert_eq!(pwm.duties.first(), Some(&0));
+        assert_eq!(pwm.duties.last(), Some(&1000));
+        assert!(pwm.duties.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(delay.waits_ns, vec![10_000_000; 10]);
+    }
+
+    #[test]
+    fn test_run_effect_zero_step_uses_one_ms() {
+        let fade = Fade::new(Brightness::FULL, Brightness::OFF, 3, Easing::Sine);
+        let mut pwm = TestPwm::with_limit(usize::MAX);
+        let mut delay = TestDelay::default();
+        block_on(run_effect(&mut pwm, &mut delay, &fade, 0)).unwrap();
+        assert_eq!(pwm.duties.len(), 4);
+        assert_eq!(pwm.duties.last(), Some(&0));
+    }
+
+    #[test]
+    fn test_run_effect_breathing_until_error() {
+        let breathing = Breathing::new(Brightness::OFF, Brightness::FULL, 200, Easing::Linear);
+        let mut pwm = TestPwm::with_limit(41);
+        let mut delay = TestDelay::default();
+        let result = block_on(run_effect(&mut pwm, &mut delay, &breathing, 10));
+        assert_eq!(result, Err(embedded_hal::pwm::ErrorKind::Other));
+        assert_eq!(pwm.duties[0], 0);
+        assert_eq!(pwm.duties[10], 1000);
+        assert_eq!(pwm.duties[20], 0);
+        assert_eq!(pwm.duties[30], 1000);
+        assert_eq!(pwm.duties[40], 0);
+    }
+
+    #[test]
+    fn test_run_effect_timeline_on_virtual_clock() {
+        let clock = VirtualClock::new();
+        let mut delay = clock.delay();
+        let fade = Fade::new(Brightness::OFF, Brightness::FULL, 250, Easing::Quadratic);
+        let mut pwm = TestPwm::with_limit(usize::MAX);
+        block_on(run_effect(&mut pwm, &mut delay, &fade, 10)).unwrap();
+        assert_eq!(clock.now_ms(), 250);
+    }
+
+    // ==================== BankRunner Tests ====================
+
+    #[test]
+    fn test_bank_runner_shared_timebase() {
+        let clock = VirtualClock::new();
+        let pattern = Pattern::on_off(100, 100)
+            .with_repeat(2)
+            .with_end_action(EndAction::Stop);
+        let mut bank: LedBank<2> = LedBank::staggered(pattern, 50);
+        let mut runner = BankRunner::new([clock.pin(), clock.pin()], clock.timer());
+        block_on(runner.run(&mut bank)).unwrap();
+        let ([a, b], _) = runner.release();
+        assert_eq!(
+            a.transitions(),
+            &[(0, true), (100, false), (200, true), (300, false)]
+        );
+        assert_eq!(
+            b.transitions(),
+            &[
+                (0, false),
+                (50, true),
+                (150, false),
+                (250, true),
+                (350, false)
+            ]
+        );
+        assert_eq!(clock.now_ms(), 450);
+        assert!(bank.is_finished());
+    }
+
+    #[test]
+    fn test_bank_runner_empty_patterns_finish() {
+        let clock = VirtualClock::new();
+        let mut bank: LedBank<3> =
+            LedBank::from_controllers([LedController::with_pattern(Pattern::new()); 3]);
+        let mut runner = BankRunner::new([clock.pin(), clock.pin(), clock.pin()], clock.timer());
+        block_on(runner.run(&mut bank)).unwrap();
+        assert_eq!(clock.now_ms(), 0);
+        let (pins, _) = runner.release();
+        assert!(pins.iter().all(|p| p.level() == Some(false)));
+    }
+
+    #[test]
+    fn test_bank_runner_propagates_pin_error() {
+        let mut bank: LedBank<2> = LedBank::new();
+        let mut runner = BankRunner::new([FailingPin, FailingPin], VirtualClock::new().timer());
+        assert_eq!(block_on(runner.run(&mut bank)), Err(PinFault));
+    }
+
+    /// Runs one finite blink channel with a laggy timer.
+    fn bank_with_latency(policy: LatePolicy, latency_ms: u64) -> (MockPin, SchedulerStats) {
+        let clock = VirtualClock::new();
+        let pattern = Pattern::on_off(100, 100)
+            .with_repeat(5)
+            .with_end_action(EndAction::Stop);
+        let mut bank: LedBank<1> = LedBank::staggered(pattern, 0);
+        let timer = clock.timer().with_latency_ns(latency_ms * 1_000_000);
+        let mut runner = BankRunner::new([clock.pin()], timer).with_policy(policy);
+        block_on(runner.run(&mut bank)).unwrap();
+        let stats = runner.stats();
+        let ([pin], _) = runner.release();
+        (pin, stats)
+    }
+
+    #[test]
+    fn test_bank_runner_latency_does_not_drift() {
+        let (pin, stats) = bank_with_latency(LatePolicy::Skip, 3);
+        assert_eq!(pin.transitions().last(), Some(&(903, false)));
+        assert_eq!(stats.max_late_ms, 3);
+    }
+
+    #[test]
+    fn test_bank_runner_catch_up_replays_missed_steps() {
+        let (pin, _) = bank_with_latency(LatePolicy::CatchUp, 250);
+        assert_eq!(pin.transitions().len(), 10);
+    }
+
+    #[test]
+    fn test_bank_runner_skip_drops_missed_steps() {
+        let (pin, _) = bank_with_latency(LatePolicy::Skip, 250);
+        assert!(pin.transitions().len() < 10);
+        assert_eq!(pin.level(), Some(false));
+    }
+
+    // ==================== ScheduledRunner Tests ====================
+
+    /// Runs a finite blink with a laggy timer and returns the pin.
+    fn scheduled(policy: LatePolicy, latency_ms: u64) -> (MockPin, SchedulerStats) {
+        let clock = VirtualClock::new();
+        let timer = clock.timer().with_latency_ns(latency_ms * 1_000_000);
+        let mut ctrl = LedController::with_pattern(
+            Pattern::on_off(100, 100)
+                .with_repeat(50)
+                .with_end_action(EndAction::Stop),
+        );
+        let mut runner = ScheduledRunner::new(clock.pin(), timer, policy);
+        block_on(runner.run(&mut ctrl)).unwrap();
+        let stats = runner.stats();
+        (runner.release().0, stats)
+    }
+
+    #[test]
+    fn test_scheduled_on_time_matches_pattern() {
+        let (pin, stats) = scheduled(LatePolicy::Skip, 0);
+        assert_eq!(
+            &pin.transitions()[..3],
+            &[(0, true), (100, false), (200, true)]
+        );
+        assert_eq!(stats.late_wakes, 0);
+    }
+
+    #[test]
+    fn test_scheduled_latency_does_not_drift() {
+        let (pin, stats) = scheduled(LatePolicy::CatchUp, 3);
+        let last = *pin.transitions().last().unwrap();
+        assert_eq!(last, (9_900 + 3, false));
+        assert_eq!(stats.max_late_ms, 3);
+        assert_eq!(stats.skipped, 0);
+    }
+
+    #[test]
+    fn test_relative_delays_drift() {
+        let clock = VirtualClock::new();
+        let mut ctrl = LedController::with_pattern(
+            Pattern::on_off(100, 100)
+                .with_repeat(50)
+                .with_end_action(EndAction::Stop),
+        );
+        let mut runner = BlinkRunner::new(clock.pin(), LaggyDelay(clock.delay()));
+        block_on(runner.run(&mut ctrl)).unwrap();
+        let (pin, _) = runner.release();
+        assert_eq!(pin.transitions().last().unwrap().0, 9_900 + 99 * 3);
+    }
+
+    #[test]
+    fn test_scheduled_skip_drops_missed_steps() {
+        let (pin, stats) = scheduled(LatePolicy::Skip, 250);
+        assert!(stats.skipped > 0);
+        assert!(pin.transitions().len() < 100);
+        assert_eq!(pin.level(), Some(false));
+    }
+
+    #[test]
+    fn test_scheduled_catch_up_plays_every_step() {
+        let (pin, stats) = scheduled(LatePolicy::CatchUp, 250);
+        assert_eq!(stats.skipped, 0);
+        assert_eq!(pin.writes(), 101);
+    }
+
+    #[test]
+    fn test_scheduled_propagates_pin_error() {
+        let mut ctrl = LedController::new();
+        let mut runner =
+            ScheduledRunner::new(FailingPin, VirtualClock::new().timer(), LatePolicy::Skip);
+        assert_eq!(block_on(runner.run(&mut ctrl)), Err(PinFault));
+    }
+
+    #[test]
+    fn test_scheduled_empty_pattern_finishes() {
+        let clock = VirtualClock::new();
+        let mut ctrl = LedController::with_pattern(Pattern::new());
+        let mut runner = ScheduledRunner::new(clock.pin(), clock.timer(), LatePolicy::Skip);
+        block_on(runner.run(&mut ctrl)).unwrap();
+        assert_eq!(clock.now_ms(), 0);
+    }
+}
@@ -0,0 +1,343 @@
+/*
+ * @file scheduler.rs
+ * @brief Drift-free absolute-deadline scheduler
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: scheduler.rs
+//!
+//! DESCRIPTION:
+//! Absolute-Deadline Scheduler for RP2350 LED Timing.
+//!
+//! BRIEF:
+//! Keeps each step's deadline as an absolute time derived from
+//! the previous deadline rather than from when the runner woke,
+//! so GPIO and executor latency never accumulate. Reports how
+//! late each wake-up was and, by policy, either plays every
+//! missed step back to back or skips steps that are already over.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use core::future::Future;
+
+/// Monotonic millisecond clock with absolute waits.
+///
+/// # Details
+/// Firmware implements this on `embassy_time::Timer::at`;
+/// host tests use a virtual clock.
+pub trait Clock {
+    /// Returns the current time.
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds since an arbitrary fixed start
+    fn now_ms(&self) -> u64;
+
+    /// Waits until a deadline.
+    ///
+    /// # Details
+    /// Returns at once if the deadline has passed.
+    ///
+    /// # Arguments
+    /// * `deadline_ms` - Absolute time to wake at
+    fn wait_until(&mut self, deadline_ms: u64) -> impl Future<Output = ()>;
+}
+
+/// What to do with steps whose time has fully passed.
+///
+/// # Variants
+/// * `CatchUp` - Play every step, back to back, until on time again
+/// * `Skip` - Drop steps that already ended and resume in phase
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum LatePolicy {
+    CatchUp,
+    #[default]
+    Skip,
+}
+
+/// Lateness statistics.
+///
+/// # Fields
+/// * `wakes` - Number of wake-ups recorded
+/// * `late_wakes` - Wake-ups after their deadline
+/// * `max_late_ms` - Worst lateness seen
+/// * `total_late_ms` - Sum of all lateness
+/// * `skipped` - Steps dropped by the `Skip` policy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct SchedulerStats {
+    pub wakes: u64,
+    pub late_wakes: u64,
+    pub max_late_ms: u64,
+    pub total_late_ms: u64,
+    pub skipped: u64,
+}
+
+/// Pure absolute-deadline scheduler.
+///
+/// # Details
+/// A step that starts at `deadline_ms` and lasts `interval_ms`
+/// ends at `deadline_ms + interval_ms`, which becomes the next
+/// deadline whatever time the runner actually woke at.
+///
+/// # Fields
+/// * `deadline_ms` - Start of the current step
+/// * `policy` - Handling of steps that are already over
+/// * `stats` - Lateness statistics
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Scheduler {
+    deadline_ms: u64,
+    policy: LatePolicy,
+    stats: SchedulerStats,
+}
+
+impl Scheduler {
+    /// Creates a scheduler whose first step starts at `start_ms`.
+    ///
+    /// # Arguments
+    /// * `start_ms` - Start of the first step
+    /// * `policy` - Handling of steps that are already over
+    ///
+    /// # Returns
+    /// * `Self` - New Scheduler instance
+    #[allow(dead_code)]
+    pub const fn new(start_ms: u64, policy: LatePolicy) -> Self {
+        Self {
+            deadline_ms: start_ms,
+            policy,
+            stats: SchedulerStats {
+                wakes: 0,
+                late_wakes: 0,
+                max_late_ms: 0,
+                total_late_ms: 0,
+                skipped: 0,
+            },
+        }
+    }
+
+    /// Moves to the next step.
+    ///
+    /// # Details
+    /// The current step ends `interval_ms` after its deadline,
+    /// and that end becomes the new deadline. Under `Skip`, a
+    /// step that ended at or before `now_ms` is counted as
+    /// skipped and should not be shown.
+    ///
+    /// # Arguments
+    /// * `interval_ms` - Length of the current step
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `bool` - True to show the step, false to skip it
+    #[allow(dead_code)]
+    pub fn advance(&mut self, interval_ms: u64, now_ms: u64) -> bool {
+        self.advance_to(self.deadline_ms.saturating_add(interval_ms), now_ms)
+    }
+
+    /// Moves to a step ending at an absolute time.
+    ///
+    /// # Details
+    /// For sources that compute their own deadlines, such as
+    /// `LedBank::next_deadline`. Skips like `advance`.
+    ///
+    /// # Arguments
+    /// * `deadline_ms` - End of the current step
+    /// * `now_ms` - Current time in milliseconds
+    ///
+    /// # Returns
+    /// * `bool` - True to show the step, false to skip it
+    #[allow(dead_code)]
+    pub fn advance_to(&mut self, deadline_ms: u64, now_ms: u64) -> bool {
+        self.deadline_ms = deadline_ms;
+        if self.policy == LatePolicy::Skip && deadline_ms <= now_ms {
+            self.stats.skipped += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Records a wake-up.
+    ///
+    /// # Arguments
+    /// * `now_ms` - Time the runner woke at
+    ///
+    /// # Returns
+    /// * `u64` - Milliseconds past the deadline, 0 if on time
+    #[allow(dead_code)]
+    pub fn wake(&mut self, now_ms: u64) -> u64 {
+        let late_ms = now_ms.saturating_sub(self.deadline_ms);
+        self.stats.wakes += 1;
+        if late_ms > 0 {
+            self.stats.late_wakes += 1;
+            self.stats.max_late_ms = self.stats.max_late_ms.max(late_ms);
+            self.stats.total_late_ms = self.stats.total_late_ms.saturating_add(late_ms);
+        }
+        late_ms
+    }
+
+    /// Returns the current deadline.
+    ///
+    /// # Returns
+    /// * `u64` - Absolute time to wake at
+    #[allow(dead_code)]
+    pub fn deadline_ms(&self) -> u64 {
+        self.deadline_ms
+    }
+
+    /// Returns the late-step policy.
+    ///
+    /// # Returns
+    /// * `LatePolicy` - Policy
+    #[allow(dead_code)]
+    pub fn policy(&self) -> LatePolicy {
+        self.policy
+    }
+
+    /// Returns the lateness statistics.
+    ///
+    /// # Returns
+    /// * `SchedulerStats` - Statistics so far
+    #[allow(dead_code)]
+    pub fn stats(&self) -> SchedulerStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Scheduler Tests ====================
+
+    #[test]
+    fn test_new_starts_at_start() {
+        let scheduler = Scheduler::new(100, LatePolicy::Skip);
+        assert_eq!(scheduler.deadline_ms(), 100);
+        assert_eq!(scheduler.stats(), SchedulerStats::default());
+    }
+
+    #[test]
+    fn test_default_policy_skips() {
+        assert_eq!(LatePolicy::default(), LatePolicy::Skip);
+    }
+
+    #[test]
+    fn test_advance_from_deadline_not_wake() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::CatchUp);
+        assert!(scheduler.advance(500, 0));
+        scheduler.wake(503);
+        assert!(scheduler.advance(500, 503));
+        assert_eq!(scheduler.deadline_ms(), 1000);
+    }
+
+    #[test]
+    fn test_no_drift_over_many_steps() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        let mut now = 0;
+        for _ in 0..10_000 {
+            scheduler.advance(250, now);
+            now = scheduler.deadline_ms() + 3;
+            scheduler.wake(now);
+        }
+        assert_eq!(scheduler.deadline_ms(), 2_500_000);
+    }
+
+    #[test]
+    fn test_wake_on_time() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        scheduler.advance(100, 0);
+        assert_eq!(scheduler.wake(100), 0);
+        assert_eq!(scheduler.stats().late_wakes, 0);
+        assert_eq!(scheduler.stats().wakes, 1);
+    }
+
+    #[test]
+    fn test_wake_early_is_not_late() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        scheduler.advance(100, 0);
+        assert_eq!(scheduler.wake(90), 0);
+    }
+
+    #[test]
+    fn test_wake_reports_lateness() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        scheduler.advance(100, 0);
+        assert_eq!(scheduler.wake(107), 7);
+        scheduler.advance(100, 107);
+        assert_eq!(scheduler.wake(202), 2);
+        let stats = scheduler.stats();
+        assert_eq!(stats.late_wakes, 2);
+        assert_eq!(stats.max_late_ms, 7);
+        assert_eq!(stats.total_late_ms, 9);
+    }
+
+    #[test]
+    fn test_skip_drops_finished_steps() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        scheduler.advance(100, 0);
+        scheduler.wake(350);
+        assert!(!scheduler.advance(100, 350));
+        assert!(!scheduler.advance(100, 350));
+        assert!(scheduler.advance(100, 350));
+        assert_eq!(scheduler.deadline_ms(), 400);
+        assert_eq!(scheduler.stats().skipped, 2);
+    }
+
+    #[test]
+    fn test_skip_step_ending_now() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        assert!(!scheduler.advance(100, 100));
+    }
+
+    #[test]
+    fn test_catch_up_keeps_every_step() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::CatchUp);
+        scheduler.advance(100, 0);
+        scheduler.wake(350);
+        assert!(scheduler.advance(100, 350));
+        assert_eq!(scheduler.deadline_ms(), 200);
+        assert_eq!(scheduler.wake(350), 150);
+        assert_eq!(scheduler.stats().skipped, 0);
+    }
+
+    #[test]
+    fn test_advance_to_absolute() {
+        let mut scheduler = Scheduler::new(0, LatePolicy::Skip);
+        assert!(scheduler.advance_to(750, 10));
+        assert_eq!(scheduler.deadline_ms(), 750);
+        assert!(!scheduler.advance_to(700, 800));
+    }
+
+    #[test]
+    fn test_advance_saturates() {
+        let mut scheduler = Scheduler::new(u64::MAX - 1, LatePolicy::CatchUp);
+        scheduler.advance(10, 0);
+        assert_eq!(scheduler.deadline_ms(), u64::MAX);
+    }
+}
@@ -0,0 +1,753 @@
+/*
+ * @file settings.rs
+ * @brief Persistent settings store in flash
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: settings.rs
+//!
+//! DESCRIPTION:
+//! Persistent Settings Store for RP2350 Flash.
+//!
+//! BRIEF:
+//! Keeps blink rate, pattern and pin polarity in a reserved flash
+//! region as append-only, CRC-checked records. Writes rotate
+//! through the region's sectors for wear leveling, and anything
+//! missing or corrupt falls back to the config.rs defaults.
+//!
+//! RECORD LAYOUT (multi-byte fields little-endian, padded with
+//! 0xFF to the flash write size):
+//!   0  magic "ST"          2 bytes
+//!   2  payload length      2 bytes
+//!   4  sequence number     4 bytes
+//!   8  CRC-32              4 bytes over bytes 2..8 and the payload
+//!  12  payload: version, flags (bit 0 active low, bit 1 pattern),
+//!      ON ms and OFF ms as u32, then an optional codec.rs pattern
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::codec::{self, MAX_ENCODED_LEN};
+use crate::config::BlinkConfig;
+use crate::crc::Crc32;
+use crate::led::Polarity;
+use crate::pattern::Pattern;
+use embedded_storage::nor_flash::NorFlash;
+
+/// Record magic bytes.
+///
+/// # Value
+/// "ST" (settings)
+#[allow(dead_code)]
+pub const RECORD_MAGIC: [u8; 2] = *b"ST";
+
+/// Current settings payload version.
+///
+/// # Value
+/// 1
+#[allow(dead_code)]
+pub const SETTINGS_VERSION: u8 = 1;
+
+/// Size of the record header.
+const HEADER_LEN: usize = 12;
+
+/// Size of the fixed part of the payload.
+const FIXED_PAYLOAD_LEN: usize = 10;
+
+/// Largest payload, with a worst-case pattern.
+///
+/// # Value
+/// 725 bytes
+#[allow(dead_code)]
+pub const MAX_PAYLOAD_LEN: usize = FIXED_PAYLOAD_LEN + MAX_ENCODED_LEN;
+
+/// Largest flash write size the store supports.
+const MAX_WRITE_SIZE: usize = 256;
+
+/// Record buffer size, room for the largest record and padding.
+const BUF_LEN: usize = HEADER_LEN + MAX_PAYLOAD_LEN + MAX_WRITE_SIZE;
+
+/// Flag bit for an active-low LED.
+const FLAG_ACTIVE_LOW: u8 = 0x01;
+
+/// Flag bit for a stored pattern.
+const FLAG_PATTERN: u8 = 0x02;
+
+/// Settings store errors.
+///
+/// # Variants
+/// * `Flash` - Underlying flash error
+/// * `InvalidRegion` - Region misaligned, too small or outside the flash
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum SettingsError<E> {
+    Flash(E),
+    InvalidRegion,
+}
+
+/// User settings.
+///
+/// # Fields
+/// * `blink` - Blink rate, used when no pattern is stored
+/// * `pattern` - Optional custom pattern
+/// * `polarity` - LED wiring polarity
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct Settings {
+    pub blink: BlinkConfig,
+    pub pattern: Option<Pattern>,
+    pub polarity: Polarity,
+}
+
+impl Default for Settings {
+    /// Returns the compile-time defaults from config.rs.
+    ///
+    /// # Returns
+    /// * `Self` - Default blink rate, no pattern, default polarity
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self {
+            blink: BlinkConfig::default(),
+            pattern: None,
+            polarity: Polarity::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the pattern to play at startup.
+    ///
+    /// # Returns
+    /// * `Pattern` - Stored pattern, or a blink at the stored rate
+    #[allow(dead_code)]
+    pub fn startup_pattern(&self) -> Pattern {
+        self.pattern
+            .unwrap_or(Pattern::on_off(self.blink.on_ms(), self.blink.off_ms()))
+    }
+
+    /// Serializes the settings payload.
+    ///
+    /// # Details
+    /// Also the wire format of settings in the control protocol.
+    /// An empty pattern cannot be encoded, so it is written as no
+    /// pattern rather than as a record that would fail to load.
+    ///
+    /// # Arguments
+    /// * `out` - Buffer of at least `MAX_PAYLOAD_LEN` bytes
+    ///
+    /// # Returns
+    /// * `usize` - Payload length
+    #[allow(dead_code)]
+    pub fn encode(&self, out: &mut [u8]) -> usize {
+        let pattern_len = self
+            .pattern
+            .as_ref()
+            .and_then(|pattern| codec::encode(pattern, &mut out[FIXED_PAYLOAD_LEN..]).ok());
+        let mut flags = 0;
+        if self.polarity == Polarity::ActiveLow {
+            flags |= FLAG_ACTIVE_LOW;
+        }
+        if pattern_len.is_some() {
+            flags |= FLAG_PATTERN;
+        }
+        out[0] = SETTINGS_VERSION;
+        out[1] = flags;
+        out[2..6].copy_from_slice(&(self.blink.on_ms() as u32).to_le_bytes());
+        out[6..10].copy_from_slice(&(self.blink.off_ms() as u32).to_le_bytes());
+        FIXED_PAYLOAD_LEN + pattern_len.unwrap_or(0)
+    }
+
+    /// Parses a settings payload.
+    ///
+    /// # Arguments
+    /// * `bytes` - Payload bytes
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Settings, None if the payload is invalid
+    #[allow(dead_code)]
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < FIXED_PAYLOAD_LEN || bytes[0] != SETTINGS_VERSION {
+            return None;
+        }
+        let flags = bytes[1];
+        let on_ms = u32::from_le_bytes(bytes[2..6].try_into().ok()?);
+        let off_ms = u32::from_le_bytes(bytes[6..10].try_into().ok()?);
+        let blink = BlinkConfig::new(u64::from(on_ms), u64::from(off_ms)).ok()?;
+        let rest = &bytes[FIXED_PAYLOAD_LEN..];
+        let pattern = if flags & FLAG_PATTERN != 0 {
+            Some(codec::decode(rest).ok()?)
+        } else if rest.is_empty() {
+            None
+        } else {
+            return None;
+        };
+        let polarity = if flags & FLAG_ACTIVE_LOW != 0 {
+            Polarity::ActiveLow
+        } else {
+            Polarity::ActiveHigh
+        };
+        Some(Self {
+            blink,
+            pattern,
+            polarity,
+        })
+    }
+}
+
+/// Append-only settings store on NOR flash.
+///
+/// # Details
+/// Each save appends a record with the next sequence number to
+/// the active sector. When a record no longer fits, the next
+/// sector is erased and becomes active, so erases rotate evenly
+/// through the region. The newest record with a valid CRC wins;
+/// the previous one stays intact until its sector is reused, so
+/// power loss mid-save leaves the last good settings readable.
+///
+/// # Fields
+/// * `flash` - Flash device
+/// * `base` - Region start offset
+/// * `size` - Region size in bytes
+/// * `active` - Index of the sector being written
+/// * `used` - Bytes used in the active sector
+/// * `seq` - Sequence number of the newest record
+/// * `current` - Settings from the newest valid record
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct SettingsStore<F> {
+    flash: F,
+    base: u32,
+    size: u32,
+    active: u32,
+    used: u32,
+    seq: u32,
+    current: Settings,
+}
+
+impl<F: NorFlash> SettingsStore<F> {
+    /// Opens the store and loads the newest settings.
+    ///
+    /// # Details
+    /// Scans every sector in the region. Corrupt records are
+    /// skipped; an empty or fully corrupt region yields the
+    /// defaults.
+    ///
+    /// # Arguments
+    /// * `flash` - Flash device
+    /// * `base` - Region start offset, sector aligned
+    /// * `size` - Region size, at least two sectors
+    ///
+    /// # Returns
+    /// * `Result<Self, SettingsError<F::Error>>` - Store or error
+    #[allow(dead_code)]
+    pub fn new(flash: F, base: u32, size: u32) -> Result<Self, SettingsError<F::Error>> {
+        let sector = F::ERASE_SIZE as u32;
+        let valid = base.is_multiple_of(sector)
+            && size.is_multiple_of(sector)
+            && size / sector >= 2
+            && F::WRITE_SIZE <= MAX_WRITE_SIZE
+            && (base as usize).saturating_add(size as usize) <= flash.capacity();
+        if !valid {
+            return Err(SettingsError::InvalidRegion);
+        }
+        let mut store = Self {
+            flash,
+            base,
+            size,
+            active: 0,
+            used: 0,
+            seq: 0,
+            current: Settings::default(),
+        };
+        store.scan()?;
+        Ok(store)
+    }
+
+    /// Returns the current settings.
+    ///
+    /// # Returns
+    /// * `&Settings` - Newest saved settings, or the defaults
+    #[allow(dead_code)]
+    pub fn settings(&self) -> &Settings {
+        &self.current
+    }
+
+    /// Saves settings.
+    ///
+    /// # Details
+    /// Nothing is written if the settings are unchanged.
+    ///
+    /// # Arguments
+    /// * `settings` - Settings to save
+    ///
+    /// # Returns
+    /// * `Result<(), SettingsError<F::Error>>` - Ok or flash error
+    #[allow(dead_code)]
+    pub fn save(&mut self, settings: &Settings) -> Result<(), SettingsError<F::Error>> {
+        if *settings == self.current {
+            return Ok(());
+        }
+        let seq = self.seq.wrapping_add(1);
+        let mut buf = [0xFFu8; BUF_LEN];
+        let payload_len = settings.encode(&mut buf[HEADER_LEN..]);
+        buf[..2].copy_from_slice(&RECORD_MAGIC);
+        buf[2..4].copy_from_slice(&(payload_len as u16).to_le_bytes());
+        buf[4..8].copy_from_slice(&seq.to_le_bytes());
+        let crc = record_crc(&buf[2..8], &buf[HEADER_LEN..HEADER_LEN + payload_len]);
+        buf[8..12].copy_from_slice(&crc.to_le_bytes());
+        let len = self.aligned(HEADER_LEN + payload_len) as u32;
+        let sector = F::ERASE_SIZE as u32;
+        if self.used + len > sector {
+            let next = (self.active + 1) % self.sectors();
+            self.erase_sector(next)?;
+            self.active = next;
+            self.used = 0;
+        }
+        let at = self.base + self.active * sector + self.used;
+        if let Err(e) = self.flash.write(at, &buf[..len as usize]) {
+            self.used = sector;
+            return Err(SettingsError::Flash(e));
+        }
+        self.used += len;
+        self.seq = seq;
+        self.current = *settings;
+        Ok(())
+    }
+
+    /// Returns the flash device.
+    ///
+    /// # Returns
+    /// * `&F` - Flash device
+    #[allow(dead_code)]
+    pub fn flash(&self) -> &F {
+        &self.flash
+    }
+
+    /// Consumes the store and returns the flash device.
+    ///
+    /// # Returns
+    /// * `F` - Flash device
+    #[allow(dead_code)]
+    pub fn release(self) -> F {
+        self.flash
+    }
+
+    /// Returns the number of sectors in the region.
+    ///
+    /// # Returns
+    /// * `u32` - Sector count
+    fn sectors(&self) -> u32 {
+        self.size / F::ERASE_SIZE as u32
+    }
+
+    /// Rounds a length up to the record alignment.
+    ///
+    /// # Arguments
+    /// * `len` - Length in bytes
+    ///
+    /// # Returns
+    /// * `usize` - Length padded to the flash write size
+    fn aligned(&self, len: usize) -> usize {
+        len.next_multiple_of(F::WRITE_SIZE.max(4))
+    }
+
+    /// Erases one sector of the region.
+    ///
+    /// # Arguments
+    /// * `index` - Sector index within the region
+    ///
+    /// # Returns
+    /// * `Result<(), SettingsError<F::Error>>` - Ok or flash error
+    fn erase_sector(&mut self, index: u32) -> Result<(), SettingsError<F::Error>> {
+        let from = self.base + index * F::ERASE_SIZE as u32;
+        self.flash
+            .erase(from, from + F::ERASE_SIZE as u32)
+            .map_err(SettingsError::Flash)
+    }
+
+    /// Finds the newest valid record and the write position.
+    ///
+    /// # Details
+    /// Walks each sector record by record. Erased space ends a
+    /// sector's records; an unreadable header marks the rest of
+    /// the sector as used so nothing is written over it.
+    ///
+    /// # Returns
+    /// * `Result<(), SettingsError<F::Error>>` - Ok or flash error
+    fn scan(&mut self) -> Result<(), SettingsError<F::Error>> {
+        let sector = F::ERASE_SIZE as u32;
+        let mut newest: Option<(u32, Settings, u32)> = None;
+        let mut newest_end = sector;
+        let mut first_free = None;
+        let mut buf = [0u8; BUF_LEN];
+        for s in 0..self.sectors() {
+            let start = s * sector;
+            let mut pos = 0;
+            let end = loop {
+                if pos + HEADER_LEN as u32 > sector {
+                    break sector;
+                }
+                let header = &mut buf[..HEADER_LEN];
+                self.flash
+                    .read(self.base + start + pos, header)
+                    .map_err(SettingsError::Flash)?;
+                if header.iter().all(|&b| b == 0xFF) {
+                    break pos;
+                }
+                let payload_len = usize::from(u16::from_le_bytes([header[2], header[3]]));
+                let len = self.aligned(HEADER_LEN + payload_len) as u32;
+                if header[..2] != RECORD_MAGIC
+                    || payload_len > MAX_PAYLOAD_LEN
+                    || pos + len > sector
+                {
+                    break sector;
+                }
+                let mut meta = [0u8; 6];
+                meta.copy_from_slice(&header[2..8]);
+                let seq = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+                let crc = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+                let payload = &mut buf[..payload_len];
+                self.flash
+                    .read(self.base + start + pos + HEADER_LEN as u32, payload)
+                    .map_err(SettingsError::Flash)?;
+                if record_crc(&meta, payload) == crc
+                    && let Some(settings) = Settings::decode(payload)
+                    && newest.is_none_or(|(best, _, _)| seq > best)
+                {
+                    newest = Some((seq, settings, s));
+                }
+                pos += len;
+            };
+            if newest.is_some_and(|(_, _, at)| at == s) {
+                newest_end = end;
+            }
+            if first_free.is_none() && end < sector {
+                first_free = Some((s, end));
+            }
+        }
+        if let Some((seq, settings, at)) = newest {
+            self.seq = seq;
+            self.current = settings;
+            self.active = at;
+            self.used = newest_end;
+        } else {
+            (self.active, self.used) = first_free.unwrap_or((0, sector));
+        }
+        Ok(())
+    }
+}
+
+/// Computes a record CRC.
+///
+/// # Arguments
+/// * `meta` - Length and sequence bytes from the header
+/// * `payload` - Payload bytes
+///
+/// # Returns
+/// * `u32` - CRC-32 over both
+fn record_crc(meta: &[u8], payload: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(meta);
+    crc.update(payload);
+    crc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MemFlash;
+
+    const SECTOR: u32 = 4096;
+
+    fn open(flash: MemFlash) -> SettingsStore<MemFlash> {
+        SettingsStore::new(flash, 0, 2 * SECTOR).unwrap()
+    }
+
+    fn custom() -> Settings {
+        Settings {
+            blink: BlinkConfig::new(100, 900).unwrap(),
+            pattern: Some(Pattern::sos()),
+            polarity: Polarity::ActiveLow,
+        }
+    }
+
+    fn rate(on_ms: u64) -> Settings {
+        Settings {
+            blink: BlinkConfig::new(on_ms, 500).unwrap(),
+            ..Settings::default()
+        }
+    }
+
+    // ==================== Settings Tests ====================
+
+    #[test]
+    fn test_default_settings() {
+        let settings = Settings::default();
+        assert_eq!(settings.blink, BlinkConfig::default());
+        assert_eq!(settings.pattern, None);
+        assert_eq!(settings.polarity, Polarity::default());
+    }
+
+    #[test]
+    fn test_startup_pattern_without_pattern() {
+        let settings = rate(200);
+        assert_eq!(settings.startup_pattern(), Pattern::on_off(200, 500));
+    }
+
+    #[test]
+    fn test_startup_pattern_with_pattern() {
+        assert_eq!(custom().startup_pattern(), Pattern::sos());
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let mut buf = [0u8; MAX_PAYLOAD_LEN];
+        let len = custom().encode(&mut buf);
+        assert_eq!(Settings::decode(&buf[..len]), Some(custom()));
+    }
+
+    #[test]
+    fn test_payload_drops_empty_pattern() {
+        let settings = Settings {
+            pattern: Some(Pattern::new()),
+            ..custom()
+        };
+        let mut buf = [0u8; MAX_PAYLOAD_LEN];
+        let len = settings.encode(&mut buf);
+        let expected = Settings {
+            pattern: None,
+            ..custom()
+        };
+        assert_eq!(Settings::decode(&buf[..len]), Some(expected));
+    }
+
+    #[test]
+    fn test_payload_rejects_bad_version() {
+        let mut buf = [0u8; MAX_PAYLOAD_LEN];
+        let len = rate(200).encode(&mut buf);
+        buf[0] = SETTINGS_VERSION + 1;
+        assert_eq!(Settings::decode(&buf[..len]), None);
+    }
+
+    #[test]
+    fn test_payload_rejects_bad_rate() {
+        let mut buf = [0u8; MAX_PAYLOAD_LEN];
+        let len = rate(200).encode(&mut buf);
+        buf[2..6].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(Settings::decode(&buf[..len]), None);
+    }
+
+    // ==================== Region Tests ====================
+
+    #[test]
+    fn test_rejects_misaligned_base() {
+        let result = SettingsStore::new(MemFlash::new(4), 100, 2 * SECTOR);
+        assert!(matches!(result, Err(SettingsError::InvalidRegion)));
+    }
+
+    #[test]
+    fn test_rejects_single_sector() {
+        let result = SettingsStore::new(MemFlash::new(4), 0, SECTOR);
+        assert!(matches!(result, Err(SettingsError::InvalidRegion)));
+    }
+
+    #[test]
+    fn test_rejects_region_past_end() {
+        let result = SettingsStore::new(MemFlash::new(4), 3 * SECTOR, 2 * SECTOR);
+        assert!(matches!(result, Err(SettingsError::InvalidRegion)));
+    }
+
+    // ==================== Load Tests ====================
+
+    #[test]
+    fn test_empty_flash_loads_defaults() {
+        let store = open(MemFlash::new(2));
+        assert_eq!(*store.settings(), Settings::default());
+    }
+
+    #[test]
+    fn test_garbage_flash_loads_defaults() {
+        let mut flash = MemFlash::new(2);
+        flash.data_mut().fill(0x5A);
+        let store = open(flash);
+        assert_eq!(*store.settings(), Settings::default());
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&custom()).unwrap();
+        let store = open(store.release());
+        assert_eq!(*store.settings(), custom());
+    }
+
+    #[test]
+    fn test_latest_save_wins() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        store.save(&rate(200)).unwrap();
+        store.save(&rate(300)).unwrap();
+        let store = open(store.release());
+        assert_eq!(*store.settings(), rate(300));
+    }
+
+    #[test]
+    fn test_region_at_offset() {
+        let mut store = SettingsStore::new(MemFlash::new(4), 2 * SECTOR, 2 * SECTOR).unwrap();
+        store.save(&custom()).unwrap();
+        let flash = store.release();
+        assert!(
+            flash.data()[..2 * SECTOR as usize]
+                .iter()
+                .all(|&b| b == 0xFF)
+        );
+        let store = SettingsStore::new(flash, 2 * SECTOR, 2 * SECTOR).unwrap();
+        assert_eq!(*store.settings(), custom());
+    }
+
+    // ==================== Save Tests ====================
+
+    #[test]
+    fn test_unchanged_save_writes_nothing() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&Settings::default()).unwrap();
+        assert!(store.release().data().iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn test_save_appends_after_reload() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        let mut store = open(store.release());
+        store.save(&rate(200)).unwrap();
+        let flash = store.release();
+        assert_eq!(flash.erase_counts(), &[0, 0]);
+        assert_eq!(*open(flash).settings(), rate(200));
+    }
+
+    #[test]
+    fn test_full_sector_rotates() {
+        let mut store = open(MemFlash::new(2));
+        for on_ms in 100..=400 {
+            store.save(&rate(on_ms)).unwrap();
+        }
+        let flash = store.release();
+        assert_eq!(flash.erase_counts(), &[0, 1]);
+        assert_eq!(*open(flash).settings(), rate(400));
+    }
+
+    #[test]
+    fn test_wear_is_spread_evenly() {
+        let mut store = SettingsStore::new(MemFlash::new(4), 0, 4 * SECTOR).unwrap();
+        for on_ms in 100..=2100 {
+            store.save(&rate(on_ms)).unwrap();
+        }
+        let flash = store.release();
+        let counts = flash.erase_counts();
+        let max = counts.iter().max().unwrap();
+        let min = counts.iter().min().unwrap();
+        assert!(max - min <= 1, "uneven wear: {counts:?}");
+    }
+
+    #[test]
+    fn test_rotation_survives_reload() {
+        let mut store = open(MemFlash::new(2));
+        for on_ms in 100..=400 {
+            store.save(&rate(on_ms)).unwrap();
+            if on_ms % 50 == 0 {
+                store = open(store.release());
+            }
+        }
+        assert_eq!(*open(store.release()).settings(), rate(400));
+    }
+
+    // ==================== Power Loss Tests ====================
+
+    #[test]
+    fn test_torn_write_keeps_previous() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        let mut flash = store.release();
+        flash.fail_after(Some(16));
+        let mut store = open(flash);
+        assert!(store.save(&custom()).is_err());
+        let mut flash = store.release();
+        flash.fail_after(None);
+        assert_eq!(*open(flash).settings(), rate(100));
+    }
+
+    #[test]
+    fn test_torn_header_keeps_previous() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        let mut flash = store.release();
+        flash.fail_after(Some(4));
+        let mut store = open(flash);
+        assert!(store.save(&rate(200)).is_err());
+        let mut flash = store.release();
+        flash.fail_after(None);
+        let mut store = open(flash);
+        assert_eq!(*store.settings(), rate(100));
+        store.save(&rate(300)).unwrap();
+        assert_eq!(*open(store.release()).settings(), rate(300));
+    }
+
+    #[test]
+    fn test_save_after_torn_write() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        let mut flash = store.release();
+        flash.fail_after(Some(16));
+        let mut store = open(flash);
+        assert!(store.save(&rate(200)).is_err());
+        let mut flash = store.release();
+        flash.fail_after(None);
+        let mut store = open(flash);
+        store.save(&rate(300)).unwrap();
+        assert_eq!(*open(store.release()).settings(), rate(300));
+    }
+
+    #[test]
+    fn test_corrupt_latest_falls_back() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&rate(100)).unwrap();
+        store.save(&rate(200)).unwrap();
+        let mut flash = store.release();
+        let second = (HEADER_LEN + FIXED_PAYLOAD_LEN).next_multiple_of(4);
+        flash.data_mut()[second + HEADER_LEN + 2] ^= 0x01;
+        assert_eq!(*open(flash).settings(), rate(100));
+    }
+
+    #[test]
+    fn test_corrupt_only_record_loads_defaults() {
+        let mut store = open(MemFlash::new(2));
+        store.save(&custom()).unwrap();
+        let mut flash = store.release();
+        flash.data_mut()[HEADER_LEN] ^= 0xFF;
+        assert_eq!(*open(flash).settings(), Settings::default());
+    }
+}
@@ -0,0 +1,1224 @@
+/*
+ * @file shell.rs
+ * @brief Line-oriented serial command shell
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: shell.rs
+//!
+//! DESCRIPTION:
+//! Serial Command Shell for RP2350 LED Control.
+//!
+//! BRIEF:
+//! Assembles bytes from any serial transport into lines, parses
+//! them into commands and executes them against the current
+//! settings. Execution writes a text reply and returns the action
+//! the firmware must carry out, so the shell runs on the host
+//! without USB. Control protocol requests go through the same
+//! dispatcher, so the console and host tools share one view of
+//! the settings. Console splits one serial port between typed
+//! commands and protocol frames.
+//!
+//! COMMANDS:
+//!   rate MS [OFF_MS]   blink at MS on, MS or OFF_MS off
+//!   pattern SOURCE     play a pattern in the dsl.rs language
+//!   on | off           hold the LED on or off
+//!   resume             clear on/off and play the pattern again
+//!   status             show rate, pattern, mode and polarity
+//!   save               store the settings in flash
+//!   reset              reboot the board
+//!   help               list the commands
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::command::LedCommand;
+use crate::config::{BlinkConfig, ConfigError};
+use crate::dsl::{self, ParseError};
+use crate::led::{LedState, Polarity};
+use crate::pattern::Pattern;
+use crate::protocol::{
+    FRAME_DELIMITER, MAX_BODY_LEN, PROTOCOL_VERSION, Request, Response, Server, ServerEvent,
+};
+use crate::settings::Settings;
+use core::fmt::{self, Write};
+
+/// Command list printed by `help`.
+const HELP: &str = "\
+rate MS [OFF_MS]   blink at MS on, MS or OFF_MS off\r
+pattern SOURCE     play a pattern, e.g. pattern on 100ms, off 900ms\r
+on | off           hold the LED on or off\r
+resume             play the pattern again\r
+status             show the current settings\r
+save               store the settings in flash\r
+reset              reboot the board\r
+";
+
+/// Shell errors.
+///
+/// # Variants
+/// * `UnknownCommand` - First word is not a command
+/// * `MissingArgument` - Command needs an argument
+/// * `UnexpectedArgument` - Command takes no further arguments
+/// * `InvalidNumber` - Argument is not a number
+/// * `InvalidRate` - Rate outside the configured limits
+/// * `Pattern` - Pattern source failed to parse
+/// * `LineTooLong` - Line exceeded the line buffer
+/// * `InvalidUtf8` - Line is not valid UTF-8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ShellError {
+    UnknownCommand,
+    MissingArgument,
+    UnexpectedArgument,
+    InvalidNumber,
+    InvalidRate(ConfigError),
+    Pattern(ParseError),
+    LineTooLong,
+    InvalidUtf8,
+}
+
+impl fmt::Display for ShellError {
+    /// Writes a one-line description for the serial reply.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand => f.write_str("unknown command, try help"),
+            Self::MissingArgument => f.write_str("missing argument"),
+            Self::UnexpectedArgument => f.write_str("unexpected argument"),
+            Self::InvalidNumber => f.write_str("invalid number"),
+            Self::InvalidRate(e) => write!(f, "invalid rate: {e:?}"),
+            Self::Pattern(e) => write!(f, "pattern column {}: {:?}", e.column, e.kind),
+            Self::LineTooLong => f.write_str("line too long"),
+            Self::InvalidUtf8 => f.write_str("invalid text"),
+        }
+    }
+}
+
+/// Parsed shell command.
+///
+/// # Variants
+/// * `Rate` - Blink with these delays
+/// * `Pattern` - Play this pattern
+/// * `On` - Hold the LED on
+/// * `Off` - Hold the LED off
+/// * `Resume` - Clear on/off
+/// * `Status` - Report the settings
+/// * `Save` - Store the settings
+/// * `Reset` - Reboot
+/// * `Help` - List the commands
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code, clippy::large_enum_variant)]
+pub enum ShellCommand {
+    Rate(BlinkConfig),
+    Pattern(Pattern),
+    On,
+    Off,
+    Resume,
+    Status,
+    Save,
+    Reset,
+    Help,
+}
+
+/// Work the firmware must do after a command.
+///
+/// # Variants
+/// * `None` - Nothing beyond the reply
+/// * `Led` - Send a command to the LED task
+/// * `Save` - Store these settings, then report the result
+/// * `Reset` - Reboot the board
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code, clippy::large_enum_variant)]
+pub enum ShellAction {
+    None,
+    Led(LedCommand),
+    Save(Settings),
+    Reset,
+}
+
+/// Parses one command line.
+///
+/// # Details
+/// The command word is case-insensitive. Everything after
+/// `pattern` is handed to the pattern parser unchanged.
+///
+/// # Arguments
+/// * `line` - Line without its terminator
+///
+/// # Returns
+/// * `Result<ShellCommand, ShellError>` - Command or parse error
+#[allow(dead_code)]
+pub fn parse_line(line: &str) -> Result<ShellCommand, ShellError> {
+    let line = line.trim();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim_start();
+    if word.eq_ignore_ascii_case("rate") {
+        return parse_rate(rest);
+    }
+    if word.eq_ignore_ascii_case("pattern") {
+        if rest.is_empty() {
+            return Err(ShellError::MissingArgument);
+        }
+        return dsl::parse(rest)
+            .map(ShellCommand::Pattern)
+            .map_err(ShellError::Pattern);
+    }
+    let (_, command) = SIMPLE_COMMANDS
+        .iter()
+        .find(|(name, _)| word.eq_ignore_ascii_case(name))
+        .ok_or(ShellError::UnknownCommand)?;
+    if !rest.is_empty() {
+        return Err(ShellError::UnexpectedArgument);
+    }
+    Ok(*command)
+}
+
+/// Parses the arguments of `rate`.
+///
+/// # Arguments
+/// * `args` - ON delay and optional OFF delay in milliseconds
+///
+/// # Returns
+/// * `Result<ShellCommand, ShellError>` - Rate command or error
+fn parse_rate(args: &str) -> Result<ShellCommand, ShellError> {
+    let mut words = args.split_whitespace();
+    let on_ms = parse_number(words.next().ok_or(ShellError::MissingArgument)?)?;
+    let off_ms = match words.next() {
+        Some(word) => parse_number(word)?,
+        None => on_ms,
+    };
+    if words.next().is_some() {
+        return Err(ShellError::UnexpectedArgument);
+    }
+    BlinkConfig::new(on_ms, off_ms)
+        .map(ShellCommand::Rate)
+        .map_err(ShellError::InvalidRate)
+}
+
+/// Parses a decimal number.
+///
+/// # Arguments
+/// * `word` - Digits
+///
+/// # Returns
+/// * `Result<u64, ShellError>` - Value or `InvalidNumber`
+fn parse_number(word: &str) -> Result<u64, ShellError> {
+    word.parse().map_err(|_| ShellError::InvalidNumber)
+}
+
+/// Commands that take no arguments.
+const SIMPLE_COMMANDS: [(&str, ShellCommand); 7] = [
+    ("on", ShellCommand::On),
+    ("off", ShellCommand::Off),
+    ("resume", ShellCommand::Resume),
+    ("status", ShellCommand::Status),
+    ("save", ShellCommand::Save),
+    ("reset", ShellCommand::Reset),
+    ("help", ShellCommand::Help),
+];
+
+/// Assembles received bytes into lines.
+///
+/// # Details
+/// CR, LF or CRLF end a line; empty lines are ignored. Backspace
+/// and DEL remove the last byte. A line longer than `N` bytes is
+/// dropped whole and reported once it ends.
+///
+/// # Fields
+/// * `buf` - Line storage
+/// * `len` - Used bytes
+/// * `overflow` - Current line exceeded `N`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct LineBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    overflow: bool,
+}
+
+impl<const N: usize> Default for LineBuffer<N> {
+    /// Returns an empty line buffer.
+    ///
+    /// # Returns
+    /// * `Self` - Empty buffer
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LineBuffer<N> {
+    /// Creates an empty line buffer.
+    ///
+    /// # Returns
+    /// * `Self` - Empty buffer
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Adds one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    ///
+    /// # Returns
+    /// * `Option<Result<&str, ShellError>>` - Completed line or line error
+    #[allow(dead_code)]
+    pub fn push(&mut self, byte: u8) -> Option<Result<&str, ShellError>> {
+        match byte {
+            b'\r' | b'\n' => {
+                let len = core::mem::take(&mut self.len);
+                if core::mem::take(&mut self.overflow) {
+                    return Some(Err(ShellError::LineTooLong));
+                }
+                if len == 0 {
+                    return None;
+                }
+                Some(core::str::from_utf8(&self.buf[..len]).map_err(|_| ShellError::InvalidUtf8))
+            }
+            0x08 | 0x7F => {
+                self.len = self.len.saturating_sub(1);
+                None
+            }
+            _ if self.len == N => {
+                self.overflow = true;
+                None
+            }
+            _ => {
+                self.buf[self.len] = byte;
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Returns the bytes of the unfinished line.
+    ///
+    /// # Returns
+    /// * `&[u8]` - Pending bytes
+    #[allow(dead_code)]
+    pub fn pending(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Discards the unfinished line.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.overflow = false;
+    }
+}
+
+/// Fixed-capacity text reply.
+///
+/// # Details
+/// Implements `fmt::Write`; text beyond `N` bytes is dropped
+/// and reported as a write error.
+///
+/// # Fields
+/// * `buf` - Reply storage
+/// * `len` - Used bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct Reply<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Reply<N> {
+    /// Returns an empty reply.
+    ///
+    /// # Returns
+    /// * `Self` - Empty reply
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Reply<N> {
+    /// Creates an empty reply.
+    ///
+    /// # Returns
+    /// * `Self` - Empty reply
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Returns the reply text.
+    ///
+    /// # Returns
+    /// * `&str` - Text written so far
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+
+    /// Empties the reply.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Write for Reply<N> {
+    /// Appends text, keeping whole characters only.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = N - self.len;
+        let mut take = s.len().min(room);
+        while !s.is_char_boundary(take) {
+            take -= 1;
+        }
+        self.buf[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+        self.len += take;
+        if take == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
+
+/// Reply sink that drops the text.
+///
+/// # Details
+/// Protocol requests answer with a response message, not text.
+struct Discard;
+
+impl Write for Discard {
+    /// Accepts and drops the text.
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Command dispatcher.
+///
+/// # Details
+/// Tracks the settings the LED is running with, so `status`
+/// and `save` reflect every change made from the shell.
+///
+/// # Fields
+/// * `settings` - Current settings
+/// * `forced` - LED state held by `on` or `off`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct Shell {
+    settings: Settings,
+    forced: Option<LedState>,
+}
+
+impl Shell {
+    /// Creates a shell for the settings loaded at boot.
+    ///
+    /// # Arguments
+    /// * `settings` - Settings the LED starts with
+    ///
+    /// # Returns
+    /// * `Self` - New Shell instance
+    #[allow(dead_code)]
+    pub const fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            forced: None,
+        }
+    }
+
+    /// Parses and runs one line.
+    ///
+    /// # Details
+    /// Writes "ok", the requested report or "error: ..." lines
+    /// to `out`. `save` and `reset` write nothing; the firmware
+    /// reports their outcome.
+    ///
+    /// # Arguments
+    /// * `line` - Line without its terminator
+    /// * `out` - Reply sink
+    ///
+    /// # Returns
+    /// * `ShellAction` - Work left for the firmware
+    #[allow(dead_code)]
+    pub fn execute<W: Write>(&mut self, line: &str, out: &mut W) -> ShellAction {
+        match parse_line(line) {
+            Ok(command) => self.dispatch(command, out),
+            Err(e) => {
+                let _ = write!(out, "error: {e}\r\n");
+                ShellAction::None
+            }
+        }
+    }
+
+    /// Runs a parsed command.
+    ///
+    /// # Arguments
+    /// * `command` - Command to run
+    /// * `out` - Reply sink
+    ///
+    /// # Returns
+    /// * `ShellAction` - Work left for the firmware
+    #[allow(dead_code)]
+    pub fn dispatch<W: Write>(&mut self, command: ShellCommand, out: &mut W) -> ShellAction {
+        let action = match command {
+            ShellCommand::Rate(config) => {
+                self.settings.blink = config;
+                self.settings.pattern = None;
+                ShellAction::Led(LedCommand::SetRate(config))
+            }
+            ShellCommand::Pattern(pattern) => {
+                self.settings.pattern = Some(pattern);
+                ShellAction::Led(LedCommand::SetPattern(pattern))
+            }
+            ShellCommand::On => {
+                self.forced = Some(LedState::On);
+                ShellAction::Led(LedCommand::ForceOn)
+            }
+            ShellCommand::Off => {
+                self.forced = Some(LedState::Off);
+                ShellAction::Led(LedCommand::ForceOff)
+            }
+            ShellCommand::Resume => {
+                self.forced = None;
+                ShellAction::Led(LedCommand::Resume)
+            }
+            ShellCommand::Status => {
+                let _ = self.write_status(out);
+                return ShellAction::None;
+            }
+            ShellCommand::Help => {
+                let _ = out.write_str(HELP);
+                return ShellAction::None;
+            }
+            ShellCommand::Save => return ShellAction::Save(self.settings),
+            ShellCommand::Reset => return ShellAction::Reset,
+        };
+        let _ = out.write_str("ok\r\n");
+        action
+    }
+
+    /// Runs a control protocol request.
+    ///
+    /// # Arguments
+    /// * `request` - Decoded request
+    /// * `led` - Current LED state, for `GetState`
+    ///
+    /// # Returns
+    /// * `(Response, ShellAction)` - Reply and work left for the firmware
+    #[allow(dead_code)]
+    pub fn request(&mut self, request: &Request, led: LedState) -> (Response, ShellAction) {
+        let command = match *request {
+            Request::Ping => return (Response::Pong(PROTOCOL_VERSION), ShellAction::None),
+            Request::GetConfig => {
+                return (Response::Config(self.settings.blink), ShellAction::None);
+            }
+            Request::GetState => return (Response::State(led), ShellAction::None),
+            Request::GetSettings => return (Response::Settings(self.settings), ShellAction::None),
+            Request::SetConfig(config) => ShellCommand::Rate(config),
+            Request::UploadPattern(pattern) => ShellCommand::Pattern(pattern),
+        };
+        (Response::Ack, self.dispatch(command, &mut Discard))
+    }
+
+    /// Returns the current settings.
+    ///
+    /// # Returns
+    /// * `&Settings` - Settings including shell changes
+    #[allow(dead_code)]
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Returns the state held by `on` or `off`.
+    ///
+    /// # Returns
+    /// * `Option<LedState>` - Held state, None while playing
+    #[allow(dead_code)]
+    pub fn forced(&self) -> Option<LedState> {
+        self.forced
+    }
+
+    /// Writes the status report.
+    ///
+    /// # Arguments
+    /// * `out` - Reply sink
+    ///
+    /// # Returns
+    /// * `fmt::Result` - Ok or sink error
+    fn write_status<W: Write>(&self, out: &mut W) -> fmt::Result {
+        let blink = self.settings.blink;
+        write!(out, "rate {} {}\r\n", blink.on_ms(), blink.off_ms())?;
+        match &self.settings.pattern {
+            Some(pattern) => write!(
+                out,
+                "pattern custom, {} segments, {} ms\r\n",
+                pattern.len(),
+                pattern.period_ms()
+            )?,
+            None => out.write_str("pattern blink\r\n")?,
+        }
+        let mode = match self.forced {
+            Some(LedState::On) => "on",
+            Some(LedState::Off) => "off",
+            None => "playing",
+        };
+        write!(out, "mode {mode}\r\n")?;
+        let polarity = match self.settings.polarity {
+            Polarity::ActiveHigh => "active-high",
+            Polarity::ActiveLow => "active-low",
+        };
+        write!(out, "polarity {polarity}\r\n")
+    }
+}
+
+/// Size of the console's text reply buffer.
+const CONSOLE_REPLY_LEN: usize = 512;
+
+/// Serial console serving the shell and the control protocol.
+///
+/// # Details
+/// A zero byte switches to protocol mode until the frame's
+/// closing zero byte; every other byte is shell input, echoed
+/// back if it is ASCII. A zero byte only closes a frame that has
+/// a body, and a frame longer than `MAX_BODY_LEN` drops back to
+/// text, so a lost or extra delimiter costs at most one frame. `save` is carried out through the
+/// callback given to `push`, so only LED commands and resets
+/// are left to the caller.
+///
+/// # Fields
+/// * `shell` - Command dispatcher
+/// * `line` - Shell line in progress
+/// * `reply` - Text output of the last byte
+/// * `server` - Protocol end point
+/// * `frame` - Body bytes of the frame in progress, None in text mode
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub struct Console<const N: usize> {
+    shell: Shell,
+    line: LineBuffer<N>,
+    reply: Reply<CONSOLE_REPLY_LEN>,
+    server: Server,
+    frame: Option<usize>,
+}
+
+impl<const N: usize> Console<N> {
+    /// Creates a console for the settings loaded at boot.
+    ///
+    /// # Arguments
+    /// * `settings` - Settings the LED starts with
+    ///
+    /// # Returns
+    /// * `Self` - New Console instance
+    #[allow(dead_code)]
+    pub const fn new(settings: Settings) -> Self {
+        Self {
+            shell: Shell::new(settings),
+            line: LineBuffer::new(),
+            reply: Reply::new(),
+            server: Server::new(),
+            frame: None,
+        }
+    }
+
+    /// Handles one received byte.
+    ///
+    /// # Arguments
+    /// * `byte` - Received byte
+    /// * `led` - Current LED state, for state queries
+    /// * `save` - Stores settings, returning whether it succeeded
+    ///
+    /// # Returns
+    /// * `(&[u8], ShellAction)` - Bytes to send back and work left for the caller
+    #[allow(dead_code)]
+    pub fn push<S>(&mut self, byte: u8, led: LedState, save: S) -> (&[u8], ShellAction)
+    where
+        S: FnOnce(&Settings) -> bool,
+    {
+        if self.frame.is_some() || byte == FRAME_DELIMITER {
+            self.frame = match (self.frame, byte) {
+                (Some(len), FRAME_DELIMITER) if len > 0 => None,
+                (_, FRAME_DELIMITER) => Some(0),
+                (Some(len), _) if len < MAX_BODY_LEN => Some(len + 1),
+                _ => None,
+            };
+            return match self.server.push(byte) {
+                Some(ServerEvent::Request(packet)) => {
+                    let (response, action) = self.shell.request(&packet.message, led);
+                    (self.server.respond(packet.seq, &response), action)
+                }
+                Some(ServerEvent::Reply) => (self.server.reply(), ShellAction::None),
+                None => (&[], ShellAction::None),
+            };
+        }
+        self.reply.clear();
+        if byte.is_ascii() {
+            let _ = self.reply.write_char(char::from(byte));
+        }
+        let action = match self.line.push(byte) {
+            Some(Ok(text)) => {
+                let _ = self.reply.write_str("\r\n");
+                self.shell.execute(text, &mut self.reply)
+            }
+            Some(Err(e)) => {
+                let _ = write!(self.reply, "\r\nerror: {e}\r\n");
+                ShellAction::None
+            }
+            None => ShellAction::None,
+        };
+        let action = match action {
+            ShellAction::Save(settings) => {
+                let _ = self.reply.write_str(if save(&settings) {
+                    "ok\r\n"
+                } else {
+                    "error: save failed\r\n"
+                });
+                ShellAction::None
+            }
+            action => action,
+        };
+        (self.reply.as_str().as_bytes(), action)
+    }
+
+    /// Starts a new session.
+    ///
+    /// # Details
+    /// Call when the host reopens the port. Drops the partial
+    /// line and frame and the remembered protocol reply.
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.line.clear();
+        self.server.reset();
+        self.frame = None;
+    }
+
+    /// Returns the command dispatcher.
+    ///
+    /// # Returns
+    /// * `&Shell` - Shell with the current settings
+    #[allow(dead_code)]
+    pub fn shell(&self) -> &Shell {
+        &self.shell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsl::ParseErrorKind;
+    use crate::protocol::{MAX_FRAME_LEN, Packet, decode_frame, encode_frame};
+    use std::string::String;
+
+    fn run(shell: &mut Shell, line: &str) -> (ShellAction, String) {
+        let mut out = String::new();
+        let action = shell.execute(line, &mut out);
+        (action, out)
+    }
+
+    fn feed<const N: usize>(
+        buffer: &mut LineBuffer<N>,
+        bytes: &[u8],
+    ) -> Vec<Result<String, ShellError>> {
+        bytes
+            .iter()
+            .filter_map(|&b| buffer.push(b).map(|r| r.map(String::from)))
+            .collect()
+    }
+
+    // ==================== parse_line Tests ====================
+
+    #[test]
+    fn test_parse_rate_symmetric() {
+        assert_eq!(
+            parse_line("rate 250"),
+            Ok(ShellCommand::Rate(BlinkConfig::new(250, 250).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_parse_rate_asymmetric() {
+        assert_eq!(
+            parse_line("rate 100 900"),
+            Ok(ShellCommand::Rate(BlinkConfig::new(100, 900).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_parse_rate_errors() {
+        assert_eq!(parse_line("rate"), Err(ShellError::MissingArgument));
+        assert_eq!(parse_line("rate fast"), Err(ShellError::InvalidNumber));
+        assert_eq!(
+            parse_line("rate 1"),
+            Err(ShellError::InvalidRate(ConfigError::TooShort))
+        );
+        assert_eq!(
+            parse_line("rate 100 100 100"),
+            Err(ShellError::UnexpectedArgument)
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let expected = dsl::parse("on 100ms, off 900ms").unwrap();
+        assert_eq!(
+            parse_line("pattern on 100ms, off 900ms"),
+            Ok(ShellCommand::Pattern(expected))
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern_errors() {
+        assert_eq!(parse_line("pattern"), Err(ShellError::MissingArgument));
+        let Err(ShellError::Pattern(e)) = parse_line("pattern on 100xs") else {
+            panic!("expected a pattern error");
+        };
+        assert_eq!(e.kind, ParseErrorKind::UnknownUnit);
+    }
+
+    #[test]
+    fn test_parse_simple_commands() {
+        assert_eq!(parse_line("on"), Ok(ShellCommand::On));
+        assert_eq!(parse_line("off"), Ok(ShellCommand::Off));
+        assert_eq!(parse_line("resume"), Ok(ShellCommand::Resume));
+        assert_eq!(parse_line("status"), Ok(ShellCommand::Status));
+        assert_eq!(parse_line("save"), Ok(ShellCommand::Save));
+        assert_eq!(parse_line("reset"), Ok(ShellCommand::Reset));
+        assert_eq!(parse_line("help"), Ok(ShellCommand::Help));
+    }
+
+    #[test]
+    fn test_parse_case_and_whitespace() {
+        assert_eq!(parse_line("  STATUS  "), Ok(ShellCommand::Status));
+        assert_eq!(
+            parse_line("Rate\t300"),
+            Ok(ShellCommand::Rate(BlinkConfig::new(300, 300).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_and_extra() {
+        assert_eq!(parse_line("blink"), Err(ShellError::UnknownCommand));
+        assert_eq!(parse_line("on now"), Err(ShellError::UnexpectedArgument));
+    }
+
+    // ==================== LineBuffer Tests ====================
+
+    #[test]
+    fn test_line_buffer_splits_lines() {
+        let mut buffer: LineBuffer<32> = LineBuffer::new();
+        let lines = feed(&mut buffer, b"on\r\noff\nstatus\r");
+        assert_eq!(
+            lines,
+            vec![
+                Ok(String::from("on")),
+                Ok(String::from("off")),
+                Ok(String::from("status"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_buffer_keeps_partial_line() {
+        let mut buffer: LineBuffer<32> = LineBuffer::new();
+        assert!(feed(&mut buffer, b"ra").is_empty());
+        assert_eq!(buffer.pending(), b"ra");
+        assert_eq!(
+            feed(&mut buffer, b"te 5\r"),
+            vec![Ok(String::from("rate 5"))]
+        );
+    }
+
+    #[test]
+    fn test_line_buffer_backspace() {
+        let mut buffer: LineBuffer<32> = LineBuffer::new();
+        let lines = feed(&mut buffer, b"onx\x08\r\x7F\x7Foff\r");
+        assert_eq!(lines, vec![Ok(String::from("on")), Ok(String::from("off"))]);
+    }
+
+    #[test]
+    fn test_line_buffer_overflow() {
+        let mut buffer: LineBuffer<4> = LineBuffer::new();
+        let lines = feed(&mut buffer, b"status\ron\r");
+        assert_eq!(
+            lines,
+            vec![Err(ShellError::LineTooLong), Ok(String::from("on"))]
+        );
+    }
+
+    #[test]
+    fn test_line_buffer_invalid_utf8() {
+        let mut buffer: LineBuffer<8> = LineBuffer::new();
+        assert_eq!(
+            feed(&mut buffer, b"\xFF\r"),
+            vec![Err(ShellError::InvalidUtf8)]
+        );
+    }
+
+    // ==================== Reply Tests ====================
+
+    #[test]
+    fn test_reply_collects_text() {
+        let mut reply: Reply<16> = Reply::new();
+        write!(reply, "ok {}", 42).unwrap();
+        assert_eq!(reply.as_str(), "ok 42");
+        reply.clear();
+        assert_eq!(reply.as_str(), "");
+    }
+
+    #[test]
+    fn test_reply_truncates_on_char_boundary() {
+        let mut reply: Reply<4> = Reply::new();
+        assert!(reply.write_str("aéé").is_err());
+        assert_eq!(reply.as_str(), "aé");
+    }
+
+    // ==================== Shell Tests ====================
+
+    #[test]
+    fn test_shell_rate_updates_settings() {
+        let mut shell = Shell::new(Settings::default());
+        let (action, out) = run(&mut shell, "rate 250");
+        let config = BlinkConfig::new(250, 250).unwrap();
+        assert_eq!(action, ShellAction::Led(LedCommand::SetRate(config)));
+        assert_eq!(out, "ok\r\n");
+        assert_eq!(shell.settings().blink, config);
+        assert_eq!(shell.settings().pattern, None);
+    }
+
+    #[test]
+    fn test_shell_pattern_updates_settings() {
+        let mut shell = Shell::new(Settings::default());
+        let (action, _) = run(&mut shell, "pattern on 50ms, off 50ms");
+        let pattern = Pattern::on_off(50, 50);
+        assert_eq!(action, ShellAction::Led(LedCommand::SetPattern(pattern)));
+        assert_eq!(shell.settings().pattern, Some(pattern));
+    }
+
+    #[test]
+    fn test_shell_rate_clears_pattern() {
+        let mut shell = Shell::new(Settings::default());
+        run(&mut shell, "pattern on 50ms, off 50ms");
+        run(&mut shell, "rate 400");
+        assert_eq!(shell.settings().pattern, None);
+    }
+
+    #[test]
+    fn test_shell_on_off_resume() {
+        let mut shell = Shell::new(Settings::default());
+        assert_eq!(
+            run(&mut shell, "on").0,
+            ShellAction::Led(LedCommand::ForceOn)
+        );
+        assert_eq!(shell.forced(), Some(LedState::On));
+        assert_eq!(
+            run(&mut shell, "off").0,
+            ShellAction::Led(LedCommand::ForceOff)
+        );
+        assert_eq!(shell.forced(), Some(LedState::Off));
+        assert_eq!(
+            run(&mut shell, "resume").0,
+            ShellAction::Led(LedCommand::Resume)
+        );
+        assert_eq!(shell.forced(), None);
+    }
+
+    #[test]
+    fn test_shell_status_report() {
+        let mut shell = Shell::new(Settings::default());
+        run(&mut shell, "rate 100 900");
+        run(&mut shell, "off");
+        let (action, out) = run(&mut shell, "status");
+        assert_eq!(action, ShellAction::None);
+        assert_eq!(
+            out,
+            "rate 100 900\r\npattern blink\r\nmode off\r\npolarity active-high\r\n"
+        );
+    }
+
+    #[test]
+    fn test_shell_status_custom_pattern() {
+        let mut shell = Shell::new(Settings::default());
+        run(&mut shell, "pattern on 100ms, off 400ms");
+        let (_, out) = run(&mut shell, "status");
+        assert!(out.contains("pattern custom, 2 segments, 500 ms\r\n"));
+    }
+
+    #[test]
+    fn test_shell_save_returns_settings() {
+        let mut shell = Shell::new(Settings::default());
+        run(&mut shell, "rate 300");
+        let (action, out) = run(&mut shell, "save");
+        assert_eq!(action, ShellAction::Save(*shell.settings()));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_shell_reset() {
+        let mut shell = Shell::new(Settings::default());
+        assert_eq!(
+            run(&mut shell, "reset"),
+            (ShellAction::Reset, String::new())
+        );
+    }
+
+    #[test]
+    fn test_shell_help() {
+        let mut shell = Shell::new(Settings::default());
+        let (_, out) = run(&mut shell, "help");
+        assert!(out.starts_with("rate MS"));
+        assert!(out.contains("reset"));
+    }
+
+    #[test]
+    fn test_shell_error_reply() {
+        let mut shell = Shell::new(Settings::default());
+        let (action, out) = run(&mut shell, "blink");
+        assert_eq!(action, ShellAction::None);
+        assert_eq!(out, "error: unknown command, try help\r\n");
+        let (_, out) = run(&mut shell, "rate 5");
+        assert_eq!(out, "error: invalid rate: TooShort\r\n");
+    }
+
+    #[test]
+    fn test_shell_failed_command_keeps_settings() {
+        let mut shell = Shell::new(Settings::default());
+        run(&mut shell, "rate 99999");
+        assert_eq!(*shell.settings(), Settings::default());
+    }
+
+    // ==================== Shell::request Tests ====================
+
+    #[test]
+    fn test_request_queries() {
+        let mut shell = Shell::new(Settings::default());
+        assert_eq!(
+            shell.request(&Request::Ping, LedState::Off),
+            (Response::Pong(PROTOCOL_VERSION), ShellAction::None)
+        );
+        assert_eq!(
+            shell.request(&Request::GetConfig, LedState::Off),
+            (Response::Config(BlinkConfig::default()), ShellAction::None)
+        );
+        assert_eq!(
+            shell.request(&Request::GetState, LedState::On),
+            (Response::State(LedState::On), ShellAction::None)
+        );
+        assert_eq!(
+            shell.request(&Request::GetSettings, LedState::On),
+            (Response::Settings(Settings::default()), ShellAction::None)
+        );
+    }
+
+    #[test]
+    fn test_request_set_config() {
+        let mut shell = Shell::new(Settings::default());
+        let config = BlinkConfig::new(100, 900).unwrap();
+        assert_eq!(
+            shell.request(&Request::SetConfig(config), LedState::Off),
+            (Response::Ack, ShellAction::Led(LedCommand::SetRate(config)))
+        );
+        assert_eq!(shell.settings().blink, config);
+    }
+
+    #[test]
+    fn test_request_upload_pattern() {
+        let mut shell = Shell::new(Settings::default());
+        let pattern = Pattern::sos();
+        assert_eq!(
+            shell.request(&Request::UploadPattern(pattern), LedState::Off),
+            (
+                Response::Ack,
+                ShellAction::Led(LedCommand::SetPattern(pattern))
+            )
+        );
+        let (_, out) = run(&mut shell, "status");
+        assert!(out.contains("pattern custom"));
+    }
+
+    // ==================== Console Tests ====================
+
+    fn type_bytes(
+        console: &mut Console<32>,
+        bytes: &[u8],
+        saved: bool,
+    ) -> (Vec<u8>, Vec<ShellAction>) {
+        let mut out = Vec::new();
+        let mut actions = Vec::new();
+        for &byte in bytes {
+            let (reply, action) = console.push(byte, LedState::On, |_| saved);
+            out.extend_from_slice(reply);
+            if action != ShellAction::None {
+                actions.push(action);
+            }
+        }
+        (out, actions)
+    }
+
+    fn request_frame(seq: u8, request: &Request) -> Vec<u8> {
+        let mut frame = [0u8; MAX_FRAME_LEN];
+        let len = encode_frame(seq, request, &mut frame).unwrap();
+        frame[..len].to_vec()
+    }
+
+    fn response_of(bytes: &[u8]) -> Packet<Response> {
+        decode_frame(&bytes[1..bytes.len() - 1]).unwrap()
+    }
+
+    #[test]
+    fn test_console_echoes_and_replies() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let (out, actions) = type_bytes(&mut console, b"on\r", true);
+        assert_eq!(out, b"on\r\r\nok\r\n");
+        assert_eq!(actions, vec![ShellAction::Led(LedCommand::ForceOn)]);
+    }
+
+    #[test]
+    fn test_console_does_not_echo_non_ascii() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let (out, _) = type_bytes(&mut console, b"\xFF\r", true);
+        assert_eq!(out, b"\r\r\nerror: invalid text\r\n");
+    }
+
+    #[test]
+    fn test_console_save_callback() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let mut stored = None;
+        for &byte in b"rate 300\rsave\r" {
+            console.push(byte, LedState::Off, |s| {
+                stored = Some(*s);
+                true
+            });
+        }
+        assert_eq!(stored.unwrap().blink, BlinkConfig::new(300, 300).unwrap());
+        let (out, actions) = type_bytes(&mut console, b"save\r", false);
+        assert!(out.ends_with(b"error: save failed\r\n"));
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_console_answers_frames() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let (out, actions) = type_bytes(&mut console, &request_frame(3, &Request::GetState), true);
+        assert_eq!(
+            response_of(&out),
+            Packet {
+                seq: 3,
+                message: Response::State(LedState::On)
+            }
+        );
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_console_frame_actions() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let config = BlinkConfig::new(100, 200).unwrap();
+        let (out, actions) = type_bytes(
+            &mut console,
+            &request_frame(1, &Request::SetConfig(config)),
+            true,
+        );
+        assert_eq!(response_of(&out).message, Response::Ack);
+        assert_eq!(actions, vec![ShellAction::Led(LedCommand::SetRate(config))]);
+        assert_eq!(console.shell().settings().blink, config);
+    }
+
+    #[test]
+    fn test_console_mixed_text_and_frames() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let mut bytes = b"sta".to_vec();
+        bytes.extend(request_frame(9, &Request::Ping));
+        bytes.extend_from_slice(b"tus\r");
+        let (out, _) = type_bytes(&mut console, &bytes, true);
+        let mut pong = [0u8; 16];
+        let len = encode_frame(9, &Response::Pong(PROTOCOL_VERSION), &mut pong).unwrap();
+        assert_eq!(&out[..3], b"sta");
+        assert_eq!(&out[3..3 + len], &pong[..len]);
+        assert!(out.ends_with(b"polarity active-high\r\n"));
+    }
+
+    #[test]
+    fn test_console_replays_resent_frame() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let frame = request_frame(5, &Request::SetConfig(BlinkConfig::default()));
+        let (first, actions) = type_bytes(&mut console, &frame, true);
+        assert_eq!(actions.len(), 1);
+        let (second, actions) = type_bytes(&mut console, &frame, true);
+        assert_eq!(first, second);
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_console_extra_delimiter_keeps_frame() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let mut bytes = vec![FRAME_DELIMITER];
+        bytes.extend(request_frame(2, &Request::GetState));
+        let (out, _) = type_bytes(&mut console, &bytes, true);
+        assert_eq!(response_of(&out).seq, 2);
+        let (out, _) = type_bytes(&mut console, b"on\r", true);
+        assert_eq!(out, b"on\r\r\nok\r\n");
+    }
+
+    #[test]
+    fn test_console_resyncs_after_lost_delimiter() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let lost = request_frame(1, &Request::Ping);
+        let mut bytes = lost[1..].to_vec();
+        bytes.extend(request_frame(2, &Request::GetState));
+        let (out, _) = type_bytes(&mut console, &bytes, true);
+        let start = out.iter().position(|&b| b == FRAME_DELIMITER).unwrap();
+        assert_eq!(response_of(&out[start..]).seq, 2);
+        let (out, _) = type_bytes(&mut console, b"\ron\r", true);
+        assert!(out.ends_with(b"on\r\r\nok\r\n"));
+    }
+
+    #[test]
+    fn test_console_overlong_frame_returns_to_text() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        let mut bytes = vec![FRAME_DELIMITER];
+        bytes.extend(core::iter::repeat_n(0x55, MAX_BODY_LEN + 1));
+        let (out, _) = type_bytes(&mut console, &bytes, true);
+        assert!(out.is_empty());
+        let (out, _) = type_bytes(&mut console, b"\ron\r", true);
+        assert!(out.ends_with(b"on\r\r\nok\r\n"));
+    }
+
+    #[test]
+    fn test_console_reset_drops_partial_line() {
+        let mut console: Console<32> = Console::new(Settings::default());
+        type_bytes(&mut console, b"blah", true);
+        console.reset();
+        let (out, _) = type_bytes(&mut console, b"on\r", true);
+        assert!(out.ends_with(b"ok\r\n"));
+    }
+}
@@ -0,0 +1,373 @@
+/*
+ * @file trace.rs
+ * @brief LED state transition tracing
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: trace.rs
+//!
+//! DESCRIPTION:
+//! LED State Transition Tracing for RP2350.
+//!
+//! BRIEF:
+//! Wraps LedController and records `(timestamp_ms, LedState)`
+//! transitions into a fixed-capacity buffer for later export.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::{LedController, LedState};
+
+/// Fixed-capacity transition buffer.
+///
+/// # Details
+/// Stores only state changes. Once full, further transitions
+/// are counted as dropped rather than overwriting history.
+///
+/// # Fields
+/// * `events` - Transition storage
+/// * `len` - Number of stored transitions
+/// * `dropped` - Transitions lost to a full buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct TraceBuffer<const N: usize> {
+    events: [(u64, LedState); N],
+    len: usize,
+    dropped: usize,
+}
+
+impl<const N: usize> Default for TraceBuffer<N> {
+    /// Returns an empty buffer.
+    ///
+    /// # Returns
+    /// * `Self` - Empty TraceBuffer
+    #[allow(dead_code)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TraceBuffer<N> {
+    /// Creates an empty buffer.
+    ///
+    /// # Returns
+    /// * `Self` - Empty TraceBuffer
+    #[allow(dead_code)]
+    pub const fn new() -> Self {
+        Self {
+            events: [(0, LedState::Off); N],
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Records a state at a timestamp.
+    ///
+    /// # Details
+    /// Ignored if the state equals the last recorded state.
+    ///
+    /// # Arguments
+    /// * `at_ms` - Timestamp in milliseconds
+    /// * `state` - LED state
+    ///
+    /// # Returns
+    /// * `bool` - true if a transition was stored
+    #[allow(dead_code)]
+    pub fn record(&mut self, at_ms: u64, state: LedState) -> bool {
+        if self.last().map(|(_, s)| s) == Some(state) {
+            return false;
+        }
+        if self.len == N {
+            self.dropped += 1;
+            return false;
+        }
+        self.events[self.len] = (at_ms, state);
+        self.len += 1;
+        true
+    }
+
+    /// Returns the recorded transitions.
+    ///
+    /// # Returns
+    /// * `&[(u64, LedState)]` - `(timestamp_ms, state)` pairs in order
+    #[allow(dead_code)]
+    pub fn events(&self) -> &[(u64, LedState)] {
+        &self.events[..self.len]
+    }
+
+    /// Returns the most recent transition.
+    ///
+    /// # Returns
+    /// * `Option<(u64, LedState)>` - Last transition, if any
+    #[allow(dead_code)]
+    pub fn last(&self) -> Option<(u64, LedState)> {
+        self.events().last().copied()
+    }
+
+    /// Returns the number of transitions lost to a full buffer.
+    ///
+    /// # Returns
+    /// * `usize` - Dropped transition count
+    #[allow(dead_code)]
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Empties the buffer.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.dropped = 0;
+    }
+}
+
+/// LedController wrapper that records state changes.
+///
+/// # Details
+/// `toggle()` stamps each new state with its scheduled time,
+/// the previous stamp plus the previous segment's delay, which
+/// gives the intended timeline. `toggle_at()` stamps an actual
+/// time instead.
+///
+/// # Fields
+/// * `controller` - Wrapped controller
+/// * `trace` - Recorded transitions
+/// * `next_ms` - Scheduled time of the next toggle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct TracedController<const N: usize> {
+    controller: LedController,
+    trace: TraceBuffer<N>,
+    next_ms: u64,
+}
+
+impl<const N: usize> TracedController<N> {
+    /// Wraps a controller with an empty trace starting at time zero.
+    ///
+    /// # Arguments
+    /// * `controller` - Controller to trace
+    ///
+    /// # Returns
+    /// * `Self` - New TracedController instance
+    #[allow(dead_code)]
+    pub fn new(controller: LedController) -> Self {
+        Self {
+            controller,
+            trace: TraceBuffer::new(),
+            next_ms: 0,
+        }
+    }
+
+    /// Toggles at the scheduled time and records the new state.
+    ///
+    /// # Returns
+    /// * `LedState` - New LED state
+    #[allow(dead_code)]
+    pub fn toggle(&mut self) -> LedState {
+        self.toggle_at(self.next_ms)
+    }
+
+    /// Toggles at the given time and records the new state.
+    ///
+    /// # Arguments
+    /// * `at_ms` - Actual toggle time in milliseconds
+    ///
+    /// # Returns
+    /// * `LedState` - New LED state
+    #[allow(dead_code)]
+    pub fn toggle_at(&mut self, at_ms: u64) -> LedState {
+        let state = self.controller.toggle();
+        self.trace.record(at_ms, state);
+        self.next_ms = at_ms.saturating_add(self.controller.delay_ms());
+        state
+    }
+
+    /// Toggles until the scheduled time reaches `end_ms`.
+    ///
+    /// # Details
+    /// Stops early if the controller finishes.
+    ///
+    /// # Arguments
+    /// * `end_ms` - End of the traced window in milliseconds
+    #[allow(dead_code)]
+    pub fn run_until(&mut self, end_ms: u64) {
+        while self.next_ms < end_ms && !self.controller.is_finished() {
+            self.toggle();
+        }
+    }
+
+    /// Returns the scheduled time of the next toggle.
+    ///
+    /// # Returns
+    /// * `u64` - Timestamp in milliseconds
+    #[allow(dead_code)]
+    pub fn next_ms(&self) -> u64 {
+        self.next_ms
+    }
+
+    /// Returns the recorded trace.
+    ///
+    /// # Returns
+    /// * `&TraceBuffer<N>` - Transition buffer
+    #[allow(dead_code)]
+    pub fn trace(&self) -> &TraceBuffer<N> {
+        &self.trace
+    }
+
+    /// Returns the wrapped controller.
+    ///
+    /// # Returns
+    /// * `&LedController` - Wrapped controller
+    #[allow(dead_code)]
+    pub fn controller(&self) -> &LedController {
+        &self.controller
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::{EndAction, Pattern};
+
+    // ==================== TraceBuffer Tests ====================
+
+    #[test]
+    fn test_buffer_starts_empty() {
+        let buf = TraceBuffer::<4>::new();
+        assert!(buf.events().is_empty());
+        assert_eq!(buf.last(), None);
+        assert_eq!(buf.dropped(), 0);
+    }
+
+    #[test]
+    fn test_buffer_default_equals_new() {
+        assert_eq!(TraceBuffer::<4>::default(), TraceBuffer::<4>::new());
+    }
+
+    #[test]
+    fn test_buffer_records_changes() {
+        let mut buf = TraceBuffer::<4>::new();
+        assert!(buf.record(0, LedState::On));
+        assert!(buf.record(10, LedState::Off));
+        assert_eq!(buf.events(), &[(0, LedState::On), (10, LedState::Off)]);
+    }
+
+    #[test]
+    fn test_buffer_skips_repeated_state() {
+        let mut buf = TraceBuffer::<4>::new();
+        buf.record(0, LedState::On);
+        assert!(!buf.record(5, LedState::On));
+        assert_eq!(buf.events().len(), 1);
+        assert_eq!(buf.dropped(), 0);
+    }
+
+    #[test]
+    fn test_buffer_counts_dropped() {
+        let mut buf = TraceBuffer::<2>::new();
+        buf.record(0, LedState::On);
+        buf.record(1, LedState::Off);
+        assert!(!buf.record(2, LedState::On));
+        assert_eq!(buf.events().len(), 2);
+        assert_eq!(buf.dropped(), 1);
+    }
+
+    #[test]
+    fn test_buffer_clear() {
+        let mut buf = TraceBuffer::<1>::new();
+        buf.record(0, LedState::On);
+        buf.record(1, LedState::Off);
+        buf.clear();
+        assert!(buf.events().is_empty());
+        assert_eq!(buf.dropped(), 0);
+    }
+
+    // ==================== TracedController Tests ====================
+
+    #[test]
+    fn test_traced_default_blink() {
+        let mut traced = TracedController::<8>::new(LedController::new());
+        for _ in 0..3 {
+            traced.toggle();
+        }
+        assert_eq!(
+            traced.trace().events(),
+            &[
+                (0, LedState::On),
+                (500, LedState::Off),
+                (1000, LedState::On)
+            ]
+        );
+        assert_eq!(traced.next_ms(), 1500);
+    }
+
+    #[test]
+    fn test_traced_toggle_at_uses_actual_time() {
+        let mut traced = TracedController::<8>::new(LedController::new());
+        traced.toggle_at(3);
+        traced.toggle_at(507);
+        assert_eq!(
+            traced.trace().events(),
+            &[(3, LedState::On), (507, LedState::Off)]
+        );
+        assert_eq!(traced.next_ms(), 1007);
+    }
+
+    #[test]
+    fn test_traced_run_until() {
+        let mut traced =
+            TracedController::<16>::new(LedController::with_pattern(Pattern::heartbeat()));
+        traced.run_until(1000);
+        assert_eq!(
+            traced.trace().events(),
+            &[
+                (0, LedState::On),
+                (100, LedState::Off),
+                (200, LedState::On),
+                (300, LedState::Off)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traced_run_until_stops_when_finished() {
+        let pattern = Pattern::heartbeat().with_end_action(EndAction::Stop);
+        let mut traced = TracedController::<16>::new(LedController::with_pattern(pattern));
+        traced.run_until(u64::MAX);
+        assert!(traced.controller().is_finished());
+        assert_eq!(traced.trace().events().len(), 4);
+    }
+
+    #[test]
+    fn test_traced_skips_hold_repeats() {
+        let pattern = Pattern::on_off(10, 10).with_end_action(EndAction::Hold);
+        let mut traced = TracedController::<16>::new(LedController::with_pattern(pattern));
+        for _ in 0..5 {
+            traced.toggle();
+        }
+        assert_eq!(traced.trace().events().len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+/*
+ * @file vcd.rs
+ * @brief Value Change Dump export for LED traces
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: vcd.rs
+//!
+//! DESCRIPTION:
+//! Value Change Dump Export for LED Traces.
+//!
+//! BRIEF:
+//! Writes `(timestamp_ms, LedState)` transitions as an IEEE 1364
+//! Value Change Dump for viewing in GTKWave alongside
+//! logic-analyzer captures.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use crate::led::{LedState, led_state_to_level};
+use core::fmt::{self, Write};
+
+/// VCD identifier code of the LED signal.
+///
+/// # Value
+/// '!'
+#[allow(dead_code)]
+pub const SIGNAL_ID: char = '!';
+
+/// Writes a trace as a Value Change Dump.
+///
+/// # Details
+/// Uses a 1 ms timescale and a single 1-bit wire in module
+/// `led`. The signal is `x` until the first transition, and a
+/// final timestamp at `end_ms` marks the end of the capture.
+///
+/// # Arguments
+/// * `out` - Output sink
+/// * `signal` - Signal name shown in the viewer
+/// * `events` - `(timestamp_ms, state)` pairs in time order
+/// * `end_ms` - End of the capture in milliseconds
+///
+/// # Returns
+/// * `fmt::Result` - Ok or sink error
+#[allow(dead_code)]
+pub fn write_vcd<W: Write>(
+    out: &mut W,
+    signal: &str,
+    events: &[(u64, LedState)],
+    end_ms: u64,
+) -> fmt::Result {
+    out.write_str("$version day001-blink-led $end\n")?;
+    out.write_str("$timescale 1 ms $end\n")?;
+    out.write_str("$scope module led $end\n")?;
+    writeln!(out, "$var wire 1 {SIGNAL_ID} {signal} $end")?;
+    out.write_str("$upscope $end\n")?;
+    out.write_str("$enddefinitions $end\n")?;
+    writeln!(out, "$dumpvars\nx{SIGNAL_ID}\n$end")?;
+    let mut last_ms = None;
+    for (at_ms, state) in events {
+        if last_ms != Some(*at_ms) {
+            writeln!(out, "#{at_ms}")?;
+            last_ms = Some(*at_ms);
+        }
+        let bit = if led_state_to_level(*state) { '1' } else { '0' };
+        writeln!(out, "{bit}{SIGNAL_ID}")?;
+    }
+    if last_ms.is_none_or(|t| end_ms > t) {
+        writeln!(out, "#{end_ms}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "$version day001-blink-led $end\n\
+        $timescale 1 ms $end\n\
+        $scope module led $end\n\
+        $var wire 1 ! led $end\n\
+        $upscope $end\n\
+        $enddefinitions $end\n\
+        $dumpvars\nx!\n$end\n";
+
+    fn vcd(events: &[(u64, LedState)], end_ms: u64) -> String {
+        let mut s = String::new();
+        write_vcd(&mut s, "led", events, end_ms).unwrap();
+        s
+    }
+
+    // ==================== write_vcd() Tests ====================
+
+    #[test]
+    fn test_vcd_header() {
+        assert!(vcd(&[], 0).starts_with(HEADER));
+    }
+
+    #[test]
+    fn test_vcd_empty_trace_has_end_time() {
+        assert_eq!(vcd(&[], 100), format!("{HEADER}#100\n"));
+    }
+
+    #[test]
+    fn test_vcd_transitions() {
+        let events = [
+            (0, LedState::On),
+            (500, LedState::Off),
+            (1000, LedState::On),
+        ];
+        assert_eq!(
+            vcd(&events, 1500),
+            format!("{HEADER}#0\n1!\n#500\n0!\n#1000\n1!\n#1500\n")
+        );
+    }
+
+    #[test]
+    fn test_vcd_end_not_repeated() {
+        let events = [(0, LedState::On), (500, LedState::Off)];
+        assert_eq!(vcd(&events, 500), format!("{HEADER}#0\n1!\n#500\n0!\n"));
+    }
+
+    #[test]
+    fn test_vcd_same_timestamp_grouped() {
+        let events = [(10, LedState::On), (10, LedState::Off)];
+        assert_eq!(vcd(&events, 0), format!("{HEADER}#10\n1!\n0!\n"));
+    }
+
+    #[test]
+    fn test_vcd_signal_name() {
+        let mut s = String::new();
+        write_vcd(&mut s, "pin16", &[], 0).unwrap();
+        assert!(s.contains("$var wire 1 ! pin16 $end\n"));
+    }
+}
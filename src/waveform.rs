@@ -0,0 +1,204 @@
+/*
+ * @file waveform.rs
+ * @brief ASCII waveform rendering for LED timelines
+ * @author Kevin Thomas
+ * @date 2025
+ *
+ * MIT License
+ *
+ * Copyright (c) 2025 Kevin Thomas
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+//! FILE: waveform.rs
+//!
+//! DESCRIPTION:
+//! ASCII Waveform Rendering for LED Timelines.
+//!
+//! BRIEF:
+//! Renders recorded `(timestamp_ms, level)` transitions as rows
+//! of ASCII samples. Writes to any `core::fmt::Write` sink.
+//!
+//! AUTHOR: Kevin Thomas
+//! CREATION DATE: October 17, 2026
+//! UPDATE DATE: October 17, 2026
+
+use core::fmt::{self, Write};
+
+/// Character drawn while the LED is high.
+///
+/// # Value
+/// '#'
+#[allow(dead_code)]
+pub const HIGH_CHAR: char = '#';
+
+/// Character drawn while the LED is low.
+///
+/// # Value
+/// '_'
+#[allow(dead_code)]
+pub const LOW_CHAR: char = '_';
+
+/// Character drawn before the first transition.
+///
+/// # Value
+/// ' '
+#[allow(dead_code)]
+pub const UNKNOWN_CHAR: char = ' ';
+
+/// Returns the level at a point in a timeline.
+///
+/// # Arguments
+/// * `transitions` - `(timestamp_ms, level)` pairs in time order
+/// * `at_ms` - Timestamp in milliseconds
+///
+/// # Returns
+/// * `Option<bool>` - Level at that time, or None before the first transition
+#[allow(dead_code)]
+pub fn level_at(transitions: &[(u64, bool)], at_ms: u64) -> Option<bool> {
+    transitions
+        .iter()
+        .take_while(|(t, _)| *t <= at_ms)
+        .last()
+        .map(|(_, level)| *level)
+}
+
+/// Renders a timeline as an ASCII waveform.
+///
+/// # Details
+/// Each character covers `resolution_ms` and each row holds
+/// `width` characters, prefixed with the row start time.
+/// Zero resolution or width is treated as one.
+///
+/// # Arguments
+/// * `out` - Output sink
+/// * `transitions` - `(timestamp_ms, level)` pairs in time order
+/// * `end_ms` - End of the rendered window in milliseconds
+/// * `resolution_ms` - Milliseconds per character
+/// * `width` - Characters per row
+///
+/// # Returns
+/// * `fmt::Result` - Ok or sink error
+#[allow(dead_code)]
+pub fn render<W: Write>(
+    out: &mut W,
+    transitions: &[(u64, bool)],
+    end_ms: u64,
+    resolution_ms: u64,
+    width: usize,
+) -> fmt::Result {
+    let resolution_ms = resolution_ms.max(1);
+    let width = width.max(1) as u64;
+    let mut row_start = 0;
+    while row_start < end_ms {
+        write!(out, "{:>8} ms |", row_start)?;
+        let mut t = row_start;
+        while t < end_ms && t < row_start + width * resolution_ms {
+            out.write_char(match level_at(transitions, t) {
+                Some(true) => HIGH_CHAR,
+                Some(false) => LOW_CHAR,
+                None => UNKNOWN_CHAR,
+            })?;
+            t += resolution_ms;
+        }
+        out.write_char('\n')?;
+        row_start += width * resolution_ms;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_to_string(
+        transitions: &[(u64, bool)],
+        end_ms: u64,
+        resolution_ms: u64,
+        width: usize,
+    ) -> String {
+        let mut s = String::new();
+        render(&mut s, transitions, end_ms, resolution_ms, width).unwrap();
+        s
+    }
+
+    // ==================== level_at() Tests ====================
+
+    #[test]
+    fn test_level_at_empty() {
+        assert_eq!(level_at(&[], 0), None);
+    }
+
+    #[test]
+    fn test_level_at_before_first() {
+        assert_eq!(level_at(&[(10, true)], 9), None);
+    }
+
+    #[test]
+    fn test_level_at_on_boundary() {
+        let t = [(0, true), (10, false)];
+        assert_eq!(level_at(&t, 9), Some(true));
+        assert_eq!(level_at(&t, 10), Some(false));
+    }
+
+    // ==================== render() Tests ====================
+
+    #[test]
+    fn test_render_single_row() {
+        let s = render_to_string(&[(0, true), (500, false)], 1000, 100, 10);
+        assert_eq!(s, "       0 ms |#####_____\n");
+    }
+
+    #[test]
+    fn test_render_wraps_rows() {
+        let s = render_to_string(&[(0, true), (200, false)], 600, 100, 3);
+        assert_eq!(s, "       0 ms |##_\n     300 ms |___\n");
+    }
+
+    #[test]
+    fn test_render_partial_last_row() {
+        let s = render_to_string(&[(0, false)], 250, 100, 2);
+        assert_eq!(s, "       0 ms |__\n     200 ms |_\n");
+    }
+
+    #[test]
+    fn test_render_unknown_before_first() {
+        let s = render_to_string(&[(200, true)], 400, 100, 10);
+        assert_eq!(s, "       0 ms |  ##\n");
+    }
+
+    #[test]
+    fn test_render_empty_window() {
+        assert_eq!(render_to_string(&[(0, true)], 0, 100, 10), "");
+    }
+
+    #[test]
+    fn test_render_zero_resolution_and_width() {
+        let s = render_to_string(&[(0, true), (1, false)], 2, 0, 0);
+        assert_eq!(s, "       0 ms |#\n       1 ms |_\n");
+    }
+
+    #[test]
+    fn test_render_default_blink() {
+        let t = [(0, true), (500, false), (1000, true), (1500, false)];
+        let s = render_to_string(&t, 2000, 250, 8);
+        assert_eq!(s, "       0 ms |##__##__\n");
+    }
+}